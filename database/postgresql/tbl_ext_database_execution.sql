@@ -0,0 +1,13 @@
+CREATE TABLE tbl_ext_database_execution(
+	id BIGINT PRIMARY KEY
+	, ext_database_id BIGINT NOT NULL
+	, source VARCHAR (20) NOT NULL
+	, query TEXT NOT NULL
+	, statement_kind VARCHAR (10) NOT NULL
+	, duration_ms BIGINT NOT NULL
+	, affected_row BIGINT
+	, error_message TEXT
+	, created_by BIGINT NOT NULL
+	, dt_created TIMESTAMP NOT NULL
+);
+CREATE INDEX idx_ext_database_execution_database ON tbl_ext_database_execution(ext_database_id, dt_created);
\ No newline at end of file
@@ -0,0 +1,18 @@
+CREATE TABLE tbl_ext_database_mask(
+	id BIGINT PRIMARY KEY
+	, ext_database_id BIGINT NOT NULL
+	, table_name VARCHAR (255)
+	, column_name VARCHAR (255)
+	, column_pattern VARCHAR (255)
+	, strategy VARCHAR (10) NOT NULL
+	, visible_prefix SMALLINT DEFAULT(0)
+	, visible_suffix SMALLINT DEFAULT(0)
+	, mask_format VARCHAR (255)
+	, mt_role_id SMALLINT
+	, is_del SMALLINT DEFAULT(0)
+	, created_by BIGINT NOT NULL
+	, dt_created TIMESTAMP NOT NULL
+	, updated_by BIGINT
+	, dt_updated TIMESTAMP
+	, version SMALLINT DEFAULT(0)
+);
\ No newline at end of file
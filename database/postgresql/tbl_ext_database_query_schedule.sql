@@ -0,0 +1,19 @@
+CREATE TABLE tbl_ext_database_query_schedule(
+	id BIGINT PRIMARY KEY
+	, ext_database_query_id BIGINT NOT NULL
+	, cron_expression VARCHAR (100) NOT NULL
+	, parameter TEXT
+	, file_format VARCHAR (4) NOT NULL
+	, ext_server_id BIGINT
+	, directory VARCHAR (500)
+	, email VARCHAR (1000)
+	, is_active SMALLINT DEFAULT(1)
+	, dt_next_run TIMESTAMP
+	, dt_last_run TIMESTAMP
+	, is_del SMALLINT DEFAULT(0)
+	, created_by BIGINT NOT NULL
+	, dt_created TIMESTAMP NOT NULL
+	, updated_by BIGINT
+	, dt_updated TIMESTAMP
+	, version SMALLINT DEFAULT(0)
+);
\ No newline at end of file
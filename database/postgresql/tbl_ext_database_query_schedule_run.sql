@@ -0,0 +1,18 @@
+CREATE TABLE tbl_ext_database_query_schedule_run(
+	id BIGINT PRIMARY KEY
+	, ext_database_query_schedule_id BIGINT NOT NULL
+	, is_manual SMALLINT DEFAULT(0)
+	, status VARCHAR (10) NOT NULL
+	, dt_started TIMESTAMP NOT NULL
+	, dt_finished TIMESTAMP
+	, duration BIGINT
+	, row_count BIGINT
+	, file_name VARCHAR (200)
+	, file_content BYTEA
+	, message TEXT
+	, created_by BIGINT NOT NULL
+	, dt_created TIMESTAMP NOT NULL
+	, updated_by BIGINT
+	, dt_updated TIMESTAMP
+	, version SMALLINT DEFAULT(0)
+);
\ No newline at end of file
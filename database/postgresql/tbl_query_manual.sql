@@ -2,6 +2,9 @@ CREATE TABLE tbl_query_manual(
 	id BIGINT PRIMARY KEY
 	, ext_database_id SMALLINT NOT NULL
 	, query TEXT NOT NULL
+	, title VARCHAR (200)
+	, tags VARCHAR (500)
+	, is_favourite SMALLINT DEFAULT(0)
 	, created_by BIGINT NOT NULL
 	, dt_created TIMESTAMP NOT NULL
 	, updated_by BIGINT
@@ -0,0 +1,12 @@
+CREATE TABLE tbl_query_manual_share(
+	id BIGINT PRIMARY KEY
+	, query_manual_id BIGINT NOT NULL
+	, user_id BIGINT
+	, mt_role_id SMALLINT
+	, is_del SMALLINT DEFAULT(0)
+	, created_by BIGINT NOT NULL
+	, dt_created TIMESTAMP NOT NULL
+	, updated_by BIGINT
+	, dt_updated TIMESTAMP
+	, version SMALLINT DEFAULT(0)
+);
\ No newline at end of file
@@ -0,0 +1,15 @@
+CREATE TABLE tbl_query_manual_snapshot(
+	id BIGINT PRIMARY KEY
+	, query_manual_id BIGINT NOT NULL
+	, header TEXT NOT NULL
+	, column_info TEXT NOT NULL
+	, row_data TEXT NOT NULL
+	, row_count BIGINT NOT NULL
+	, byte_size BIGINT NOT NULL
+	, dt_expired TIMESTAMP NOT NULL
+	, created_by BIGINT NOT NULL
+	, dt_created TIMESTAMP NOT NULL
+	, updated_by BIGINT
+	, dt_updated TIMESTAMP
+	, version SMALLINT DEFAULT(0)
+);
\ No newline at end of file
@@ -7,26 +7,53 @@ pub struct JwtAuth {
     pub claims: Claims,
 }
 
+const NOTIFY_PATH_SUFFIX: &str = "/database-notify.json";
+
 impl<'a> FromRequest<'a> for JwtAuth {
     fn from_request(
         req: &'a Request,
         _body: &mut RequestBody,
     ) -> impl Future<Output = Result<Self, poem::Error>> + Send {
         Box::pin(async move {
-            let auth_header = req
+            // Browser tidak bisa mengirim header pada WebSocket, token dibaca dari query string,
+            // hanya untuk route notify supaya token tidak tersebar lewat URL route lain
+            let query_token = req
                 .headers()
-                .get("authorization")
-                .ok_or_else(|| {
-                    common::error_message(StatusCode::UNAUTHORIZED, "Missing authorization header")
-                })?
-                .to_str()
-                .map_err(|_| {
-                    common::error_message(StatusCode::UNAUTHORIZED, "Invalid authorization header")
-                })?;
-
-            let token = auth_header.strip_prefix("Bearer ").ok_or_else(|| {
-                common::error_message(StatusCode::UNAUTHORIZED, "Missing Bearer prefix")
-            })?;
+                .get("upgrade")
+                .and_then(|v| v.to_str().ok())
+                .filter(|v| v.eq_ignore_ascii_case("websocket"))
+                .filter(|_| req.original_uri().path().ends_with(NOTIFY_PATH_SUFFIX))
+                .and_then(|_| {
+                    req.uri().query().and_then(|query| {
+                        query
+                            .split('&')
+                            .find_map(|pair| pair.strip_prefix("accessToken="))
+                    })
+                });
+
+            let token = match (req.headers().get("authorization"), query_token) {
+                (None, Some(token)) => token,
+                (auth_header, _) => {
+                    let auth_header = auth_header
+                        .ok_or_else(|| {
+                            common::error_message(
+                                StatusCode::UNAUTHORIZED,
+                                "Missing authorization header",
+                            )
+                        })?
+                        .to_str()
+                        .map_err(|_| {
+                            common::error_message(
+                                StatusCode::UNAUTHORIZED,
+                                "Invalid authorization header",
+                            )
+                        })?;
+
+                    auth_header.strip_prefix("Bearer ").ok_or_else(|| {
+                        common::error_message(StatusCode::UNAUTHORIZED, "Missing Bearer prefix")
+                    })?
+                }
+            };
 
             let secret = env::var(if "/refresh-token.json" == req.original_uri().path() {
                 "JWT_REFRESH_TOKEN_SECRET"
@@ -52,3 +79,21 @@ impl<'a> FromRequest<'a> for JwtAuth {
         })
     }
 }
+
+// Role 0 adalah System Admin pada tbl_mt_role
+const ADMIN_ROLE_ID: i16 = 0;
+
+pub fn ensure_admin(claims: &Claims) -> poem::Result<()> {
+    if claims
+        .role
+        .as_deref()
+        .is_some_and(|roles| roles.contains(&ADMIN_ROLE_ID))
+    {
+        Ok(())
+    } else {
+        Err(common::error_message(
+            StatusCode::FORBIDDEN,
+            "information.forbidden",
+        ))
+    }
+}
@@ -1,6 +1,25 @@
 pub mod api;
 pub mod api_req;
+pub mod connection_test;
+pub mod credential;
 pub mod database;
+pub mod database_chart;
+pub mod database_compare;
+pub mod database_diff;
+pub mod database_dump;
+pub mod database_execution;
+pub mod database_explain;
+pub mod database_format;
+pub mod database_import;
+pub mod database_keyset;
+pub mod database_mask;
+pub mod database_metadata;
+pub mod database_notify;
+pub mod database_object;
 pub mod database_query;
+pub mod database_query_history;
+pub mod database_row;
+pub mod database_schedule;
+pub mod database_snapshot;
 pub mod server;
 pub mod server_command;
@@ -0,0 +1,456 @@
+use std::io::Write;
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+use diesel::prelude::*;
+use poem::IntoResponse;
+use poem::web::Query;
+use poem::{handler, http::StatusCode, web::Json};
+use sqlx::mysql::MySqlPoolOptions;
+use sqlx::postgres::PgPoolOptions;
+use ssh2::Session;
+use tempfile::NamedTempFile;
+use validator::Validate;
+
+use crate::db::DbPool;
+use crate::facades::external::database_query::build_database_url;
+use crate::facades::external::server_command::start_ssh_tunnel;
+use crate::models::common::DataResponse;
+use crate::models::external::connection_test::{
+    ConnectionStep, ConnectionTestParam, ConnectionTestResult,
+};
+use crate::models::external::database::EntryExternalDatabase;
+use crate::models::external::server::EntryExternalServer;
+use crate::schema::{tbl_ext_database, tbl_ext_server, tbl_mt_database_type};
+use crate::utils::common::{self, validate_id, validation_error_response};
+use crate::utils::crypto::reveal_credential;
+
+const TEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct SshTarget {
+    ip: String,
+    port: i16,
+    username: String,
+    password: Option<String>,
+    private_key: Option<String>,
+}
+
+struct Diagnostics {
+    steps: Vec<ConnectionStep>,
+    started: Instant,
+}
+
+impl Diagnostics {
+    fn new() -> Self {
+        Diagnostics {
+            steps: Vec::new(),
+            started: Instant::now(),
+        }
+    }
+
+    fn record<T>(&mut self, name: &str, started: Instant, result: Result<T, String>) -> Option<T> {
+        let (status, message, value) = match result {
+            Ok(value) => ("success", None, Some(value)),
+            Err(message) => ("failed", Some(message), None),
+        };
+        self.steps.push(ConnectionStep {
+            name: name.to_string(),
+            status: status.to_string(),
+            duration: started.elapsed().as_millis(),
+            message,
+        });
+        value
+    }
+
+    fn run<T>(&mut self, name: &str, step: impl FnOnce() -> Result<T, String>) -> Option<T> {
+        let started = Instant::now();
+        let result = step();
+        self.record(name, started, result)
+    }
+
+    async fn run_async<T>(
+        &mut self,
+        name: &str,
+        step: impl Future<Output = Result<T, String>>,
+    ) -> Option<T> {
+        let started = Instant::now();
+        let result = step.await;
+        self.record(name, started, result)
+    }
+
+    // Langkah setelah kegagalan tetap dilaporkan sebagai skipped agar urutannya terlihat utuh
+    fn finish(
+        mut self,
+        step_names: &[&str],
+        server_version: Option<String>,
+    ) -> ConnectionTestResult {
+        for name in step_names {
+            if !self.steps.iter().any(|step| step.name == *name) {
+                self.steps.push(ConnectionStep {
+                    name: name.to_string(),
+                    status: String::from("skipped"),
+                    duration: 0,
+                    message: None,
+                });
+            }
+        }
+
+        let is_success = self.steps.iter().all(|step| step.status == "success");
+        ConnectionTestResult {
+            is_success: if is_success { 1 } else { 0 },
+            server_version,
+            duration: self.started.elapsed().as_millis(),
+            steps: self.steps,
+        }
+    }
+}
+
+fn resolve_address(host: &str, port: i16) -> Result<SocketAddr, String> {
+    (host, port as u16)
+        .to_socket_addrs()
+        .map_err(|e| e.to_string())?
+        .next()
+        .ok_or_else(|| String::from("connection.addressNotFound"))
+}
+
+fn connect_tcp(addr: SocketAddr) -> Result<TcpStream, String> {
+    TcpStream::connect_timeout(&addr, TEST_TIMEOUT).map_err(|e| e.to_string())
+}
+
+fn authenticate_ssh(session: &Session, target: &SshTarget) -> Result<(), String> {
+    if let Some(password) = &target.password {
+        session
+            .userauth_password(&target.username, password)
+            .map_err(|e| e.to_string())?;
+    } else if let Some(key) = &target.private_key {
+        let mut temp_key = NamedTempFile::new().map_err(|e| e.to_string())?;
+        temp_key
+            .write_all(key.as_bytes())
+            .map_err(|e| e.to_string())?;
+        session
+            .userauth_pubkey_file(&target.username, None, temp_key.path(), None)
+            .map_err(|e| e.to_string())?;
+    } else {
+        return Err(String::from("ssh.missingCredentials"));
+    }
+
+    if session.authenticated() {
+        Ok(())
+    } else {
+        Err(String::from("ssh.authFailed"))
+    }
+}
+
+fn run_ssh_steps(diag: &mut Diagnostics, target: &SshTarget, auth_step: &str) -> Option<Session> {
+    let addr = diag.run("dns", || resolve_address(&target.ip, target.port))?;
+    let tcp = diag.run("tcp", || connect_tcp(addr))?;
+    let session = diag.run("sshHandshake", || {
+        let mut session = Session::new().map_err(|e| e.to_string())?;
+        session.set_timeout(TEST_TIMEOUT.as_millis() as u32);
+        session.set_tcp_stream(tcp);
+        session.handshake().map_err(|e| e.to_string())?;
+        Ok(session)
+    })?;
+    diag.run(auth_step, || authenticate_ssh(&session, target))?;
+    Some(session)
+}
+
+async fn run_database_version(
+    diag: &mut Diagnostics,
+    mt_database_type_id: i16,
+    url: &str,
+) -> Option<String> {
+    match mt_database_type_id {
+        1 => {
+            let pool = diag
+                .run_async("authentication", async {
+                    PgPoolOptions::new()
+                        .max_connections(1)
+                        .acquire_timeout(TEST_TIMEOUT)
+                        .connect(url)
+                        .await
+                        .map_err(|e| e.to_string())
+                })
+                .await?;
+            let version = diag
+                .run_async("serverVersion", async {
+                    sqlx::query_scalar::<_, String>("SELECT version()")
+                        .fetch_one(&pool)
+                        .await
+                        .map_err(|e| e.to_string())
+                })
+                .await;
+            pool.close().await;
+            version
+        }
+        _ => {
+            let pool = diag
+                .run_async("authentication", async {
+                    MySqlPoolOptions::new()
+                        .max_connections(1)
+                        .acquire_timeout(TEST_TIMEOUT)
+                        .connect(url)
+                        .await
+                        .map_err(|e| e.to_string())
+                })
+                .await?;
+            let version = diag
+                .run_async("serverVersion", async {
+                    sqlx::query_scalar::<_, String>("SELECT VERSION()")
+                        .fetch_one(&pool)
+                        .await
+                        .map_err(|e| e.to_string())
+                })
+                .await;
+            pool.close().await;
+            version
+        }
+    }
+}
+
+async fn run_database_steps(
+    diag: &mut Diagnostics,
+    conn: &mut PgConnection,
+    entry: &EntryExternalDatabase,
+    password: &str,
+    url_template: &str,
+    ssh_target: Option<&SshTarget>,
+) -> Option<String> {
+    let (host, port, tunnel) = match (entry.ext_server_id, ssh_target) {
+        (Some(ext_server_id), Some(target)) => {
+            run_ssh_steps(diag, target, "sshAuthentication")?;
+            let (tunnel, local_port) = diag.run("tunnel", || {
+                start_ssh_tunnel(conn, ext_server_id, &entry.ip, entry.port)
+                    .map_err(|_| String::from("ssh.tunnelFailed"))
+            })?;
+            (String::from("localhost"), local_port, Some(tunnel))
+        }
+        _ => {
+            let addr = diag.run("dns", || resolve_address(&entry.ip, entry.port))?;
+            diag.run("tcp", || connect_tcp(addr).map(|_| ()))?;
+            (entry.ip.clone(), entry.port as u16, None)
+        }
+    };
+
+    let url = build_database_url(
+        url_template,
+        &entry.username,
+        password,
+        &host,
+        port,
+        &entry.db_name,
+    );
+    let server_version = run_database_version(diag, entry.mt_database_type_id, &url).await;
+
+    if let Some(mut tunnel) = tunnel {
+        let _ = tunnel.kill().ok();
+    };
+
+    server_version
+}
+
+fn saved_ssh_target(conn: &mut PgConnection, ext_server_id: i64) -> poem::Result<SshTarget> {
+    let (ip, port, username, password, private_key) = tbl_ext_server::table
+        .filter(tbl_ext_server::id.eq(ext_server_id))
+        .filter(tbl_ext_server::is_del.eq(0))
+        .select((
+            tbl_ext_server::ip,
+            tbl_ext_server::port,
+            tbl_ext_server::username,
+            tbl_ext_server::password,
+            tbl_ext_server::private_key,
+        ))
+        .first::<(String, i16, String, Option<String>, Option<String>)>(conn)
+        .map_err(|_| common::error_message(StatusCode::NOT_FOUND, "information.notFound"))?;
+
+    Ok(SshTarget {
+        ip,
+        port,
+        username,
+        password: password.as_deref().map(reveal_credential).transpose()?,
+        private_key: private_key.as_deref().map(reveal_credential).transpose()?,
+    })
+}
+
+#[handler]
+pub async fn database_test(
+    pool: poem::web::Data<&DbPool>,
+    _: crate::auth::middleware::JwtAuth,
+    Query(param): Query<ConnectionTestParam>,
+    Json(entry): Json<EntryExternalDatabase>,
+) -> poem::Result<impl IntoResponse> {
+    if let Err(e) = entry.validate() {
+        return Err(validation_error_response(e));
+    }
+    if !matches!(entry.mt_database_type_id, 1 | 2) {
+        return Err(common::error_message(
+            StatusCode::BAD_REQUEST,
+            "database.unsupportedType",
+        ));
+    }
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    // Password tersimpan hanya dipakai ulang untuk target yang sama persis,
+    // supaya tidak bisa dikirim ke host lain
+    let password = match (
+        entry
+            .password
+            .as_deref()
+            .filter(|password| !password.is_empty()),
+        param.id,
+    ) {
+        (Some(password), _) => password.to_string(),
+        (None, Some(ext_database_id)) => {
+            validate_id(ext_database_id)?;
+            let (ext_server_id, mt_database_type_id, ip, port, username, password) =
+                tbl_ext_database::table
+                    .filter(tbl_ext_database::id.eq(ext_database_id))
+                    .filter(tbl_ext_database::is_del.eq(0))
+                    .select((
+                        tbl_ext_database::ext_server_id,
+                        tbl_ext_database::mt_database_type_id,
+                        tbl_ext_database::ip,
+                        tbl_ext_database::port,
+                        tbl_ext_database::username,
+                        tbl_ext_database::password,
+                    ))
+                    .first::<(Option<i64>, i16, String, i16, String, String)>(conn)
+                    .map_err(|_| {
+                        common::error_message(StatusCode::NOT_FOUND, "information.notFound")
+                    })?;
+            if ext_server_id != entry.ext_server_id
+                || mt_database_type_id != entry.mt_database_type_id
+                || ip != entry.ip
+                || port != entry.port
+                || username != entry.username
+            {
+                return Err(common::error_message(
+                    StatusCode::BAD_REQUEST,
+                    "credential.passwordRequired",
+                ));
+            }
+            reveal_credential(&password)?
+        }
+        (None, None) => {
+            return Err(common::error_message(
+                StatusCode::BAD_REQUEST,
+                "credential.passwordRequired",
+            ));
+        }
+    };
+
+    let url_template = tbl_mt_database_type::table
+        .filter(tbl_mt_database_type::id.eq(entry.mt_database_type_id))
+        .select(tbl_mt_database_type::url)
+        .first::<String>(conn)
+        .map_err(|_| common::error_message(StatusCode::NOT_FOUND, "information.notFound"))?;
+    let ssh_target = entry
+        .ext_server_id
+        .map(|ext_server_id| saved_ssh_target(conn, ext_server_id))
+        .transpose()?;
+
+    let step_names: &[&str] = if ssh_target.is_some() {
+        &[
+            "dns",
+            "tcp",
+            "sshHandshake",
+            "sshAuthentication",
+            "tunnel",
+            "authentication",
+            "serverVersion",
+        ]
+    } else {
+        &["dns", "tcp", "authentication", "serverVersion"]
+    };
+
+    let mut diag = Diagnostics::new();
+    let server_version = run_database_steps(
+        &mut diag,
+        conn,
+        &entry,
+        &password,
+        &url_template,
+        ssh_target.as_ref(),
+    )
+    .await;
+
+    Ok(Json(DataResponse {
+        data: diag.finish(step_names, server_version),
+    }))
+}
+
+#[handler]
+pub fn server_test(
+    pool: poem::web::Data<&DbPool>,
+    _: crate::auth::middleware::JwtAuth,
+    Query(param): Query<ConnectionTestParam>,
+    Json(entry): Json<EntryExternalServer>,
+) -> poem::Result<impl IntoResponse> {
+    if let Err(e) = entry.validate() {
+        return Err(validation_error_response(e));
+    }
+
+    let password = entry.password.filter(|password| !password.is_empty());
+    let private_key = entry
+        .private_key
+        .filter(|private_key| !private_key.is_empty());
+
+    let (password, private_key) = match param.id {
+        Some(ext_server_id) if password.is_none() && private_key.is_none() => {
+            validate_id(ext_server_id)?;
+            let conn = &mut pool.get().map_err(|_| {
+                common::error_message(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "information.connectionFailed",
+                )
+            })?;
+            let saved = saved_ssh_target(conn, ext_server_id)?;
+            if saved.ip != entry.ip || saved.port != entry.port || saved.username != entry.username
+            {
+                return Err(common::error_message(
+                    StatusCode::BAD_REQUEST,
+                    "credential.passwordRequired",
+                ));
+            }
+            (saved.password, saved.private_key)
+        }
+        _ => (password, private_key),
+    };
+
+    let target = SshTarget {
+        ip: entry.ip,
+        port: entry.port,
+        username: entry.username,
+        password,
+        private_key,
+    };
+
+    let mut diag = Diagnostics::new();
+    let server_version = run_ssh_steps(&mut diag, &target, "authentication").and_then(|session| {
+        diag.run("serverVersion", || {
+            session
+                .banner()
+                .map(str::to_string)
+                .ok_or_else(|| String::from("ssh.bannerUnavailable"))
+        })
+    });
+
+    Ok(Json(DataResponse {
+        data: diag.finish(
+            &[
+                "dns",
+                "tcp",
+                "sshHandshake",
+                "authentication",
+                "serverVersion",
+            ],
+            server_version,
+        ),
+    }))
+}
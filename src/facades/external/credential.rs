@@ -0,0 +1,134 @@
+use diesel::prelude::*;
+use poem::IntoResponse;
+use poem::{handler, http::StatusCode, web::Json};
+use serde_json::json;
+
+use crate::db::DbPool;
+use crate::models::common::DataResponse;
+use crate::schema::{tbl_ext_database, tbl_ext_server};
+use crate::utils::common;
+use crate::utils::crypto::{decrypt_credential, encrypt_credential, is_current_credential};
+
+// Nilai yang belum memakai master key saat ini (termasuk plain text lama) dibuka lalu dienkripsi ulang
+fn reencrypt(stored: &str) -> Result<Option<String>, String> {
+    if stored.is_empty() || is_current_credential(stored) {
+        return Ok(None);
+    }
+    encrypt_credential(&decrypt_credential(stored)?).map(Some)
+}
+
+fn reencrypt_optional(stored: Option<&str>) -> Result<Option<String>, String> {
+    stored.map(reencrypt).transpose().map(Option::flatten)
+}
+
+enum RotateError {
+    Credential(String),
+    Database(diesel::result::Error),
+}
+
+impl From<diesel::result::Error> for RotateError {
+    fn from(e: diesel::result::Error) -> Self {
+        RotateError::Database(e)
+    }
+}
+
+impl From<String> for RotateError {
+    fn from(e: String) -> Self {
+        RotateError::Credential(e)
+    }
+}
+
+// Baris dikunci FOR UPDATE dalam satu transaksi supaya tidak tertimpa
+// perubahan kredensial yang berjalan bersamaan
+fn rotate_all(conn: &mut PgConnection) -> Result<(usize, usize), RotateError> {
+    conn.transaction::<_, RotateError, _>(|conn| {
+        let mut database_count = 0;
+        for (id, password) in tbl_ext_database::table
+            .select((tbl_ext_database::id, tbl_ext_database::password))
+            .for_update()
+            .load::<(i64, String)>(conn)?
+        {
+            if let Some(password) = reencrypt(&password)? {
+                diesel::update(tbl_ext_database::table.filter(tbl_ext_database::id.eq(id)))
+                    .set(tbl_ext_database::password.eq(password))
+                    .execute(conn)?;
+                database_count += 1;
+            }
+        }
+
+        let mut server_count = 0;
+        for (id, password, private_key) in tbl_ext_server::table
+            .select((
+                tbl_ext_server::id,
+                tbl_ext_server::password,
+                tbl_ext_server::private_key,
+            ))
+            .for_update()
+            .load::<(i64, Option<String>, Option<String>)>(conn)?
+        {
+            let password = reencrypt_optional(password.as_deref())?;
+            let private_key = reencrypt_optional(private_key.as_deref())?;
+            if password.is_none() && private_key.is_none() {
+                continue;
+            }
+
+            let target = tbl_ext_server::table.filter(tbl_ext_server::id.eq(id));
+            if let Some(password) = password {
+                diesel::update(target)
+                    .set(tbl_ext_server::password.eq(password))
+                    .execute(conn)?;
+            }
+            if let Some(private_key) = private_key {
+                diesel::update(target)
+                    .set(tbl_ext_server::private_key.eq(private_key))
+                    .execute(conn)?;
+            }
+            server_count += 1;
+        }
+
+        Ok((database_count, server_count))
+    })
+}
+
+#[handler]
+pub fn credential_rotate(
+    pool: poem::web::Data<&DbPool>,
+    jwt_auth: crate::auth::middleware::JwtAuth,
+) -> poem::Result<impl IntoResponse> {
+    crate::auth::middleware::ensure_admin(&jwt_auth.claims)?;
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    let (database_count, server_count) = rotate_all(conn).map_err(|e| match e {
+        RotateError::Credential(e) if e.starts_with("credential.") => {
+            eprintln!("Credential error: {}", e);
+            common::error_message(StatusCode::BAD_REQUEST, &e)
+        }
+        RotateError::Credential(e) => {
+            eprintln!("Credential error: {}", e);
+            common::error_message(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "information.internalServerError",
+            )
+        }
+        RotateError::Database(e) => {
+            eprintln!("Updating error: {}", e);
+            common::error_message(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "information.internalServerError",
+            )
+        }
+    })?;
+
+    Ok(Json(DataResponse {
+        data: json!({
+            "databaseCount": database_count,
+            "serverCount": server_count,
+        }),
+    }))
+}
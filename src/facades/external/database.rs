@@ -1,7 +1,9 @@
+use crate::facades::external::database_metadata::invalidate_metadata;
 use crate::models::common::{DataResponse, PaginatedResponse};
 use crate::models::external::database::{EntryExternalDatabase, ExternalDatabase};
 use crate::schema::tbl_ext_database;
 use crate::utils::common::{self, parse_pagination, validate_id, validation_error_response};
+use crate::utils::crypto::seal_credential;
 use crate::{db::DbPool, models::common::Pagination};
 use chrono::Utc;
 use diesel::prelude::*;
@@ -114,6 +116,15 @@ pub fn add(
         return Err(validation_error_response(e));
     }
 
+    let password = entry_ext_database
+        .password
+        .as_deref()
+        .filter(|password| !password.is_empty())
+        .ok_or_else(|| {
+            common::error_message(StatusCode::BAD_REQUEST, "credential.passwordRequired")
+        })?;
+    let password = seal_credential(password)?;
+
     let ext_database = ExternalDatabase {
         id: common::generate_id(),
         cd: entry_ext_database.cd,
@@ -123,10 +134,12 @@ pub fn add(
         ip: entry_ext_database.ip,
         port: entry_ext_database.port,
         username: entry_ext_database.username,
-        password: entry_ext_database.password,
+        password,
         db_name: entry_ext_database.db_name,
+        default_schema: entry_ext_database.default_schema,
         // db_connection: entry_ext_database.db_connection,
         is_use_page: entry_ext_database.is_use_page,
+        count_estimate_threshold: entry_ext_database.count_estimate_threshold,
         is_lock: entry_ext_database.is_lock,
         is_del: 0,
         created_by: jwt_auth.claims.id,
@@ -178,26 +191,45 @@ pub fn update(
     })?;
 
     entry_ext_database.version = entry_ext_database.version + 1;
+    let password = entry_ext_database
+        .password
+        .as_deref()
+        .filter(|password| !password.is_empty())
+        .map(seal_credential)
+        .transpose()?;
 
-    let updated = diesel::update(
-        tbl_ext_database::table
-            .filter(tbl_ext_database::id.eq(ext_database_id))
-            .filter(tbl_ext_database::version.eq(&entry_ext_database.version - 1)),
-    )
-    .set((
-        &entry_ext_database,
-        tbl_ext_database::updated_by.eq(Some(jwt_auth.claims.id)),
-        tbl_ext_database::dt_updated.eq(Some(Utc::now().naive_utc())),
-    ))
-    .get_result::<ExternalDatabase>(conn)
-    .map_err(|e| {
-        eprintln!("Updating error: {}", e);
-        common::error_message(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "information.internalServerError",
-        )
-    })?;
+    let updated = conn
+        .transaction::<_, diesel::result::Error, _>(|conn| {
+            let updated = diesel::update(
+                tbl_ext_database::table
+                    .filter(tbl_ext_database::id.eq(ext_database_id))
+                    .filter(tbl_ext_database::version.eq(&entry_ext_database.version - 1)),
+            )
+            .set((
+                &entry_ext_database,
+                tbl_ext_database::updated_by.eq(Some(jwt_auth.claims.id)),
+                tbl_ext_database::dt_updated.eq(Some(Utc::now().naive_utc())),
+            ))
+            .get_result::<ExternalDatabase>(conn)?;
+
+            match password {
+                Some(password) => diesel::update(
+                    tbl_ext_database::table.filter(tbl_ext_database::id.eq(ext_database_id)),
+                )
+                .set(tbl_ext_database::password.eq(password))
+                .get_result::<ExternalDatabase>(conn),
+                None => Ok(updated),
+            }
+        })
+        .map_err(|e| {
+            eprintln!("Updating error: {}", e);
+            common::error_message(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "information.internalServerError",
+            )
+        })?;
 
+    invalidate_metadata(ext_database_id);
     Ok(Json(DataResponse { data: updated }))
 }
 
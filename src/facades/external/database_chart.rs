@@ -0,0 +1,724 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+use diesel::PgConnection;
+use poem::web::Json;
+use poem::{IntoResponse, handler, http::StatusCode, web::Path};
+use serde_json::{Map, Number, Value};
+use validator::Validate;
+
+use crate::database_pool::DatabasePool;
+use crate::db::DbPool;
+use crate::facades::external::database_execution::{ExecutionContext, record_execution};
+use crate::facades::external::database_mask::{MaskPolicy, load_mask_policy};
+use crate::facades::external::database_query::{
+    get_external_pool, get_manual_query, get_whitelist_query, is_mysql_database,
+};
+use crate::facades::external::database_snapshot::{Snapshot, get_snapshot, with_snapshot_header};
+use crate::models::common::DataResponse;
+use crate::models::external::database_chart::{ChartResult, ChartSeries, EntryChart};
+use crate::utils::common::{self, validate_id, validation_error_response};
+use crate::utils::database::{
+    SqlStatementKind, apply_parameter, parse_statement_info, quote_identifier_mysql,
+    quote_identifier_postgres, row_to_json_mysql, row_to_json_postgres, sql_dialect,
+};
+
+const CHART_GROUP_LIMIT: usize = 10_000;
+const CHART_SERIES_LIMIT: usize = 100;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Aggregation {
+    Count,
+    CountDistinct,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+impl Aggregation {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "count" => Some(Aggregation::Count),
+            "countDistinct" => Some(Aggregation::CountDistinct),
+            "sum" => Some(Aggregation::Sum),
+            "avg" => Some(Aggregation::Avg),
+            "min" => Some(Aggregation::Min),
+            "max" => Some(Aggregation::Max),
+            _ => None,
+        }
+    }
+
+    // MIN/MAX juga berlaku untuk teks dan tanggal sehingga nilainya tidak diubah ke angka
+    fn is_numeric(&self) -> bool {
+        !matches!(self, Aggregation::Min | Aggregation::Max)
+    }
+}
+
+struct Measure {
+    column: Option<String>,
+    aggregation: Aggregation,
+}
+
+impl Measure {
+    fn label(&self) -> String {
+        let column = self.column.as_deref().unwrap_or("*");
+        match self.aggregation {
+            Aggregation::Count => format!("count({})", column),
+            Aggregation::CountDistinct => format!("count(distinct {})", column),
+            Aggregation::Sum => format!("sum({})", column),
+            Aggregation::Avg => format!("avg({})", column),
+            Aggregation::Min => format!("min({})", column),
+            Aggregation::Max => format!("max({})", column),
+        }
+    }
+
+    fn sql(&self, column: Option<String>) -> String {
+        let Some(column) = column else {
+            return String::from("COUNT(*)");
+        };
+        match self.aggregation {
+            Aggregation::Count => format!("COUNT({})", column),
+            Aggregation::CountDistinct => format!("COUNT(DISTINCT {})", column),
+            Aggregation::Sum => format!("SUM({})", column),
+            Aggregation::Avg => format!("AVG({})", column),
+            Aggregation::Min => format!("MIN({})", column),
+            Aggregation::Max => format!("MAX({})", column),
+        }
+    }
+}
+
+struct ChartSpec {
+    dimensions: Vec<String>,
+    pivot: Option<String>,
+    measures: Vec<Measure>,
+    top_n: Option<usize>,
+}
+
+struct ChartRow {
+    dimensions: Vec<Value>,
+    pivot: Option<Value>,
+    measures: Vec<Value>,
+}
+
+fn invalid(key: &str) -> poem::Error {
+    common::error_message(StatusCode::BAD_REQUEST, key)
+}
+
+fn non_blank(value: Option<String>) -> Option<String> {
+    value.filter(|v| !v.trim().is_empty())
+}
+
+// Kolom yang dimasking tidak boleh dikelompokkan atau diagregasi, kecuali sekadar dihitung
+fn chart_spec(entry: EntryChart, mask: &MaskPolicy) -> poem::Result<ChartSpec> {
+    let pivot = non_blank(entry.pivot_column);
+    if entry.dimensions.iter().any(|d| d.trim().is_empty()) {
+        return Err(invalid("chart.invalidColumn"));
+    }
+
+    let mut measures = Vec::new();
+    for measure in entry.measures {
+        let aggregation = Aggregation::parse(&measure.aggregation)
+            .ok_or_else(|| invalid("chart.invalidAggregation"))?;
+        let column = non_blank(measure.column);
+        if column.is_none() && aggregation != Aggregation::Count {
+            return Err(invalid("chart.columnRequired"));
+        }
+        if aggregation != Aggregation::Count && column.as_deref().is_some_and(|c| mask.is_masked(c))
+        {
+            return Err(common::error_message(
+                StatusCode::FORBIDDEN,
+                "chart.maskedColumn",
+            ));
+        }
+        measures.push(Measure {
+            column,
+            aggregation,
+        });
+    }
+
+    if entry
+        .dimensions
+        .iter()
+        .chain(pivot.as_ref())
+        .any(|column| mask.is_masked(column))
+    {
+        return Err(common::error_message(
+            StatusCode::FORBIDDEN,
+            "chart.maskedColumn",
+        ));
+    }
+
+    Ok(ChartSpec {
+        top_n: entry
+            .top_n
+            .filter(|_| !entry.dimensions.is_empty())
+            .map(|n| n as usize),
+        dimensions: entry.dimensions,
+        pivot,
+        measures,
+    })
+}
+
+// Query tersimpan dibungkus sebagai subquery sehingga hanya hasil agregasi yang dikirim dari database
+fn build_chart_query(query: &str, spec: &ChartSpec, is_mysql: bool) -> String {
+    let quote: fn(&str) -> String = if is_mysql {
+        quote_identifier_mysql
+    } else {
+        quote_identifier_postgres
+    };
+    let column = |alias: &str, name: &str| format!("{}.{}", alias, quote(name));
+    let source = query.trim().trim_end_matches(';');
+    let descending = if is_mysql { "DESC" } else { "DESC NULLS LAST" };
+
+    let mut select = Vec::new();
+    let mut group = Vec::new();
+    for (i, dimension) in spec.dimensions.iter().enumerate() {
+        select.push(format!(
+            "{} AS chart_d{}",
+            column("chart_rows", dimension),
+            i
+        ));
+        group.push(column("chart_rows", dimension));
+    }
+    if let Some(pivot) = &spec.pivot {
+        select.push(format!("{} AS chart_p", column("chart_rows", pivot)));
+        group.push(column("chart_rows", pivot));
+    }
+    for (i, measure) in spec.measures.iter().enumerate() {
+        let measure_column = measure.column.as_deref().map(|c| column("chart_rows", c));
+        select.push(format!("{} AS chart_m{}", measure.sql(measure_column), i));
+    }
+
+    let mut sql = format!("SELECT {} FROM ({}) chart_rows", select.join(", "), source);
+
+    // Dengan pivot, kategori teratas diukur dari seluruh nilai pivot lalu di-join ke hasil utama
+    if let (Some(top_n), Some(_)) = (spec.top_n, &spec.pivot) {
+        let first = &spec.measures[0];
+        let top_select = spec
+            .dimensions
+            .iter()
+            .enumerate()
+            .map(|(i, d)| format!("{} AS chart_d{}", column("chart_top_rows", d), i))
+            .collect::<Vec<_>>();
+        let top_group = spec
+            .dimensions
+            .iter()
+            .map(|d| column("chart_top_rows", d))
+            .collect::<Vec<_>>();
+        let condition = spec
+            .dimensions
+            .iter()
+            .enumerate()
+            .map(|(i, d)| {
+                format!(
+                    "{} {} chart_top.chart_d{}",
+                    column("chart_rows", d),
+                    if is_mysql {
+                        "<=>"
+                    } else {
+                        "IS NOT DISTINCT FROM"
+                    },
+                    i
+                )
+            })
+            .collect::<Vec<_>>();
+        sql.push_str(&format!(
+            " JOIN (SELECT {} FROM ({}) chart_top_rows GROUP BY {} ORDER BY {} {} LIMIT {}) chart_top ON {}",
+            top_select.join(", "),
+            source,
+            top_group.join(", "),
+            first.sql(first.column.as_deref().map(|c| column("chart_top_rows", c))),
+            descending,
+            top_n,
+            condition.join(" AND ")
+        ));
+    }
+
+    if !group.is_empty() {
+        sql.push_str(&format!(" GROUP BY {}", group.join(", ")));
+    }
+    match spec.top_n {
+        Some(top_n) if spec.pivot.is_none() => {
+            sql.push_str(&format!(
+                " ORDER BY chart_m0 {} LIMIT {}",
+                descending, top_n
+            ));
+        }
+        _ => {
+            if !group.is_empty() {
+                sql.push_str(&format!(" ORDER BY {}", group.join(", ")));
+            }
+            sql.push_str(&format!(" LIMIT {}", CHART_GROUP_LIMIT + 1));
+        }
+    }
+    sql
+}
+
+async fn fetch_chart_rows(
+    ext_pool: &DatabasePool,
+    sql: &str,
+    spec: &ChartSpec,
+) -> poem::Result<Vec<ChartRow>> {
+    let query_error = |e: sqlx::Error| {
+        eprintln!("Query error: {}", e);
+        invalid("chart.queryFailed")
+    };
+
+    let rows: Vec<Map<String, Value>> = match ext_pool {
+        DatabasePool::Postgres(pg_pool) => sqlx::query(sql)
+            .fetch_all(pg_pool)
+            .await
+            .map_err(query_error)?
+            .iter()
+            .map(row_to_json_postgres)
+            .collect(),
+        DatabasePool::MySql(my_pool) => sqlx::query(sql)
+            .fetch_all(my_pool)
+            .await
+            .map_err(query_error)?
+            .iter()
+            .map(row_to_json_mysql)
+            .collect(),
+    };
+    if rows.len() > CHART_GROUP_LIMIT {
+        return Err(invalid("chart.tooManyGroups"));
+    }
+
+    Ok(rows
+        .into_iter()
+        .map(|mut row| {
+            let mut take = |name: String| row.remove(&name).unwrap_or(Value::Null);
+            ChartRow {
+                dimensions: (0..spec.dimensions.len())
+                    .map(|i| take(format!("chart_d{}", i)))
+                    .collect(),
+                pivot: spec.pivot.as_ref().map(|_| take(String::from("chart_p"))),
+                measures: (0..spec.measures.len())
+                    .map(|i| take(format!("chart_m{}", i)))
+                    .collect(),
+            }
+        })
+        .collect())
+}
+
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn as_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::from("NULL"),
+        other => other.to_string(),
+    }
+}
+
+// NULL selalu di urutan terakhir seperti urutan bawaan ORDER BY
+fn compare_values(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Null, Value::Null) => Ordering::Equal,
+        (Value::Null, _) => Ordering::Greater,
+        (_, Value::Null) => Ordering::Less,
+        _ => match (as_number(a), as_number(b)) {
+            (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+            _ => as_text(a).cmp(&as_text(b)),
+        },
+    }
+}
+
+fn compare_descending(a: &Value, b: &Value) -> Ordering {
+    match (a.is_null(), b.is_null()) {
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        _ => compare_values(b, a),
+    }
+}
+
+fn compare_list(a: &[Value], b: &[Value]) -> Ordering {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| compare_values(x, y))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+#[derive(Default)]
+struct MeasureState {
+    count: i64,
+    numeric_count: i64,
+    sum: f64,
+    distinct: HashSet<String>,
+    min: Option<Value>,
+    max: Option<Value>,
+}
+
+impl MeasureState {
+    // None berarti COUNT(*) sehingga setiap baris dihitung
+    fn add(&mut self, value: Option<&Value>) {
+        let Some(value) = value else {
+            self.count += 1;
+            return;
+        };
+        if value.is_null() {
+            return;
+        }
+        self.count += 1;
+        self.distinct.insert(value.to_string());
+        if let Some(number) = as_number(value) {
+            self.numeric_count += 1;
+            self.sum += number;
+        }
+        if self
+            .min
+            .as_ref()
+            .is_none_or(|min| compare_values(value, min).is_lt())
+        {
+            self.min = Some(value.clone());
+        }
+        if self
+            .max
+            .as_ref()
+            .is_none_or(|max| compare_values(value, max).is_gt())
+        {
+            self.max = Some(value.clone());
+        }
+    }
+
+    fn finish(&self, aggregation: Aggregation) -> Value {
+        match aggregation {
+            Aggregation::Count => Value::from(self.count),
+            Aggregation::CountDistinct => Value::from(self.distinct.len()),
+            Aggregation::Sum if self.numeric_count > 0 => number_value(self.sum),
+            Aggregation::Avg if self.numeric_count > 0 => {
+                number_value(self.sum / self.numeric_count as f64)
+            }
+            Aggregation::Min => self.min.clone().unwrap_or(Value::Null),
+            Aggregation::Max => self.max.clone().unwrap_or(Value::Null),
+            _ => Value::Null,
+        }
+    }
+}
+
+fn number_value(number: f64) -> Value {
+    Number::from_f64(number)
+        .map(Value::Number)
+        .unwrap_or(Value::Null)
+}
+
+// Agregasi di memori hanya untuk snapshot, karena query aslinya tidak dijalankan ulang
+fn aggregate_rows(rows: &[Value], spec: &ChartSpec) -> Vec<ChartRow> {
+    let mut groups: Vec<(Vec<Value>, Option<Value>, Vec<MeasureState>)> = Vec::new();
+    let mut group_index: HashMap<String, usize> = HashMap::new();
+    let mut ranks: HashMap<String, MeasureState> = HashMap::new();
+
+    for row in rows.iter().filter_map(Value::as_object) {
+        let cell = |name: &str| row.get(name).cloned().unwrap_or(Value::Null);
+        let dimensions: Vec<Value> = spec.dimensions.iter().map(|d| cell(d)).collect();
+        let pivot = spec.pivot.as_deref().map(cell);
+        let dimension_key = Value::from(dimensions.clone()).to_string();
+        let key = format!(
+            "{}{}",
+            dimension_key,
+            pivot.as_ref().map(Value::to_string).unwrap_or_default()
+        );
+
+        let index = *group_index.entry(key).or_insert_with(|| {
+            groups.push((
+                dimensions,
+                pivot,
+                spec.measures
+                    .iter()
+                    .map(|_| MeasureState::default())
+                    .collect(),
+            ));
+            groups.len() - 1
+        });
+        for (state, measure) in groups[index].2.iter_mut().zip(&spec.measures) {
+            state.add(
+                measure
+                    .column
+                    .as_deref()
+                    .map(|c| row.get(c).unwrap_or(&Value::Null)),
+            );
+        }
+
+        if spec.top_n.is_some() {
+            let first = &spec.measures[0];
+            ranks.entry(dimension_key).or_default().add(
+                first
+                    .column
+                    .as_deref()
+                    .map(|c| row.get(c).unwrap_or(&Value::Null)),
+            );
+        }
+    }
+
+    let mut chart_rows: Vec<ChartRow> = groups
+        .into_iter()
+        .map(|(dimensions, pivot, states)| ChartRow {
+            dimensions,
+            pivot,
+            measures: states
+                .iter()
+                .zip(&spec.measures)
+                .map(|(state, measure)| state.finish(measure.aggregation))
+                .collect(),
+        })
+        .collect();
+
+    let first = spec.measures[0].aggregation;
+    match spec.top_n {
+        Some(top_n) if spec.pivot.is_none() => {
+            chart_rows.sort_by(|a, b| compare_descending(&a.measures[0], &b.measures[0]));
+            chart_rows.truncate(top_n);
+        }
+        Some(top_n) => {
+            let mut ranked: Vec<(&String, Value)> = ranks
+                .iter()
+                .map(|(key, state)| (key, state.finish(first)))
+                .collect();
+            ranked.sort_by(|a, b| compare_descending(&a.1, &b.1));
+            let top: HashSet<String> = ranked
+                .into_iter()
+                .take(top_n)
+                .map(|(key, _)| key.clone())
+                .collect();
+            chart_rows.retain(|row| top.contains(&Value::from(row.dimensions.clone()).to_string()));
+            chart_rows.sort_by(|a, b| compare_list(&a.dimensions, &b.dimensions));
+        }
+        None => chart_rows.sort_by(|a, b| {
+            compare_list(&a.dimensions, &b.dimensions).then_with(|| {
+                compare_values(
+                    a.pivot.as_ref().unwrap_or(&Value::Null),
+                    b.pivot.as_ref().unwrap_or(&Value::Null),
+                )
+            })
+        }),
+    }
+    chart_rows
+}
+
+// Angka desimal dari database dikirim sebagai teks, untuk grafik diubah menjadi angka
+fn chart_value(value: Value, aggregation: Aggregation) -> Value {
+    match value {
+        Value::String(s) if aggregation.is_numeric() => s
+            .trim()
+            .parse::<i64>()
+            .map(Value::from)
+            .ok()
+            .or_else(|| s.trim().parse::<f64>().ok().map(number_value))
+            .unwrap_or(Value::String(s)),
+        other => other,
+    }
+}
+
+// Satu series per nilai pivot dan measure, data disejajarkan dengan urutan kategori
+fn build_chart_result(
+    rows: Vec<ChartRow>,
+    spec: ChartSpec,
+    is_pushed_down: i16,
+) -> poem::Result<ChartResult> {
+    let mut categories: Vec<Vec<Value>> = Vec::new();
+    let mut category_index: HashMap<String, usize> = HashMap::new();
+    let mut pivots: Vec<Option<Value>> = Vec::new();
+    let mut pivot_index: HashMap<String, usize> = HashMap::new();
+    let mut positions = Vec::new();
+
+    for row in &rows {
+        let category = *category_index
+            .entry(Value::from(row.dimensions.clone()).to_string())
+            .or_insert_with(|| {
+                categories.push(row.dimensions.clone());
+                categories.len() - 1
+            });
+        let pivot = *pivot_index
+            .entry(row.pivot.as_ref().map(Value::to_string).unwrap_or_default())
+            .or_insert_with(|| {
+                pivots.push(row.pivot.clone());
+                pivots.len() - 1
+            });
+        positions.push((category, pivot));
+    }
+
+    if pivots.len() * spec.measures.len() > CHART_SERIES_LIMIT {
+        return Err(invalid("chart.tooManySeries"));
+    }
+
+    let mut series: Vec<ChartSeries> = Vec::new();
+    for pivot in &pivots {
+        for measure in &spec.measures {
+            let label = measure.label();
+            series.push(ChartSeries {
+                nm: match pivot {
+                    Some(value) if spec.measures.len() == 1 => as_text(value),
+                    Some(value) => format!("{} - {}", as_text(value), label),
+                    None => label.clone(),
+                },
+                measure: label,
+                pivot_value: pivot.clone(),
+                data: vec![Value::Null; categories.len()],
+            });
+        }
+    }
+
+    for (row, (category, pivot)) in rows.into_iter().zip(positions) {
+        for (i, (value, measure)) in row.measures.into_iter().zip(&spec.measures).enumerate() {
+            series[pivot * spec.measures.len() + i].data[category] =
+                chart_value(value, measure.aggregation);
+        }
+    }
+
+    Ok(ChartResult {
+        dimensions: spec.dimensions,
+        categories,
+        series,
+        is_pushed_down,
+    })
+}
+
+async fn chart_query(
+    conn: &mut PgConnection,
+    ext_database_id: i64,
+    query: &str,
+    snapshot: Option<Snapshot>,
+    entry: EntryChart,
+    roles: &[i16],
+    user_id: i64,
+) -> poem::Result<ChartResult> {
+    // Placeholder diganti sebelum query diparse untuk masking maupun pengecekan SELECT
+    let is_mysql = is_mysql_database(conn, ext_database_id)?;
+    let query = &apply_parameter(query, &entry.parameter, is_mysql).map_err(|e| invalid(&e))?;
+    let mask = load_mask_policy(conn, ext_database_id, roles, query)?;
+    let spec = chart_spec(entry, &mask)?;
+
+    if let Some(snapshot) = snapshot {
+        let is_known = spec
+            .dimensions
+            .iter()
+            .chain(spec.pivot.as_ref())
+            .chain(spec.measures.iter().filter_map(|m| m.column.as_ref()))
+            .all(|column| snapshot.headers.contains(column));
+        if !is_known {
+            return Err(invalid("chart.invalidColumn"));
+        }
+        let rows = aggregate_rows(&snapshot.rows, &spec);
+        if rows.len() > CHART_GROUP_LIMIT {
+            return Err(invalid("chart.tooManyGroups"));
+        }
+        return build_chart_result(rows, spec, 0);
+    }
+
+    let (ext_pool, tunnel, _, _) = get_external_pool(conn, ext_database_id).await?;
+    let result = async {
+        let dialect = sql_dialect(is_mysql);
+        let is_select = parse_statement_info(query, dialect.as_ref())
+            .is_ok_and(|statement| statement.kind == SqlStatementKind::Select);
+        if !is_select {
+            return Err(invalid("chart.selectOnly"));
+        }
+        let sql = build_chart_query(query, &spec, is_mysql);
+        let dt_started = Instant::now();
+        let rows = fetch_chart_rows(&ext_pool, &sql, &spec).await;
+        Ok((sql, dt_started, rows))
+    }
+    .await;
+
+    if let Some(mut tunnel) = tunnel {
+        let _ = tunnel.kill().ok();
+    };
+
+    let (sql, dt_started, rows) = result?;
+    let context = ExecutionContext::new(&ext_pool, ext_database_id, user_id, "chart");
+    record_execution(
+        conn,
+        &context,
+        &sql,
+        dt_started,
+        rows.as_ref()
+            .map(|rows| Some(rows.len() as u64))
+            .map_err(|e| e.to_string()),
+    );
+
+    build_chart_result(rows?, spec, 1)
+}
+
+#[handler]
+pub async fn chart_query_manual(
+    pool: poem::web::Data<&DbPool>,
+    jwt_auth: crate::auth::middleware::JwtAuth,
+    Path(query_manual_id): Path<i64>,
+    Json(entry): Json<EntryChart>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(query_manual_id)?;
+
+    if let Err(e) = entry.validate() {
+        return Err(validation_error_response(e));
+    }
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    let (ext_database_id, query) = get_manual_query(conn, query_manual_id)?;
+    let snapshot = get_snapshot(conn, query_manual_id);
+    let dt_captured = snapshot.as_ref().map(|s| s.dt_captured);
+    let data = chart_query(
+        conn,
+        ext_database_id,
+        &query,
+        snapshot,
+        entry,
+        jwt_auth.claims.role.as_deref().unwrap_or_default(),
+        jwt_auth.claims.id,
+    )
+    .await?;
+
+    Ok(with_snapshot_header(
+        Json(DataResponse { data }),
+        dt_captured,
+    ))
+}
+
+#[handler]
+pub async fn chart_query_whitelist(
+    pool: poem::web::Data<&DbPool>,
+    jwt_auth: crate::auth::middleware::JwtAuth,
+    Path(ext_database_query_id): Path<i64>,
+    Json(entry): Json<EntryChart>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(ext_database_query_id)?;
+
+    if let Err(e) = entry.validate() {
+        return Err(validation_error_response(e));
+    }
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    let (ext_database_id, query) = get_whitelist_query(conn, ext_database_query_id)?;
+    let data = chart_query(
+        conn,
+        ext_database_id,
+        &query,
+        None,
+        entry,
+        jwt_auth.claims.role.as_deref().unwrap_or_default(),
+        jwt_auth.claims.id,
+    )
+    .await?;
+
+    Ok(Json(DataResponse { data }))
+}
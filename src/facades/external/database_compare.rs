@@ -0,0 +1,564 @@
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::time::Instant;
+
+use diesel::PgConnection;
+use poem::web::Json;
+use poem::{IntoResponse, handler, http::StatusCode, web::Path};
+use serde_json::{Map, Value};
+use sqlx::types::BigDecimal;
+use sqlx::{Column, Row, TypeInfo};
+use validator::Validate;
+
+use crate::auth::model::Claims;
+use crate::database_pool::DatabasePool;
+use crate::db::DbPool;
+use crate::facades::external::database_execution::{ExecutionContext, record_execution};
+use crate::facades::external::database_mask::{MaskPolicy, load_mask_policy};
+use crate::facades::external::database_query::{get_external_pool, resolve_schema};
+use crate::models::common::DataResponse;
+use crate::models::external::database_compare::{
+    ColumnDifference, DataCompareResult, EntryDataCompare, RowDifference,
+};
+use crate::utils::common::{self, validate_id, validation_error_response};
+use crate::utils::database::{
+    SqlStatementKind, logical_type_mysql, logical_type_postgres, parse_statement_info,
+    quote_identifier_mysql, quote_identifier_postgres, row_to_json_mysql, row_to_json_postgres,
+    sql_dialect, value_to_sql_literal,
+};
+
+const COMPARE_ROW_LIMIT: usize = 200_000;
+const COMPARE_REPORT_LIMIT: usize = 1000;
+
+struct CompareSide {
+    headers: Vec<String>,
+    rows: Vec<Map<String, Value>>,
+    numeric_columns: HashSet<String>,
+}
+
+fn quote_identifier(ext_pool: &DatabasePool, name: &str) -> String {
+    match ext_pool {
+        DatabasePool::Postgres(_) => quote_identifier_postgres(name),
+        DatabasePool::MySql(_) => quote_identifier_mysql(name),
+    }
+}
+
+fn qualify_table(ext_pool: &DatabasePool, schema: Option<&str>, table: &str) -> String {
+    match schema {
+        Some(schema) => format!(
+            "{}.{}",
+            quote_identifier(ext_pool, schema),
+            quote_identifier(ext_pool, table)
+        ),
+        None => quote_identifier(ext_pool, table),
+    }
+}
+
+fn compare_source_query(
+    ext_pool: &DatabasePool,
+    schema: Option<&str>,
+    table: Option<&str>,
+    query: Option<&str>,
+) -> poem::Result<String> {
+    let source = match (
+        query.filter(|q| !q.trim().is_empty()),
+        table.filter(|t| !t.trim().is_empty()),
+    ) {
+        (Some(query), _) => {
+            let dialect = sql_dialect(matches!(ext_pool, DatabasePool::MySql(_)));
+            let is_select = parse_statement_info(query, dialect.as_ref())
+                .is_ok_and(|statement| statement.kind == SqlStatementKind::Select);
+            if !is_select {
+                return Err(common::error_message(
+                    StatusCode::BAD_REQUEST,
+                    "dataCompare.selectOnly",
+                ));
+            }
+            format!("({})", query.trim().trim_end_matches(';'))
+        }
+        (None, Some(table)) => qualify_table(ext_pool, schema, table),
+        (None, None) => {
+            return Err(common::error_message(
+                StatusCode::BAD_REQUEST,
+                "dataCompare.sourceRequired",
+            ));
+        }
+    };
+
+    Ok(format!(
+        "SELECT * FROM {} compare_rows LIMIT {}",
+        source,
+        COMPARE_ROW_LIMIT + 1
+    ))
+}
+
+async fn fetch_compare_side(
+    conn: &mut PgConnection,
+    context: &ExecutionContext,
+    ext_pool: &DatabasePool,
+    query: &str,
+) -> poem::Result<CompareSide> {
+    let dt_started = Instant::now();
+    let fetched = match ext_pool {
+        DatabasePool::Postgres(pg_pool) => {
+            sqlx::query(query).fetch_all(pg_pool).await.map(|rows| {
+                let columns = rows.first().map(|row| row.columns()).unwrap_or_default();
+                (
+                    columns.iter().map(|c| c.name().to_string()).collect(),
+                    columns
+                        .iter()
+                        .filter(|c| is_numeric_type(logical_type_postgres(c.type_info())))
+                        .map(|c| c.name().to_string())
+                        .collect(),
+                    rows.iter().map(row_to_json_postgres).collect::<Vec<_>>(),
+                )
+            })
+        }
+        DatabasePool::MySql(my_pool) => sqlx::query(query).fetch_all(my_pool).await.map(|rows| {
+            let columns = rows.first().map(|row| row.columns()).unwrap_or_default();
+            (
+                columns.iter().map(|c| c.name().to_string()).collect(),
+                columns
+                    .iter()
+                    .filter(|c| is_numeric_type(logical_type_mysql(c.type_info().name())))
+                    .map(|c| c.name().to_string())
+                    .collect(),
+                rows.iter().map(row_to_json_mysql).collect::<Vec<_>>(),
+            )
+        }),
+    };
+    record_execution(
+        conn,
+        context,
+        query,
+        dt_started,
+        fetched
+            .as_ref()
+            .map(|(_, _, rows)| Some(rows.len() as u64))
+            .map_err(|e| e.to_string()),
+    );
+    let (headers, numeric_columns, rows) = fetched.map_err(|e| {
+        eprintln!("Query error: {}", e);
+        common::error_message(StatusCode::BAD_REQUEST, "dataCompare.queryFailed")
+    })?;
+
+    if rows.len() > COMPARE_ROW_LIMIT {
+        return Err(common::error_message(
+            StatusCode::BAD_REQUEST,
+            "dataCompare.tooManyRows",
+        ));
+    }
+
+    Ok(CompareSide {
+        headers,
+        rows,
+        numeric_columns,
+    })
+}
+
+fn is_numeric_type(logical_type: &str) -> bool {
+    matches!(logical_type, "integer" | "float" | "decimal")
+}
+
+// Nilai dibandingkan dalam bentuk teks agar tipe yang setara di dua dialek dianggap sama,
+// kolom angka dinormalkan dulu supaya 1.50 dan 1.5 tidak dianggap berbeda
+fn normalize_value(value: &Value, is_numeric: bool) -> Option<String> {
+    let text = match value {
+        Value::Null => return None,
+        Value::Bool(b) => return Some(if *b { "1" } else { "0" }.to_string()),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    if is_numeric && let Ok(number) = BigDecimal::from_str(&text) {
+        return Some(number.normalized().to_string());
+    }
+    Some(text)
+}
+
+fn row_key(
+    row: &Map<String, Value>,
+    key_columns: &[String],
+    numeric_columns: &HashSet<&str>,
+) -> Vec<Option<String>> {
+    key_columns
+        .iter()
+        .map(|col| {
+            normalize_value(
+                row.get(col).unwrap_or(&Value::Null),
+                numeric_columns.contains(col.as_str()),
+            )
+        })
+        .collect()
+}
+
+fn key_map(row: &Map<String, Value>, key_columns: &[String]) -> Map<String, Value> {
+    key_columns
+        .iter()
+        .map(|col| (col.clone(), row.get(col).cloned().unwrap_or(Value::Null)))
+        .collect()
+}
+
+fn where_clause(
+    row: &Map<String, Value>,
+    key_columns: &[String],
+    quote: impl Fn(&str) -> String,
+    is_mysql: bool,
+) -> String {
+    key_columns
+        .iter()
+        .map(|col| match row.get(col).unwrap_or(&Value::Null) {
+            Value::Null => format!("{} IS NULL", quote(col)),
+            value => format!("{} = {}", quote(col), value_to_sql_literal(value, is_mysql)),
+        })
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+fn compare_sides(
+    source: &CompareSide,
+    target: &CompareSide,
+    key_columns: &[String],
+    reconcile_table: Option<(&str, &DatabasePool)>,
+) -> poem::Result<DataCompareResult> {
+    for side in [source, target] {
+        if !side.rows.is_empty() && key_columns.iter().any(|k| !side.headers.contains(k)) {
+            return Err(common::error_message(
+                StatusCode::BAD_REQUEST,
+                "dataCompare.keyColumnNotFound",
+            ));
+        }
+    }
+
+    // Kolom dianggap angka jika bertipe angka di salah satu sisi
+    let numeric_columns: HashSet<&str> = source
+        .numeric_columns
+        .iter()
+        .chain(target.numeric_columns.iter())
+        .map(String::as_str)
+        .collect();
+
+    let mut target_index = HashMap::new();
+    for (i, row) in target.rows.iter().enumerate() {
+        if target_index
+            .insert(row_key(row, key_columns, &numeric_columns), i)
+            .is_some()
+        {
+            return Err(common::error_message(
+                StatusCode::BAD_REQUEST,
+                "dataCompare.duplicateKey",
+            ));
+        }
+    }
+
+    let compared_columns: Vec<&String> = source
+        .headers
+        .iter()
+        .filter(|h| !key_columns.contains(h))
+        .filter(|h| target.rows.is_empty() || target.headers.contains(h))
+        .collect();
+    let insert_columns: Vec<&String> = source
+        .headers
+        .iter()
+        .filter(|h| target.rows.is_empty() || target.headers.contains(h))
+        .collect();
+
+    let mut seen = vec![false; target.rows.len()];
+    let mut source_keys = HashSet::new();
+    let mut matched_row = 0;
+    let mut only_in_source = Vec::new();
+    let mut different = Vec::new();
+    let mut inserts = Vec::new();
+    let mut updates = Vec::new();
+    let mut deletes = Vec::new();
+
+    let is_mysql = matches!(reconcile_table, Some((_, DatabasePool::MySql(_))));
+    let quote = |name: &str| match reconcile_table {
+        Some((_, ext_pool)) => quote_identifier(ext_pool, name),
+        None => name.to_string(),
+    };
+
+    for source_row in &source.rows {
+        let key = row_key(source_row, key_columns, &numeric_columns);
+        if !source_keys.insert(key.clone()) {
+            return Err(common::error_message(
+                StatusCode::BAD_REQUEST,
+                "dataCompare.duplicateKey",
+            ));
+        }
+
+        let Some(&target_pos) = target_index.get(&key) else {
+            if let Some((table, _)) = reconcile_table {
+                inserts.push(format!(
+                    "INSERT INTO {} ({}) VALUES ({});",
+                    table,
+                    insert_columns
+                        .iter()
+                        .map(|c| quote(c))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    insert_columns
+                        .iter()
+                        .map(|c| value_to_sql_literal(
+                            source_row.get(*c).unwrap_or(&Value::Null),
+                            is_mysql
+                        ))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+            only_in_source.push(source_row);
+            continue;
+        };
+
+        seen[target_pos] = true;
+        let target_row = &target.rows[target_pos];
+
+        let columns: Vec<ColumnDifference> = compared_columns
+            .iter()
+            .filter_map(|col| {
+                let source_value = source_row.get(*col).unwrap_or(&Value::Null);
+                let target_value = target_row.get(*col).unwrap_or(&Value::Null);
+                let is_numeric = numeric_columns.contains(col.as_str());
+                (normalize_value(source_value, is_numeric)
+                    != normalize_value(target_value, is_numeric))
+                .then(|| ColumnDifference {
+                    nm: col.to_string(),
+                    source_value: source_value.clone(),
+                    target_value: target_value.clone(),
+                })
+            })
+            .collect();
+
+        if columns.is_empty() {
+            matched_row += 1;
+            continue;
+        }
+
+        if let Some((table, _)) = reconcile_table {
+            updates.push(format!(
+                "UPDATE {} SET {} WHERE {};",
+                table,
+                columns
+                    .iter()
+                    .map(|c| format!(
+                        "{} = {}",
+                        quote(&c.nm),
+                        value_to_sql_literal(&c.source_value, is_mysql)
+                    ))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                where_clause(target_row, key_columns, quote, is_mysql)
+            ));
+        }
+        different.push(RowDifference {
+            key: key_map(source_row, key_columns),
+            columns,
+        });
+    }
+
+    let only_in_target: Vec<&Map<String, Value>> = target
+        .rows
+        .iter()
+        .zip(seen.iter())
+        .filter(|(_, seen)| !**seen)
+        .map(|(row, _)| row)
+        .collect();
+
+    if let Some((table, _)) = reconcile_table {
+        for target_row in &only_in_target {
+            deletes.push(format!(
+                "DELETE FROM {} WHERE {};",
+                table,
+                where_clause(target_row, key_columns, quote, is_mysql)
+            ));
+        }
+    }
+
+    Ok(DataCompareResult {
+        source_row: source.rows.len(),
+        target_row: target.rows.len(),
+        matched_row,
+        only_in_source_row: only_in_source.len(),
+        only_in_target_row: only_in_target.len(),
+        different_row: different.len(),
+        only_in_source: only_in_source
+            .into_iter()
+            .take(COMPARE_REPORT_LIMIT)
+            .cloned()
+            .collect(),
+        only_in_target: only_in_target
+            .into_iter()
+            .take(COMPARE_REPORT_LIMIT)
+            .cloned()
+            .collect(),
+        different: different.into_iter().take(COMPARE_REPORT_LIMIT).collect(),
+        script: reconcile_table.map(|_| [deletes, updates, inserts].concat().join("\n")),
+    })
+}
+
+struct CompareDatabase<'a> {
+    ext_database_id: i64,
+    pool: &'a DatabasePool,
+    schema: Option<String>,
+}
+
+// Key dan script reconcile memakai nilai asli, jadi kolom tersamarkan di sana ditolak;
+// selain itu baris disamarkan sebelum dibandingkan agar nilai asli tidak ikut keluar
+fn apply_compare_mask(
+    mask: &MaskPolicy,
+    side: &mut CompareSide,
+    key_columns: &[String],
+    is_reconcile: bool,
+) -> poem::Result<()> {
+    let is_blocked = key_columns.iter().any(|column| mask.is_masked(column))
+        || (is_reconcile && side.headers.iter().any(|column| mask.is_masked(column)));
+    if is_blocked {
+        return Err(common::error_message(
+            StatusCode::FORBIDDEN,
+            "dataCompare.maskedColumn",
+        ));
+    }
+    for row in side.rows.iter_mut() {
+        mask.mask_row(row);
+    }
+    Ok(())
+}
+
+async fn compare_data(
+    conn: &mut PgConnection,
+    claims: &Claims,
+    source_database: CompareDatabase<'_>,
+    target_database: CompareDatabase<'_>,
+    entry: &EntryDataCompare,
+) -> poem::Result<DataCompareResult> {
+    let roles = claims.role.as_deref().unwrap_or_default();
+    let source_pool = source_database.pool;
+    let target_pool = target_database.pool;
+    let source_query = compare_source_query(
+        source_pool,
+        source_database.schema.as_deref(),
+        entry.source_table.as_deref(),
+        entry.source_query.as_deref(),
+    )?;
+    let target_query = compare_source_query(
+        target_pool,
+        target_database.schema.as_deref(),
+        entry.target_table.as_deref(),
+        entry.target_query.as_deref(),
+    )?;
+    let source_mask =
+        load_mask_policy(conn, source_database.ext_database_id, roles, &source_query)?;
+    let target_mask =
+        load_mask_policy(conn, target_database.ext_database_id, roles, &target_query)?;
+
+    let reconcile_table = if entry.is_reconcile == 1 {
+        let table = entry
+            .target_table
+            .as_deref()
+            .filter(|t| !t.trim().is_empty())
+            .ok_or_else(|| {
+                common::error_message(StatusCode::BAD_REQUEST, "dataCompare.targetTableRequired")
+            })?;
+        Some(qualify_table(
+            target_pool,
+            target_database.schema.as_deref(),
+            table,
+        ))
+    } else {
+        None
+    };
+
+    let source_context = ExecutionContext::new(
+        source_pool,
+        source_database.ext_database_id,
+        claims.id,
+        "compare",
+    );
+    let target_context = ExecutionContext::new(
+        target_pool,
+        target_database.ext_database_id,
+        claims.id,
+        "compare",
+    );
+    let mut source = fetch_compare_side(conn, &source_context, source_pool, &source_query).await?;
+    let mut target = fetch_compare_side(conn, &target_context, target_pool, &target_query).await?;
+    apply_compare_mask(
+        &source_mask,
+        &mut source,
+        &entry.key_columns,
+        reconcile_table.is_some(),
+    )?;
+    apply_compare_mask(
+        &target_mask,
+        &mut target,
+        &entry.key_columns,
+        reconcile_table.is_some(),
+    )?;
+
+    compare_sides(
+        &source,
+        &target,
+        &entry.key_columns,
+        reconcile_table.as_deref().map(|table| (table, target_pool)),
+    )
+}
+
+#[handler]
+pub async fn data_compare(
+    pool: poem::web::Data<&DbPool>,
+    jwt_auth: crate::auth::middleware::JwtAuth,
+    Path((ext_database_id, target_ext_database_id)): Path<(i64, i64)>,
+    Json(entry): Json<EntryDataCompare>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(ext_database_id)?;
+    validate_id(target_ext_database_id)?;
+
+    if let Err(e) = entry.validate() {
+        return Err(validation_error_response(e));
+    }
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    let source_schema = resolve_schema(conn, ext_database_id, entry.source_schema.clone())?;
+    let target_schema = resolve_schema(conn, target_ext_database_id, entry.target_schema.clone())?;
+
+    let (source_pool, source_tunnel, _, _) = get_external_pool(conn, ext_database_id).await?;
+
+    let result = match get_external_pool(conn, target_ext_database_id).await {
+        Ok((target_pool, target_tunnel, _, _)) => {
+            let result = compare_data(
+                conn,
+                &jwt_auth.claims,
+                CompareDatabase {
+                    ext_database_id,
+                    pool: &source_pool,
+                    schema: source_schema,
+                },
+                CompareDatabase {
+                    ext_database_id: target_ext_database_id,
+                    pool: &target_pool,
+                    schema: target_schema,
+                },
+                &entry,
+            )
+            .await;
+
+            if let Some(mut tunnel) = target_tunnel {
+                let _ = tunnel.kill().ok();
+            };
+            result
+        }
+        Err(e) => Err(e),
+    };
+
+    if let Some(mut tunnel) = source_tunnel {
+        let _ = tunnel.kill().ok();
+    };
+
+    Ok(Json(DataResponse { data: result? }))
+}
@@ -0,0 +1,857 @@
+use poem::web::{Json, Query};
+use poem::{IntoResponse, handler, http::StatusCode, web::Path};
+use regex::{Captures, Regex};
+use std::sync::LazyLock;
+
+use crate::database_pool::DatabasePool;
+use crate::db::DbPool;
+use crate::facades::external::database_object::{
+    column_definition_postgres, fetch_object_structure, list_schema_objects,
+};
+use crate::facades::external::database_query::{get_external_pool, resolve_schema};
+use crate::models::common::DataResponse;
+use crate::models::external::database_diff::{
+    ColumnDiff, DefinitionDiff, ObjectDiff, SchemaDiff, SchemaDiffParam,
+};
+use crate::models::external::database_object::{
+    ObjectColumn, ObjectConstraint, ObjectIndex, ObjectStructure,
+};
+use crate::utils::common::{self, validate_id};
+use crate::utils::database::{
+    quote_identifier_mysql, quote_identifier_postgres, quote_literal_mysql,
+};
+
+static DEFINER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"DEFINER=\S+\s").unwrap());
+
+struct DiffContext<'a> {
+    source_schema: &'a str,
+    target_schema: &'a str,
+    is_mysql: bool,
+    source_qualifier: Regex,
+    target_qualifier: Regex,
+}
+
+// Urutan fase menjaga dependensi: constraint lama dilepas dulu, foreign key dipasang setelah semua tabel ada
+#[derive(Default)]
+struct DiffScript {
+    drop_foreign_keys: Vec<String>,
+    drops: Vec<String>,
+    creates: Vec<String>,
+    alters: Vec<String>,
+    foreign_keys: Vec<String>,
+    routines: Vec<String>,
+    cleanups: Vec<String>,
+}
+
+// Prefix schema hanya cocok jika berdiri sendiri, jadi schema app tidak ikut mengubah myapp.x
+fn schema_qualifier(quoted_schema: &str) -> Regex {
+    Regex::new(&format!(
+        r#"(^|[^\w$"`.]){}\."#,
+        regex::escape(quoted_schema)
+    ))
+    .unwrap()
+}
+
+impl<'a> DiffContext<'a> {
+    fn new(source_schema: &'a str, target_schema: &'a str, is_mysql: bool) -> Self {
+        let quote = |name: &str| {
+            if is_mysql {
+                quote_identifier_mysql(name)
+            } else {
+                quote_identifier_postgres(name)
+            }
+        };
+        DiffContext {
+            source_schema,
+            target_schema,
+            is_mysql,
+            source_qualifier: schema_qualifier(&quote(source_schema)),
+            target_qualifier: schema_qualifier(&quote(target_schema)),
+        }
+    }
+
+    fn quote(&self, name: &str) -> String {
+        if self.is_mysql {
+            quote_identifier_mysql(name)
+        } else {
+            quote_identifier_postgres(name)
+        }
+    }
+
+    fn qualify(&self, name: &str) -> String {
+        format!("{}.{}", self.quote(self.target_schema), self.quote(name))
+    }
+
+    fn strip_schema(&self, text: &str, qualifier: &Regex) -> String {
+        let text = qualifier.replace_all(text, "$1");
+        DEFINER
+            .replace_all(&text, "")
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn retarget(&self, text: &str) -> String {
+        if self.source_schema == self.target_schema {
+            return text.to_string();
+        }
+        let target = format!("{}.", self.quote(self.target_schema));
+        self.source_qualifier
+            .replace_all(text, |caps: &Captures| format!("{}{}", &caps[1], target))
+            .into_owned()
+    }
+
+    fn same_definition(&self, source: &str, target: &str) -> bool {
+        self.strip_schema(source, &self.source_qualifier)
+            == self.strip_schema(target, &self.target_qualifier)
+    }
+
+    fn drop_object(&self, object_type: &str, name: &str, if_exists: bool) -> String {
+        format!(
+            "DROP {}{} {};",
+            object_type.to_uppercase(),
+            if if_exists { " IF EXISTS" } else { "" },
+            self.qualify(name)
+        )
+    }
+
+    fn default_sql(&self, default_value: &str) -> String {
+        if !self.is_mysql {
+            return self.retarget(default_value);
+        }
+
+        let upper = default_value.to_uppercase();
+        if default_value.parse::<f64>().is_ok()
+            || upper == "NULL"
+            || upper.starts_with("CURRENT_TIMESTAMP")
+            || default_value.starts_with('(')
+        {
+            default_value.to_string()
+        } else {
+            quote_literal_mysql(default_value)
+        }
+    }
+
+    fn column_definition(&self, column: &ObjectColumn) -> String {
+        if !self.is_mysql {
+            return self.retarget(&column_definition_postgres(column));
+        }
+
+        let mut definition = format!("{} {}", self.quote(&column.nm), column.data_type);
+        if let Some(default_value) = &column.default_value {
+            definition.push_str(&format!(" DEFAULT {}", self.default_sql(default_value)));
+        }
+        if !column.nullable {
+            definition.push_str(" NOT NULL");
+        }
+        definition
+    }
+
+    fn sequence_statement(&self, column: &ObjectColumn) -> Option<String> {
+        if column.is_generated {
+            return None;
+        }
+        let default_value = column.default_value.as_deref()?;
+        let start = default_value.find("nextval('")? + "nextval('".len();
+        let end = default_value[start..].find('\'')? + start;
+
+        Some(format!(
+            "CREATE SEQUENCE IF NOT EXISTS {};",
+            self.retarget(&default_value[start..end])
+        ))
+    }
+
+    fn add_constraint(&self, table: &str, constraint: &ObjectConstraint) -> String {
+        if self.is_mysql && constraint.constraint_type == "PRIMARY KEY" {
+            return format!(
+                "ALTER TABLE {} ADD {};",
+                self.qualify(table),
+                constraint.definition
+            );
+        }
+
+        format!(
+            "ALTER TABLE {} ADD CONSTRAINT {} {};",
+            self.qualify(table),
+            self.quote(&constraint.nm),
+            self.retarget(&constraint.definition)
+        )
+    }
+
+    fn drop_constraint(&self, table: &str, constraint: &ObjectConstraint) -> String {
+        let clause = match (self.is_mysql, constraint.constraint_type.as_str()) {
+            (false, _) => format!("DROP CONSTRAINT {}", self.quote(&constraint.nm)),
+            (true, "PRIMARY KEY") => String::from("DROP PRIMARY KEY"),
+            (true, "FOREIGN KEY") => format!("DROP FOREIGN KEY {}", self.quote(&constraint.nm)),
+            (true, "UNIQUE") => format!("DROP INDEX {}", self.quote(&constraint.nm)),
+            (true, _) => format!("DROP CHECK {}", self.quote(&constraint.nm)),
+        };
+        format!("ALTER TABLE {} {};", self.qualify(table), clause)
+    }
+
+    fn drop_index(&self, table: &str, index: &ObjectIndex) -> String {
+        if self.is_mysql {
+            format!(
+                "DROP INDEX {} ON {};",
+                self.quote(&index.nm),
+                self.qualify(table)
+            )
+        } else {
+            format!("DROP INDEX {};", self.qualify(&index.nm))
+        }
+    }
+
+    fn routine_statement(&self, ddl: &str) -> String {
+        let ddl = self.retarget(ddl.trim_end());
+        if self.is_mysql {
+            format!("DELIMITER $$\n{}$$\nDELIMITER ;", ddl.trim_end_matches(';'))
+        } else {
+            ddl
+        }
+    }
+}
+
+fn all_constraints(structure: &ObjectStructure) -> Vec<&ObjectConstraint> {
+    structure
+        .primary_key
+        .iter()
+        .chain(structure.unique_keys.iter())
+        .chain(structure.check_constraints.iter())
+        .chain(structure.foreign_keys.iter())
+        .collect()
+}
+
+// Index milik primary key atau unique constraint sudah ikut terbandingkan lewat constraint
+fn standalone_indexes(structure: &ObjectStructure) -> Vec<&ObjectIndex> {
+    let constraint_names: Vec<&str> = structure
+        .primary_key
+        .iter()
+        .chain(structure.unique_keys.iter())
+        .map(|c| c.nm.as_str())
+        .collect();
+
+    structure
+        .indexes
+        .iter()
+        .filter(|i| i.is_primary == 0 && !constraint_names.contains(&i.nm.as_str()))
+        .collect()
+}
+
+fn definition_diff(
+    nm: &str,
+    status: &str,
+    source: Option<&str>,
+    target: Option<&str>,
+) -> DefinitionDiff {
+    DefinitionDiff {
+        nm: nm.to_string(),
+        status: status.to_string(),
+        source_definition: source.map(String::from),
+        target_definition: target.map(String::from),
+    }
+}
+
+fn object_diff(
+    structure: &ObjectStructure,
+    status: &str,
+    source: Option<&str>,
+    target: Option<&str>,
+) -> ObjectDiff {
+    ObjectDiff {
+        nm: structure.nm.clone(),
+        object_type: structure.object_type.clone(),
+        status: status.to_string(),
+        columns: vec![],
+        constraints: vec![],
+        indexes: vec![],
+        source_definition: source.map(String::from),
+        target_definition: target.map(String::from),
+    }
+}
+
+fn create_table(ctx: &DiffContext, source: &ObjectStructure, script: &mut DiffScript) {
+    if ctx.is_mysql {
+        // SHOW CREATE TABLE sudah lengkap, trigger dipisah karena butuh DELIMITER
+        let mut ddl = source.ddl.clone();
+        for trigger in &source.triggers {
+            ddl = ddl.replace(&format!("{};\n", trigger.definition), "");
+        }
+        script.creates.push(ctx.retarget(ddl.trim_end()));
+        for trigger in &source.triggers {
+            script
+                .routines
+                .push(ctx.routine_statement(&format!("{};", trigger.definition)));
+        }
+        return;
+    }
+
+    let mut lines = Vec::new();
+    for column in &source.columns {
+        if let Some(sequence) = ctx.sequence_statement(column) {
+            script.creates.push(sequence);
+        }
+        lines.push(format!("    {}", ctx.column_definition(column)));
+    }
+    for constraint in all_constraints(source)
+        .into_iter()
+        .filter(|c| c.constraint_type != "FOREIGN KEY")
+    {
+        lines.push(format!(
+            "    CONSTRAINT {} {}",
+            ctx.quote(&constraint.nm),
+            ctx.retarget(&constraint.definition)
+        ));
+    }
+
+    script.creates.push(format!(
+        "CREATE TABLE {} (\n{}\n);",
+        ctx.qualify(&source.nm),
+        lines.join(",\n")
+    ));
+
+    for index in standalone_indexes(source) {
+        script
+            .creates
+            .push(format!("{};", ctx.retarget(&index.definition)));
+    }
+    for constraint in &source.foreign_keys {
+        script
+            .foreign_keys
+            .push(ctx.add_constraint(&source.nm, constraint));
+    }
+    for trigger in &source.triggers {
+        script
+            .routines
+            .push(format!("{};", ctx.retarget(&trigger.definition)));
+    }
+}
+
+// Ekspresi kolom generated tersimpan di default_value tapi bukan default kolom
+fn column_default(column: &ObjectColumn) -> Option<&String> {
+    column
+        .default_value
+        .as_ref()
+        .filter(|_| !column.is_generated)
+}
+
+fn diff_columns(
+    ctx: &DiffContext,
+    source: &ObjectStructure,
+    target: &ObjectStructure,
+    script: &mut DiffScript,
+) -> Vec<ColumnDiff> {
+    let table = ctx.qualify(&source.nm);
+    let mut diffs = Vec::new();
+
+    for source_col in &source.columns {
+        let target_col = target.columns.iter().find(|c| c.nm == source_col.nm);
+
+        let status = match target_col {
+            None => {
+                if let Some(sequence) = ctx.sequence_statement(source_col) {
+                    script.alters.push(sequence);
+                }
+                script.alters.push(format!(
+                    "ALTER TABLE {} ADD COLUMN {};",
+                    table,
+                    ctx.column_definition(source_col)
+                ));
+                "added"
+            }
+            Some(target_col) => {
+                let is_type_changed = !source_col
+                    .data_type
+                    .eq_ignore_ascii_case(&target_col.data_type);
+                let is_default_changed =
+                    match (column_default(source_col), column_default(target_col)) {
+                        (Some(s), Some(t)) => !ctx.same_definition(s, t),
+                        (s, t) => s.is_some() != t.is_some(),
+                    };
+                let is_nullable_changed = source_col.nullable != target_col.nullable;
+                let is_identity_changed =
+                    source_col.identity_generation != target_col.identity_generation;
+                let is_generated_changed = source_col.is_generated != target_col.is_generated
+                    || (source_col.is_generated
+                        && !ctx.same_definition(
+                            source_col.default_value.as_deref().unwrap_or_default(),
+                            target_col.default_value.as_deref().unwrap_or_default(),
+                        ));
+
+                if !is_type_changed
+                    && !is_default_changed
+                    && !is_nullable_changed
+                    && !is_identity_changed
+                    && !is_generated_changed
+                {
+                    continue;
+                }
+
+                if !ctx.is_mysql && source_col.is_generated && is_generated_changed {
+                    // Ekspresi generated tidak bisa diubah di tempat, kolom dibuat ulang
+                    script.alters.push(format!(
+                        "ALTER TABLE {} DROP COLUMN {};",
+                        table,
+                        ctx.quote(&source_col.nm)
+                    ));
+                    script.alters.push(format!(
+                        "ALTER TABLE {} ADD COLUMN {};",
+                        table,
+                        ctx.column_definition(source_col)
+                    ));
+                } else if ctx.is_mysql {
+                    script.alters.push(format!(
+                        "ALTER TABLE {} MODIFY COLUMN {};",
+                        table,
+                        ctx.column_definition(source_col)
+                    ));
+                } else {
+                    let column = ctx.quote(&source_col.nm);
+                    // Kolom generated di target dijadikan kolom biasa tanpa kehilangan data
+                    if is_generated_changed {
+                        script.alters.push(format!(
+                            "ALTER TABLE {} ALTER COLUMN {} DROP EXPRESSION;",
+                            table, column
+                        ));
+                    }
+                    if is_type_changed {
+                        script.alters.push(format!(
+                            "ALTER TABLE {0} ALTER COLUMN {1} TYPE {2} USING {1}::{2};",
+                            table, column, source_col.data_type
+                        ));
+                    }
+                    if is_identity_changed && source_col.identity_generation.is_none() {
+                        script.alters.push(format!(
+                            "ALTER TABLE {} ALTER COLUMN {} DROP IDENTITY IF EXISTS;",
+                            table, column
+                        ));
+                    }
+                    // Default lama dilepas lebih dulu karena identity tidak boleh punya default
+                    if is_default_changed {
+                        script.alters.push(match column_default(source_col) {
+                            Some(default_value) => format!(
+                                "ALTER TABLE {} ALTER COLUMN {} SET DEFAULT {};",
+                                table,
+                                column,
+                                ctx.default_sql(default_value)
+                            ),
+                            None => {
+                                format!(
+                                    "ALTER TABLE {} ALTER COLUMN {} DROP DEFAULT;",
+                                    table, column
+                                )
+                            }
+                        });
+                    }
+                    if is_identity_changed && let Some(generation) = &source_col.identity_generation
+                    {
+                        script.alters.push(match target_col.identity_generation {
+                            Some(_) => format!(
+                                "ALTER TABLE {} ALTER COLUMN {} SET GENERATED {};",
+                                table, column, generation
+                            ),
+                            None => format!(
+                                "ALTER TABLE {} ALTER COLUMN {} ADD GENERATED {} AS IDENTITY;",
+                                table, column, generation
+                            ),
+                        });
+                    }
+                    if is_nullable_changed {
+                        script.alters.push(format!(
+                            "ALTER TABLE {} ALTER COLUMN {} {} NOT NULL;",
+                            table,
+                            column,
+                            if source_col.nullable { "DROP" } else { "SET" }
+                        ));
+                    }
+                }
+                "changed"
+            }
+        };
+
+        diffs.push(ColumnDiff {
+            nm: source_col.nm.clone(),
+            status: status.to_string(),
+            source_data_type: Some(source_col.data_type.clone()),
+            target_data_type: target_col.map(|c| c.data_type.clone()),
+            source_nullable: Some(source_col.nullable),
+            target_nullable: target_col.map(|c| c.nullable),
+            source_default_value: source_col.default_value.clone(),
+            target_default_value: target_col.and_then(|c| c.default_value.clone()),
+            source_identity_generation: source_col.identity_generation.clone(),
+            target_identity_generation: target_col.and_then(|c| c.identity_generation.clone()),
+            source_generated: Some(source_col.is_generated),
+            target_generated: target_col.map(|c| c.is_generated),
+        });
+    }
+
+    for target_col in &target.columns {
+        if source.columns.iter().any(|c| c.nm == target_col.nm) {
+            continue;
+        }
+
+        script.cleanups.push(format!(
+            "ALTER TABLE {} DROP COLUMN {};",
+            table,
+            ctx.quote(&target_col.nm)
+        ));
+        diffs.push(ColumnDiff {
+            nm: target_col.nm.clone(),
+            status: "removed".to_string(),
+            source_data_type: None,
+            target_data_type: Some(target_col.data_type.clone()),
+            source_nullable: None,
+            target_nullable: Some(target_col.nullable),
+            source_default_value: None,
+            target_default_value: target_col.default_value.clone(),
+            source_identity_generation: None,
+            target_identity_generation: target_col.identity_generation.clone(),
+            source_generated: None,
+            target_generated: Some(target_col.is_generated),
+        });
+    }
+
+    diffs
+}
+
+fn diff_constraints(
+    ctx: &DiffContext,
+    source: &ObjectStructure,
+    target: &ObjectStructure,
+    script: &mut DiffScript,
+) -> Vec<DefinitionDiff> {
+    let source_constraints = all_constraints(source);
+    let target_constraints = all_constraints(target);
+    let mut diffs = Vec::new();
+
+    let drop_con = |script: &mut DiffScript, constraint: &ObjectConstraint| {
+        let statement = ctx.drop_constraint(&target.nm, constraint);
+        if constraint.constraint_type == "FOREIGN KEY" {
+            script.drop_foreign_keys.push(statement);
+        } else {
+            script.drops.push(statement);
+        }
+    };
+    let add_con = |script: &mut DiffScript, constraint: &ObjectConstraint| {
+        let statement = ctx.add_constraint(&source.nm, constraint);
+        if constraint.constraint_type == "FOREIGN KEY" {
+            script.foreign_keys.push(statement);
+        } else {
+            script.alters.push(statement);
+        }
+    };
+
+    for source_con in &source_constraints {
+        match target_constraints.iter().find(|c| c.nm == source_con.nm) {
+            None => {
+                add_con(script, source_con);
+                diffs.push(definition_diff(
+                    &source_con.nm,
+                    "added",
+                    Some(&source_con.definition),
+                    None,
+                ));
+            }
+            Some(target_con)
+                if !ctx.same_definition(&source_con.definition, &target_con.definition) =>
+            {
+                drop_con(script, target_con);
+                add_con(script, source_con);
+                diffs.push(definition_diff(
+                    &source_con.nm,
+                    "changed",
+                    Some(&source_con.definition),
+                    Some(&target_con.definition),
+                ));
+            }
+            Some(_) => {}
+        }
+    }
+
+    for target_con in &target_constraints {
+        if !source_constraints.iter().any(|c| c.nm == target_con.nm) {
+            drop_con(script, target_con);
+            diffs.push(definition_diff(
+                &target_con.nm,
+                "removed",
+                None,
+                Some(&target_con.definition),
+            ));
+        }
+    }
+
+    diffs
+}
+
+fn diff_indexes(
+    ctx: &DiffContext,
+    source: &ObjectStructure,
+    target: &ObjectStructure,
+    script: &mut DiffScript,
+) -> Vec<DefinitionDiff> {
+    let source_indexes = standalone_indexes(source);
+    let target_indexes = standalone_indexes(target);
+    let mut diffs = Vec::new();
+
+    for source_idx in &source_indexes {
+        match target_indexes.iter().find(|i| i.nm == source_idx.nm) {
+            None => {
+                script
+                    .alters
+                    .push(format!("{};", ctx.retarget(&source_idx.definition)));
+                diffs.push(definition_diff(
+                    &source_idx.nm,
+                    "added",
+                    Some(&source_idx.definition),
+                    None,
+                ));
+            }
+            // Definisi ikut dibandingkan agar perubahan predicate partial index dan ekspresi terdeteksi
+            Some(target_idx)
+                if source_idx.columns != target_idx.columns
+                    || source_idx.is_unique != target_idx.is_unique
+                    || source_idx.index_type != target_idx.index_type
+                    || !ctx.same_definition(&source_idx.definition, &target_idx.definition) =>
+            {
+                script.drops.push(ctx.drop_index(&target.nm, target_idx));
+                script
+                    .alters
+                    .push(format!("{};", ctx.retarget(&source_idx.definition)));
+                diffs.push(definition_diff(
+                    &source_idx.nm,
+                    "changed",
+                    Some(&source_idx.definition),
+                    Some(&target_idx.definition),
+                ));
+            }
+            Some(_) => {}
+        }
+    }
+
+    for target_idx in &target_indexes {
+        if !source_indexes.iter().any(|i| i.nm == target_idx.nm) {
+            script.drops.push(ctx.drop_index(&target.nm, target_idx));
+            diffs.push(definition_diff(
+                &target_idx.nm,
+                "removed",
+                None,
+                Some(&target_idx.definition),
+            ));
+        }
+    }
+
+    diffs
+}
+
+fn diff_schema(
+    ctx: &DiffContext,
+    source: &[ObjectStructure],
+    target: &[ObjectStructure],
+) -> (Vec<ObjectDiff>, String) {
+    let mut script = DiffScript::default();
+    let mut diffs = Vec::new();
+
+    for source_obj in source {
+        let target_obj = target
+            .iter()
+            .find(|t| t.nm == source_obj.nm && t.object_type == source_obj.object_type);
+
+        match (target_obj, source_obj.object_type.as_str()) {
+            (None, "table") => {
+                create_table(ctx, source_obj, &mut script);
+                diffs.push(object_diff(
+                    source_obj,
+                    "added",
+                    Some(&source_obj.ddl),
+                    None,
+                ));
+            }
+            (None, _) => {
+                script.routines.push(ctx.routine_statement(&source_obj.ddl));
+                diffs.push(object_diff(
+                    source_obj,
+                    "added",
+                    Some(&source_obj.ddl),
+                    None,
+                ));
+            }
+            (Some(target_obj), "table") => {
+                let columns = diff_columns(ctx, source_obj, target_obj, &mut script);
+                let constraints = diff_constraints(ctx, source_obj, target_obj, &mut script);
+                let indexes = diff_indexes(ctx, source_obj, target_obj, &mut script);
+
+                if !columns.is_empty() || !constraints.is_empty() || !indexes.is_empty() {
+                    diffs.push(ObjectDiff {
+                        columns,
+                        constraints,
+                        indexes,
+                        ..object_diff(source_obj, "changed", None, None)
+                    });
+                }
+            }
+            (Some(target_obj), object_type) => {
+                if ctx.same_definition(&source_obj.ddl, &target_obj.ddl) {
+                    continue;
+                }
+                script
+                    .drops
+                    .push(ctx.drop_object(object_type, &target_obj.nm, true));
+                script.routines.push(ctx.routine_statement(&source_obj.ddl));
+                diffs.push(object_diff(
+                    source_obj,
+                    "changed",
+                    Some(&source_obj.ddl),
+                    Some(&target_obj.ddl),
+                ));
+            }
+        }
+    }
+
+    for target_obj in target {
+        if source
+            .iter()
+            .any(|s| s.nm == target_obj.nm && s.object_type == target_obj.object_type)
+        {
+            continue;
+        }
+
+        let statement = ctx.drop_object(&target_obj.object_type, &target_obj.nm, false);
+        if target_obj.object_type == "table" {
+            script.cleanups.push(statement);
+        } else {
+            script.drops.push(statement);
+        }
+        diffs.push(object_diff(
+            target_obj,
+            "removed",
+            None,
+            Some(&target_obj.ddl),
+        ));
+    }
+
+    let statements: Vec<String> = [
+        script.drop_foreign_keys,
+        script.drops,
+        script.creates,
+        script.alters,
+        script.foreign_keys,
+        script.routines,
+        script.cleanups,
+    ]
+    .concat();
+
+    if statements.is_empty() {
+        return (diffs, String::new());
+    }
+
+    let (header, footer) = if ctx.is_mysql {
+        (
+            format!(
+                "USE {};\nSET FOREIGN_KEY_CHECKS = 0;",
+                quote_identifier_mysql(ctx.target_schema)
+            ),
+            "SET FOREIGN_KEY_CHECKS = 1;",
+        )
+    } else {
+        (String::from("BEGIN;"), "COMMIT;")
+    };
+
+    let script = format!(
+        "-- Source schema: {}\n-- Target schema: {}\n{}\n\n{}\n\n{}\n",
+        ctx.source_schema,
+        ctx.target_schema,
+        header,
+        statements.join("\n\n"),
+        footer
+    );
+
+    (diffs, script)
+}
+
+async fn fetch_schema_structures(
+    ext_pool: &DatabasePool,
+    schema: Option<&str>,
+) -> poem::Result<(String, Vec<ObjectStructure>)> {
+    let (schema_name, objects) = list_schema_objects(ext_pool, schema).await?;
+
+    let mut structures = Vec::new();
+    for (name, _) in objects {
+        structures.push(fetch_object_structure(ext_pool, Some(&schema_name), &name).await?);
+    }
+
+    Ok((schema_name, structures))
+}
+
+async fn compare_schemas(
+    source_pool: &DatabasePool,
+    target_pool: &DatabasePool,
+    source_schema: Option<String>,
+    target_schema: Option<String>,
+) -> poem::Result<SchemaDiff> {
+    let is_mysql = match (source_pool, target_pool) {
+        (DatabasePool::Postgres(_), DatabasePool::Postgres(_)) => false,
+        (DatabasePool::MySql(_), DatabasePool::MySql(_)) => true,
+        _ => {
+            return Err(common::error_message(
+                StatusCode::BAD_REQUEST,
+                "schemaDiff.databaseTypeMismatch",
+            ));
+        }
+    };
+
+    let (source_schema, source_objects) =
+        fetch_schema_structures(source_pool, source_schema.as_deref()).await?;
+    let (target_schema, target_objects) =
+        fetch_schema_structures(target_pool, target_schema.as_deref()).await?;
+
+    let ctx = DiffContext::new(&source_schema, &target_schema, is_mysql);
+    let (objects, script) = diff_schema(&ctx, &source_objects, &target_objects);
+
+    Ok(SchemaDiff {
+        source_schema: source_schema.clone(),
+        target_schema: target_schema.clone(),
+        objects,
+        script,
+    })
+}
+
+#[handler]
+pub async fn schema_diff(
+    pool: poem::web::Data<&DbPool>,
+    _: crate::auth::middleware::JwtAuth,
+    Path((ext_database_id, target_ext_database_id)): Path<(i64, i64)>,
+    Query(param): Query<SchemaDiffParam>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(ext_database_id)?;
+    validate_id(target_ext_database_id)?;
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    let source_schema = resolve_schema(conn, ext_database_id, param.schema)?;
+    let target_schema = resolve_schema(conn, target_ext_database_id, param.target_schema)?;
+
+    let (source_pool, source_tunnel, _, _) = get_external_pool(conn, ext_database_id).await?;
+
+    let result = match get_external_pool(conn, target_ext_database_id).await {
+        Ok((target_pool, target_tunnel, _, _)) => {
+            let result =
+                compare_schemas(&source_pool, &target_pool, source_schema, target_schema).await;
+
+            if let Some(mut tunnel) = target_tunnel {
+                let _ = tunnel.kill().ok();
+            };
+            result
+        }
+        Err(e) => Err(e),
+    };
+
+    if let Some(mut tunnel) = source_tunnel {
+        let _ = tunnel.kill().ok();
+    };
+
+    Ok(Json(DataResponse { data: result? }))
+}
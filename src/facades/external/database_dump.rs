@@ -0,0 +1,623 @@
+use std::collections::HashSet;
+use std::sync::LazyLock;
+use std::time::Instant;
+
+use chrono::Utc;
+use diesel::PgConnection;
+use poem::web::Json;
+use poem::{Body, IntoResponse, handler, http::StatusCode, web::Path};
+use regex::Regex;
+use serde_json::{Map, Value};
+use sqlx::Row;
+use tokio::io;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::{Stream, StreamExt};
+use tokio_util::bytes::Bytes;
+use validator::Validate;
+
+use crate::database_pool::DatabasePool;
+use crate::db::DbPool;
+use crate::facades::external::database_execution::{ExecutionContext, record_execution_pooled};
+use crate::facades::external::database_mask::{MaskPolicy, load_mask_policy};
+use crate::facades::external::database_object::{
+    column_definition_postgres, fetch_object_structure, show_create_statement,
+};
+use crate::facades::external::database_query::{get_external_pool, resolve_schema};
+use crate::models::external::database_dump::EntryDatabaseDump;
+use crate::models::external::database_object::{ObjectConstraint, ObjectStructure};
+use crate::utils::common::{self, validate_id, validation_error_response};
+use crate::utils::database::{
+    SqlStatementKind, extract_columns_info_mysql, extract_columns_info_postgres,
+    parse_statement_info, quote_identifier_mysql, quote_identifier_postgres,
+    quote_literal_postgres, row_to_json_mysql, row_to_json_postgres, rows_to_insert_query_string,
+    sql_dialect,
+};
+
+const DUMP_CHUNK_SIZE: usize = 64 * 1024;
+const DUMP_DEFAULT_LINE_PER_ACTION: i16 = 100;
+
+static NEXTVAL_DEFAULT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"nextval\('((?:[^']|'')+)'(?:::regclass)?\)").unwrap());
+
+struct DumpSource {
+    structure: ObjectStructure,
+    // Nama tabel di skrip; MySQL tanpa schema seperti mysqldump
+    target: String,
+    query: String,
+    create_ddl: String,
+    mask: MaskPolicy,
+}
+
+struct DumpWriter {
+    tx: mpsc::Sender<Result<Bytes, io::Error>>,
+    buffer: String,
+}
+
+impl DumpWriter {
+    fn push(&mut self, text: &str) {
+        self.buffer.push_str(text);
+    }
+
+    async fn flush(&mut self, is_forced: bool) -> Result<(), io::Error> {
+        if self.buffer.is_empty() || (!is_forced && self.buffer.len() < DUMP_CHUNK_SIZE) {
+            return Ok(());
+        }
+        let chunk = Bytes::from(std::mem::take(&mut self.buffer));
+        self.tx
+            .send(Ok(chunk))
+            .await
+            .map_err(|_| io::Error::other("Dump receiver closed"))
+    }
+}
+
+fn invalid(key: &str) -> poem::Error {
+    common::error_message(StatusCode::BAD_REQUEST, key)
+}
+
+fn qualified_name(structure: &ObjectStructure, is_mysql: bool) -> String {
+    if is_mysql {
+        format!(
+            "{}.{}",
+            quote_identifier_mysql(&structure.schema),
+            quote_identifier_mysql(&structure.nm)
+        )
+    } else {
+        format!(
+            "{}.{}",
+            quote_identifier_postgres(&structure.schema),
+            quote_identifier_postgres(&structure.nm)
+        )
+    }
+}
+
+// regclass hanya menyertakan schema jika tabel tidak terlihat dari search_path
+fn is_referenced(fk: &ObjectConstraint, target: &ObjectStructure, is_mysql: bool) -> bool {
+    let Some(referenced) = fk.referenced_table.as_deref() else {
+        return false;
+    };
+    if is_mysql {
+        return referenced == target.nm;
+    }
+    referenced == quote_identifier_postgres(&target.nm)
+        || referenced == qualified_name(target, false)
+}
+
+// Tabel induk didahulukan, siklus foreign key diputus mengikuti urutan permintaan
+fn dependency_order(mut remaining: Vec<DumpSource>, is_mysql: bool) -> Vec<DumpSource> {
+    let mut ordered = Vec::new();
+    while !remaining.is_empty() {
+        let position = remaining
+            .iter()
+            .position(|source| {
+                source.structure.foreign_keys.iter().all(|fk| {
+                    remaining.iter().all(|other| {
+                        std::ptr::eq(other, source)
+                            || !is_referenced(fk, &other.structure, is_mysql)
+                    })
+                })
+            })
+            .unwrap_or(0);
+        ordered.push(remaining.remove(position));
+    }
+    ordered
+}
+
+fn always_identity_columns(structure: &ObjectStructure) -> Vec<String> {
+    structure
+        .columns
+        .iter()
+        .filter(|column| column.identity_generation.as_deref() == Some("ALWAYS"))
+        .map(|column| quote_identifier_postgres(&column.nm))
+        .collect()
+}
+
+fn sequence_defaults(structure: &ObjectStructure) -> Vec<(String, String)> {
+    structure
+        .columns
+        .iter()
+        .filter(|column| !column.is_generated)
+        .filter_map(|column| {
+            let caps = NEXTVAL_DEFAULT.captures(column.default_value.as_deref()?)?;
+            Some((caps[1].replace("''", "'"), column.nm.clone()))
+        })
+        .collect()
+}
+
+// Constraint dan index dipasang setelah data agar proses insert tidak terhambat
+fn create_table_postgres(structure: &ObjectStructure) -> String {
+    let mut ddl = String::new();
+    for (sequence, _) in sequence_defaults(structure) {
+        ddl.push_str(&format!("CREATE SEQUENCE IF NOT EXISTS {};\n", sequence));
+    }
+
+    let lines: Vec<String> = structure
+        .columns
+        .iter()
+        .map(|column| format!("    {}", column_definition_postgres(column)))
+        .collect();
+
+    ddl.push_str(&format!(
+        "CREATE TABLE {} (\n{}\n);\n",
+        qualified_name(structure, false),
+        lines.join(",\n")
+    ));
+    ddl
+}
+
+fn constraint_ddl_postgres(source: &DumpSource) -> String {
+    let structure = &source.structure;
+    let mut ddl = String::new();
+    let constraints = structure
+        .primary_key
+        .iter()
+        .chain(&structure.unique_keys)
+        .chain(&structure.check_constraints);
+    for constraint in constraints.clone() {
+        ddl.push_str(&format!(
+            "ALTER TABLE {} ADD CONSTRAINT {} {};\n",
+            source.target,
+            quote_identifier_postgres(&constraint.nm),
+            constraint.definition
+        ));
+    }
+
+    // Index milik constraint sudah dibuat oleh constraint itu sendiri
+    let constraint_names: HashSet<&str> = constraints.map(|c| c.nm.as_str()).collect();
+    for index in &structure.indexes {
+        if !constraint_names.contains(index.nm.as_str()) {
+            ddl.push_str(&format!("{};\n", index.definition));
+        }
+    }
+    ddl
+}
+
+fn foreign_key_ddl_postgres(source: &DumpSource, sources: &[DumpSource]) -> String {
+    let mut ddl = String::new();
+    for fk in &source.structure.foreign_keys {
+        if sources
+            .iter()
+            .any(|other| is_referenced(fk, &other.structure, false))
+        {
+            ddl.push_str(&format!(
+                "ALTER TABLE {} ADD CONSTRAINT {} {};\n",
+                source.target,
+                quote_identifier_postgres(&fk.nm),
+                fk.definition
+            ));
+        } else {
+            ddl.push_str(&format!(
+                "-- Foreign key {} skipped, {} is not part of this dump\n",
+                fk.nm,
+                fk.referenced_table.as_deref().unwrap_or_default()
+            ));
+        }
+    }
+    ddl
+}
+
+async fn send_table_rows<R, S>(
+    mut rows: S,
+    source: &DumpSource,
+    number_line_per_action: i16,
+    to_json: fn(&R) -> Map<String, Value>,
+    to_columns: fn(&[R]) -> Vec<Value>,
+    quote: fn(&str) -> String,
+    writer: &mut DumpWriter,
+) -> Result<u64, io::Error>
+where
+    R: Row,
+    S: Stream<Item = Result<R, sqlx::Error>> + Unpin,
+{
+    let mut columns: Option<(Vec<String>, Vec<Value>, HashSet<String>)> = None;
+    let mut batch = Vec::new();
+    let mut row_count = 0;
+
+    while let Some(row) = rows.next().await {
+        let row = row.map_err(|e| {
+            eprintln!("Query error: {}", e);
+            io::Error::other(e.to_string())
+        })?;
+
+        // Nama kolom dikutip karena dipakai langsung sebagai daftar kolom INSERT
+        let (headers, columns, nulled) = columns.get_or_insert_with(|| {
+            let mut columns = to_columns(std::slice::from_ref(&row));
+            // Nilai samaran tidak bisa di-restore ke kolom non-teks, kolom tersebut ditulis NULL
+            let nulled = columns
+                .iter()
+                .filter(|column| column.get("logicalType").and_then(Value::as_str) != Some("text"))
+                .filter_map(|column| column.get("name").and_then(Value::as_str))
+                .filter(|name| source.mask.is_masked(name))
+                .map(String::from)
+                .collect();
+            source.mask.mask_columns(&mut columns);
+            for column in columns.iter_mut() {
+                if let Some(Value::String(name)) = column.get_mut("name") {
+                    *name = quote(name);
+                }
+            }
+            let headers = columns
+                .iter()
+                .filter_map(|column| column.get("name").and_then(Value::as_str))
+                .map(String::from)
+                .collect();
+            (headers, columns, nulled)
+        });
+
+        row_count += 1;
+        let mut map = to_json(&row);
+        source.mask.mask_row(&mut map);
+        for name in nulled.iter() {
+            map.insert(name.clone(), Value::Null);
+        }
+        batch.push(Value::Object(
+            map.into_iter()
+                .map(|(name, value)| (quote(&name), value))
+                .collect(),
+        ));
+
+        if batch.len() >= number_line_per_action as usize {
+            writer.push(&rows_to_insert_query_string(
+                &source.target,
+                1,
+                number_line_per_action,
+                std::mem::take(&mut batch),
+                headers.clone(),
+                columns,
+            ));
+            writer.flush(false).await?;
+        }
+    }
+
+    if let Some((headers, columns, _)) = columns
+        && !batch.is_empty()
+    {
+        writer.push(&rows_to_insert_query_string(
+            &source.target,
+            1,
+            number_line_per_action,
+            batch,
+            headers,
+            &columns,
+        ));
+    }
+
+    Ok(row_count)
+}
+
+async fn write_dump(
+    db_pool: &DbPool,
+    context: &ExecutionContext,
+    ext_pool: &DatabasePool,
+    sources: &[DumpSource],
+    entry: &EntryDatabaseDump,
+    writer: &mut DumpWriter,
+) -> Result<(), io::Error> {
+    let is_mysql = matches!(ext_pool, DatabasePool::MySql(_));
+    let number_line_per_action = entry
+        .number_line_per_action
+        .unwrap_or(DUMP_DEFAULT_LINE_PER_ACTION);
+
+    writer.push(&format!(
+        "-- Dump of {} table(s) from schema {}\n-- Generated at {} UTC\n",
+        sources.len(),
+        sources
+            .first()
+            .map(|source| source.structure.schema.as_str())
+            .unwrap_or_default(),
+        Utc::now().format("%Y-%m-%d %H:%M:%S")
+    ));
+    // Literal string hanya di-escape dengan menggandakan kutip sehingga backslash harus literal
+    if !is_mysql {
+        writer.push("\nSET standard_conforming_strings = on;\n");
+    }
+    if is_mysql {
+        writer.push(
+            "\nSET NAMES utf8mb4;\nSET FOREIGN_KEY_CHECKS = 0;\nSET SESSION sql_mode = CONCAT_WS(',', NULLIF(@@SESSION.sql_mode, ''), 'NO_BACKSLASH_ESCAPES', 'NO_AUTO_VALUE_ON_ZERO');\n",
+        );
+    }
+
+    if entry.is_data_only != 1 {
+        for source in sources {
+            writer.push(&format!("\n-- Structure of {}\n", source.target));
+            writer.push(&source.create_ddl);
+        }
+    }
+
+    if entry.is_schema_only != 1 {
+        for source in sources {
+            writer.push(&format!("\n-- Data of {}\n", source.target));
+            // Identity ALWAYS menolak nilai eksplisit, sementara dilonggarkan selama data dimuat
+            let identity_always = always_identity_columns(&source.structure);
+            for column in &identity_always {
+                writer.push(&format!(
+                    "ALTER TABLE {} ALTER COLUMN {} SET GENERATED BY DEFAULT;\n",
+                    source.target, column
+                ));
+            }
+            let dt_started = Instant::now();
+            let sent = match ext_pool {
+                DatabasePool::Postgres(pg_pool) => {
+                    let rows = sqlx::query(&source.query).fetch(pg_pool);
+                    send_table_rows(
+                        rows,
+                        source,
+                        number_line_per_action,
+                        row_to_json_postgres,
+                        extract_columns_info_postgres,
+                        quote_identifier_postgres,
+                        writer,
+                    )
+                    .await
+                }
+                DatabasePool::MySql(my_pool) => {
+                    let rows = sqlx::query(&source.query).fetch(my_pool);
+                    send_table_rows(
+                        rows,
+                        source,
+                        number_line_per_action,
+                        row_to_json_mysql,
+                        extract_columns_info_mysql,
+                        quote_identifier_mysql,
+                        writer,
+                    )
+                    .await
+                }
+            };
+            record_execution_pooled(
+                db_pool,
+                context,
+                &source.query,
+                dt_started,
+                sent.as_ref()
+                    .map(|rows| Some(*rows))
+                    .map_err(|e| e.to_string()),
+            );
+            sent?;
+
+            if !is_mysql {
+                for column in source
+                    .structure
+                    .columns
+                    .iter()
+                    .filter(|column| column.identity_generation.is_some())
+                {
+                    let quoted = quote_identifier_postgres(&column.nm);
+                    writer.push(&format!(
+                        "SELECT pg_catalog.setval(pg_catalog.pg_get_serial_sequence({}, {}), COALESCE(MAX({}), 1), MAX({}) IS NOT NULL) FROM {};\n",
+                        quote_literal_postgres(&source.target),
+                        quote_literal_postgres(&column.nm),
+                        quoted,
+                        quoted,
+                        source.target
+                    ));
+                }
+                for column in &identity_always {
+                    writer.push(&format!(
+                        "ALTER TABLE {} ALTER COLUMN {} SET GENERATED ALWAYS;\n",
+                        source.target, column
+                    ));
+                }
+                for (sequence, column) in sequence_defaults(&source.structure) {
+                    let column = quote_identifier_postgres(&column);
+                    writer.push(&format!(
+                        "SELECT pg_catalog.setval({}, COALESCE(MAX({}), 1), MAX({}) IS NOT NULL) FROM {};\n",
+                        quote_literal_postgres(&sequence),
+                        column,
+                        column,
+                        source.target
+                    ));
+                }
+            }
+            writer.flush(false).await?;
+        }
+    }
+
+    if entry.is_data_only != 1 && !is_mysql {
+        writer.push("\n-- Constraints and indexes\n");
+        for source in sources {
+            writer.push(&constraint_ddl_postgres(source));
+        }
+        writer.push("\n-- Foreign keys\n");
+        for source in sources {
+            writer.push(&foreign_key_ddl_postgres(source, sources));
+        }
+    }
+
+    if is_mysql {
+        writer.push("\nSET FOREIGN_KEY_CHECKS = 1;\n");
+    }
+
+    writer.flush(true).await
+}
+
+async fn prepare_sources(
+    conn: &mut PgConnection,
+    ext_pool: &DatabasePool,
+    ext_database_id: i64,
+    schema: Option<&str>,
+    entry: &EntryDatabaseDump,
+    roles: &[i16],
+) -> poem::Result<Vec<DumpSource>> {
+    let is_mysql = matches!(ext_pool, DatabasePool::MySql(_));
+    let dialect = sql_dialect(is_mysql);
+    let mut sources = Vec::new();
+
+    for table in &entry.tables {
+        let structure = fetch_object_structure(ext_pool, schema, &table.nm).await?;
+        if structure.object_type != "table" {
+            return Err(invalid("dump.invalidTable"));
+        }
+
+        let qualified = qualified_name(&structure, is_mysql);
+        // Kolom generated dihitung ulang oleh database tujuan sehingga tidak ikut di INSERT
+        let select_list = if structure.columns.iter().any(|column| column.is_generated) {
+            structure
+                .columns
+                .iter()
+                .filter(|column| !column.is_generated)
+                .map(|column| {
+                    if is_mysql {
+                        quote_identifier_mysql(&column.nm)
+                    } else {
+                        quote_identifier_postgres(&column.nm)
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        } else {
+            String::from("*")
+        };
+        let query = match table.filter.as_deref().map(str::trim) {
+            Some(filter) if !filter.is_empty() => {
+                format!("SELECT {} FROM {} WHERE {}", select_list, qualified, filter)
+            }
+            _ => format!("SELECT {} FROM {}", select_list, qualified),
+        };
+        // Filter harus tetap satu SELECT agar tidak bisa menyisipkan statement lain
+        let is_select = parse_statement_info(&query, dialect.as_ref())
+            .is_ok_and(|statement| statement.kind == SqlStatementKind::Select);
+        if !is_select {
+            return Err(invalid("dump.invalidFilter"));
+        }
+
+        let create_ddl = match ext_pool {
+            DatabasePool::Postgres(_) => create_table_postgres(&structure),
+            DatabasePool::MySql(my_pool) => {
+                let row = sqlx::query(&format!("SHOW CREATE TABLE {}", qualified))
+                    .fetch_one(my_pool)
+                    .await
+                    .and_then(|row| show_create_statement(&row, 1))
+                    .map_err(|e| {
+                        eprintln!("Query error: {}", e);
+                        poem::Error::from_status(StatusCode::INTERNAL_SERVER_ERROR)
+                    })?;
+                format!("{};\n", row)
+            }
+        };
+
+        sources.push(DumpSource {
+            mask: load_mask_policy(conn, ext_database_id, roles, &query)?,
+            target: if is_mysql {
+                quote_identifier_mysql(&structure.nm)
+            } else {
+                qualified
+            },
+            structure,
+            query,
+            create_ddl,
+        });
+    }
+
+    Ok(dependency_order(sources, is_mysql))
+}
+
+#[handler]
+pub async fn database_dump(
+    pool: poem::web::Data<&DbPool>,
+    jwt_auth: crate::auth::middleware::JwtAuth,
+    Path(ext_database_id): Path<i64>,
+    Json(entry): Json<EntryDatabaseDump>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(ext_database_id)?;
+
+    if let Err(e) = entry.validate() {
+        return Err(validation_error_response(e));
+    }
+    if entry.is_schema_only == 1 && entry.is_data_only == 1 {
+        return Err(invalid("dump.conflictingOption"));
+    }
+    let mut table_names = HashSet::new();
+    if !entry
+        .tables
+        .iter()
+        .all(|table| table_names.insert(&table.nm))
+    {
+        return Err(invalid("dump.duplicateTable"));
+    }
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    let schema = resolve_schema(conn, ext_database_id, entry.schema.clone())?;
+    let (ext_pool, tunnel, _, _) = get_external_pool(conn, ext_database_id).await?;
+
+    let sources = match prepare_sources(
+        conn,
+        &ext_pool,
+        ext_database_id,
+        schema.as_deref(),
+        &entry,
+        jwt_auth.claims.role.as_deref().unwrap_or_default(),
+    )
+    .await
+    {
+        Ok(sources) => sources,
+        Err(e) => {
+            if let Some(mut tunnel) = tunnel {
+                let _ = tunnel.kill().ok();
+            };
+            return Err(e);
+        }
+    };
+
+    let context = ExecutionContext::new(&ext_pool, ext_database_id, jwt_auth.claims.id, "dump");
+    let db_pool = pool.0.clone();
+    let (tx, mut rx) = mpsc::channel::<Result<Bytes, io::Error>>(16);
+
+    tokio::spawn(async move {
+        let mut writer = DumpWriter {
+            tx: tx.clone(),
+            buffer: String::new(),
+        };
+        if let Err(e) =
+            write_dump(&db_pool, &context, &ext_pool, &sources, &entry, &mut writer).await
+        {
+            let _ = tx.send(Err(e)).await;
+        }
+
+        // Tunnel baru ditutup setelah seluruh tabel selesai dibaca
+        if let Some(mut tunnel) = tunnel {
+            let _ = tunnel.kill().ok();
+        };
+    });
+
+    // Kesalahan sebelum potongan pertama (misalnya filter yang salah) masih bisa dikembalikan sebagai status error
+    let body = match rx.recv().await {
+        Some(Ok(first)) => {
+            Body::from_bytes_stream(tokio_stream::once(Ok(first)).chain(ReceiverStream::new(rx)))
+        }
+        Some(Err(_)) => return Err(invalid("dump.queryFailed")),
+        None => Body::empty(),
+    };
+
+    Ok(poem::Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", "application/sql; charset=utf-8")
+        .header("Content-Disposition", "attachment; filename=\"dump.sql\"")
+        .body(body))
+}
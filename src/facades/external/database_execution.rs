@@ -0,0 +1,293 @@
+use std::time::Instant;
+
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::{Days, NaiveDateTime, NaiveTime, Utc};
+use diesel::dsl::{avg, count_star, max};
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use diesel::sql_types::Bool;
+use poem::web::{Json, Query};
+use poem::{IntoResponse, handler, http::StatusCode, web::Path};
+
+use crate::database_pool::DatabasePool;
+use crate::db::DbPool;
+use crate::models::common::{DataResponse, PaginatedResponse};
+use crate::models::external::database_execution::{
+    DatabaseExecution, ExecutionPagination, SlowestExecution,
+};
+use crate::schema::tbl_ext_database_execution;
+use crate::utils::common::{self, like_pattern, validate_id};
+use crate::utils::database::{parse_statement_info, sql_dialect};
+
+pub struct ExecutionContext {
+    ext_database_id: i64,
+    user_id: i64,
+    source: &'static str,
+    is_mysql: bool,
+}
+
+impl ExecutionContext {
+    pub fn new(
+        ext_pool: &DatabasePool,
+        ext_database_id: i64,
+        user_id: i64,
+        source: &'static str,
+    ) -> Self {
+        ExecutionContext {
+            ext_database_id,
+            user_id,
+            source,
+            is_mysql: matches!(ext_pool, DatabasePool::MySql(_)),
+        }
+    }
+}
+
+// Pencatatan tidak boleh menggagalkan eksekusi, kesalahan insert cukup ditulis ke log
+pub fn record_execution(
+    conn: &mut PgConnection,
+    context: &ExecutionContext,
+    query: &str,
+    dt_started: Instant,
+    outcome: Result<Option<u64>, String>,
+) {
+    let dialect = sql_dialect(context.is_mysql);
+    let statement_kind = parse_statement_info(query, dialect.as_ref())
+        .map(|statement| statement.kind.action())
+        .unwrap_or("other");
+    let (affected_row, error_message) = match outcome {
+        Ok(affected_row) => (affected_row.map(|rows| rows as i64), None),
+        Err(message) => (None, Some(message)),
+    };
+
+    let inserted = diesel::insert_into(tbl_ext_database_execution::table)
+        .values(DatabaseExecution {
+            id: common::generate_id(),
+            ext_database_id: context.ext_database_id,
+            source: context.source.to_string(),
+            query: query.trim().to_string(),
+            statement_kind: statement_kind.to_string(),
+            duration_ms: dt_started.elapsed().as_millis() as i64,
+            affected_row,
+            error_message,
+            created_by: context.user_id,
+            dt_created: Utc::now().naive_utc(),
+        })
+        .execute(conn);
+    if let Err(e) = inserted {
+        eprintln!("Inserting error: {}", e);
+    }
+}
+
+// Task yang berjalan setelah handler selesai (stream export, dump) mengambil koneksi sendiri dari pool
+pub fn record_execution_pooled(
+    pool: &DbPool,
+    context: &ExecutionContext,
+    query: &str,
+    dt_started: Instant,
+    outcome: Result<Option<u64>, String>,
+) {
+    match pool.get() {
+        Ok(mut conn) => record_execution(&mut conn, context, query, dt_started, outcome),
+        Err(e) => eprintln!("Inserting error: {}", e),
+    }
+}
+
+type ExecutionFilter =
+    Box<dyn BoxableExpression<tbl_ext_database_execution::table, Pg, SqlType = Bool>>;
+
+fn execution_filter(ext_database_id: i64, pagination: &ExecutionPagination) -> ExecutionFilter {
+    let mut filter: ExecutionFilter =
+        Box::new(tbl_ext_database_execution::ext_database_id.eq(ext_database_id));
+
+    if let Some(term) = pagination
+        .search
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+    {
+        let pattern = like_pattern(term);
+        filter = Box::new(
+            filter.and(
+                tbl_ext_database_execution::query.ilike(pattern.clone()).or(
+                    tbl_ext_database_execution::error_message
+                        .ilike(pattern)
+                        .assume_not_null(),
+                ),
+            ),
+        );
+    }
+    if let Some(source) = pagination.source.clone() {
+        filter = Box::new(filter.and(tbl_ext_database_execution::source.eq(source)));
+    }
+    if let Some(statement_kind) = pagination.statement_kind.clone() {
+        filter =
+            Box::new(filter.and(tbl_ext_database_execution::statement_kind.eq(statement_kind)));
+    }
+    if let Some(created_by) = pagination.created_by {
+        filter = Box::new(filter.and(tbl_ext_database_execution::created_by.eq(created_by)));
+    }
+    match pagination.error_flag {
+        Some(1) => {
+            filter = Box::new(filter.and(tbl_ext_database_execution::error_message.is_not_null()))
+        }
+        Some(0) => {
+            filter = Box::new(filter.and(tbl_ext_database_execution::error_message.is_null()))
+        }
+        _ => {}
+    }
+    if let Some(dt_start) = pagination.dt_start {
+        filter = Box::new(
+            filter
+                .and(tbl_ext_database_execution::dt_created.ge(dt_start.and_time(NaiveTime::MIN))),
+        );
+    }
+    // Tanggal akhir inklusif sehingga batasnya awal hari berikutnya
+    if let Some(dt_end) = pagination
+        .dt_end
+        .and_then(|d| d.checked_add_days(Days::new(1)))
+    {
+        filter = Box::new(
+            filter.and(tbl_ext_database_execution::dt_created.lt(dt_end.and_time(NaiveTime::MIN))),
+        );
+    }
+
+    filter
+}
+
+fn loading_error(e: diesel::result::Error) -> poem::Error {
+    eprintln!("Loading error: {}", e);
+    common::error_message(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "information.internalServerError",
+    )
+}
+
+#[handler]
+pub fn execution_list(
+    pool: poem::web::Data<&DbPool>,
+    _: crate::auth::middleware::JwtAuth,
+    Path(ext_database_id): Path<i64>,
+    Query(pagination): Query<ExecutionPagination>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(ext_database_id)?;
+
+    let start = pagination.start.unwrap_or(0);
+    let length = pagination.length.unwrap_or(10).min(100);
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    let total: i64 = tbl_ext_database_execution::table
+        .filter(execution_filter(ext_database_id, &pagination))
+        .count()
+        .get_result(conn)
+        .map_err(|e| {
+            eprintln!("Counting error: {}", e);
+            common::error_message(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "information.internalServerError",
+            )
+        })?;
+
+    if total == 0 {
+        return Ok(Json(PaginatedResponse {
+            total: 0,
+            data: vec![],
+        }));
+    }
+
+    let mut query = tbl_ext_database_execution::table
+        .filter(execution_filter(ext_database_id, &pagination))
+        .into_boxed();
+    match (pagination.sort.as_deref(), pagination.dir.as_deref()) {
+        (Some("duration"), Some("asc")) => {
+            query = query.order(tbl_ext_database_execution::duration_ms.asc())
+        }
+        (Some("duration"), _) => {
+            query = query.order(tbl_ext_database_execution::duration_ms.desc())
+        }
+        (Some("createdDate"), Some("asc")) => {
+            query = query.order(tbl_ext_database_execution::dt_created.asc())
+        }
+        _ => query = query.order(tbl_ext_database_execution::dt_created.desc()),
+    }
+
+    let data = query
+        .offset(start)
+        .limit(length)
+        .load::<DatabaseExecution>(conn)
+        .map_err(loading_error)?;
+
+    Ok(Json(PaginatedResponse { total, data }))
+}
+
+// Statement dengan teks yang sama digabung agar query lambat yang berulang langsung terlihat
+#[handler]
+pub fn execution_slowest(
+    pool: poem::web::Data<&DbPool>,
+    _: crate::auth::middleware::JwtAuth,
+    Path(ext_database_id): Path<i64>,
+    Query(pagination): Query<ExecutionPagination>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(ext_database_id)?;
+
+    let length = pagination.length.unwrap_or(10).min(100);
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    let query = tbl_ext_database_execution::table
+        .filter(execution_filter(ext_database_id, &pagination))
+        .group_by((
+            tbl_ext_database_execution::query,
+            tbl_ext_database_execution::statement_kind,
+        ))
+        .select((
+            tbl_ext_database_execution::query,
+            tbl_ext_database_execution::statement_kind,
+            count_star(),
+            avg(tbl_ext_database_execution::duration_ms),
+            max(tbl_ext_database_execution::duration_ms),
+            max(tbl_ext_database_execution::dt_created),
+        ))
+        .into_boxed();
+    let query = match pagination.sort.as_deref() {
+        Some("average") => query.order(avg(tbl_ext_database_execution::duration_ms).desc()),
+        _ => query.order(max(tbl_ext_database_execution::duration_ms).desc()),
+    };
+
+    let data = query
+        .limit(length)
+        .load::<(
+            String,
+            String,
+            i64,
+            Option<BigDecimal>,
+            Option<i64>,
+            Option<NaiveDateTime>,
+        )>(conn)
+        .map_err(loading_error)?
+        .into_iter()
+        .map(
+            |(query, statement_kind, execution_count, average, maximum, dt_last_executed)| {
+                SlowestExecution {
+                    query,
+                    statement_kind,
+                    execution_count,
+                    average_duration_ms: average.and_then(|v| v.to_f64()).unwrap_or_default(),
+                    max_duration_ms: maximum.unwrap_or_default(),
+                    dt_last_executed: dt_last_executed.unwrap_or_default(),
+                }
+            },
+        )
+        .collect::<Vec<_>>();
+
+    Ok(Json(DataResponse { data }))
+}
@@ -0,0 +1,343 @@
+use std::time::Instant;
+
+use poem::web::Json;
+use poem::{IntoResponse, handler, http::StatusCode, web::Path};
+use serde_json::{Map, Value};
+use sqlx::Row;
+use validator::Validate;
+
+use crate::database_pool::DatabasePool;
+use crate::db::DbPool;
+use crate::facades::external::database_execution::{ExecutionContext, record_execution};
+use crate::facades::external::database_query::get_external_pool;
+use crate::facades::external::database_row::ensure_not_locked;
+use crate::models::common::DataResponse;
+use crate::models::external::database_explain::{EntryQueryExplain, PlanNode, QueryPlan};
+use crate::utils::common::{self, validate_id, validation_error_response};
+use crate::utils::database::{
+    SqlStatementKind, is_only_comment, parse_statement_info, split_manual_query, sql_dialect,
+};
+
+const POSTGRES_NODE_KEYS: [&str; 10] = [
+    "Node Type",
+    "Relation Name",
+    "Startup Cost",
+    "Total Cost",
+    "Plan Rows",
+    "Actual Startup Time",
+    "Actual Total Time",
+    "Actual Rows",
+    "Actual Loops",
+    "Plans",
+];
+
+// Key pada EXPLAIN FORMAT=JSON MySQL yang berisi operasi turunan
+const MYSQL_NESTED_KEYS: [&str; 17] = [
+    "query_block",
+    "table",
+    "nested_loop",
+    "ordering_operation",
+    "grouping_operation",
+    "duplicates_removal",
+    "windowing",
+    "buffer_result",
+    "union_result",
+    "query_specifications",
+    "materialized_from_subquery",
+    "attached_subqueries",
+    "optimized_away_subqueries",
+    "having_subqueries",
+    "select_list_subqueries",
+    "order_by_subqueries",
+    "group_by_subqueries",
+];
+
+// MySQL menuliskan sebagian angka sebagai string, misalnya "query_cost": "1.20"
+fn number_value(value: Option<&Value>) -> Option<f64> {
+    match value? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn normalize_postgres_node(node: &Map<String, Value>) -> PlanNode {
+    PlanNode {
+        node_type: node
+            .get("Node Type")
+            .and_then(Value::as_str)
+            .unwrap_or("Unknown")
+            .to_string(),
+        relation_name: node
+            .get("Relation Name")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        startup_cost: number_value(node.get("Startup Cost")),
+        total_cost: number_value(node.get("Total Cost")),
+        plan_rows: number_value(node.get("Plan Rows")),
+        actual_startup_time: number_value(node.get("Actual Startup Time")),
+        actual_total_time: number_value(node.get("Actual Total Time")),
+        actual_rows: number_value(node.get("Actual Rows")),
+        actual_loops: number_value(node.get("Actual Loops")),
+        detail: node
+            .iter()
+            .filter(|(key, _)| !POSTGRES_NODE_KEYS.contains(&key.as_str()))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect(),
+        children: node
+            .get("Plans")
+            .and_then(Value::as_array)
+            .map(|plans| {
+                plans
+                    .iter()
+                    .filter_map(Value::as_object)
+                    .map(normalize_postgres_node)
+                    .collect()
+            })
+            .unwrap_or_default(),
+    }
+}
+
+fn normalize_postgres_plan(raw: &Value, is_analyze: i16) -> poem::Result<QueryPlan> {
+    let root = raw
+        .as_array()
+        .and_then(|items| items.first())
+        .and_then(Value::as_object)
+        .ok_or_else(|| {
+            common::error_message(StatusCode::INTERNAL_SERVER_ERROR, "explain.invalidPlan")
+        })?;
+    let plan = root.get("Plan").and_then(Value::as_object).ok_or_else(|| {
+        common::error_message(StatusCode::INTERNAL_SERVER_ERROR, "explain.invalidPlan")
+    })?;
+
+    Ok(QueryPlan {
+        database_type: String::from("postgres"),
+        is_analyze,
+        planning_time: number_value(root.get("Planning Time")),
+        execution_time: number_value(root.get("Execution Time")),
+        plan: normalize_postgres_node(plan),
+        raw: raw.clone(),
+    })
+}
+
+fn mysql_children(node: &Map<String, Value>) -> Vec<PlanNode> {
+    node.iter()
+        .filter(|(key, _)| MYSQL_NESTED_KEYS.contains(&key.as_str()))
+        .filter_map(|(key, value)| match value {
+            Value::Object(child) => Some(normalize_mysql_node(key, child)),
+            Value::Array(items) => Some(PlanNode {
+                node_type: key.clone(),
+                relation_name: None,
+                startup_cost: None,
+                total_cost: None,
+                plan_rows: None,
+                actual_startup_time: None,
+                actual_total_time: None,
+                actual_rows: None,
+                actual_loops: None,
+                detail: Map::new(),
+                children: items
+                    .iter()
+                    .filter_map(Value::as_object)
+                    .flat_map(mysql_children)
+                    .collect(),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+fn normalize_mysql_node(node_type: &str, node: &Map<String, Value>) -> PlanNode {
+    let cost_info = node.get("cost_info").and_then(Value::as_object);
+    let cost = |key: &str| number_value(cost_info.and_then(|c| c.get(key)));
+
+    PlanNode {
+        node_type: node_type.to_string(),
+        relation_name: node
+            .get("table_name")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        startup_cost: cost("read_cost"),
+        total_cost: cost("query_cost")
+            .or_else(|| cost("prefix_cost"))
+            .or_else(|| cost("sort_cost")),
+        plan_rows: number_value(node.get("rows_produced_per_join"))
+            .or_else(|| number_value(node.get("rows_examined_per_scan"))),
+        actual_startup_time: None,
+        actual_total_time: None,
+        actual_rows: None,
+        actual_loops: None,
+        detail: node
+            .iter()
+            .filter(|(key, _)| !MYSQL_NESTED_KEYS.contains(&key.as_str()))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect(),
+        children: mysql_children(node),
+    }
+}
+
+fn normalize_mysql_plan(raw: &Value) -> poem::Result<QueryPlan> {
+    let query_block = raw
+        .get("query_block")
+        .and_then(Value::as_object)
+        .ok_or_else(|| {
+            common::error_message(StatusCode::INTERNAL_SERVER_ERROR, "explain.invalidPlan")
+        })?;
+
+    Ok(QueryPlan {
+        database_type: String::from("mysql"),
+        is_analyze: 0,
+        planning_time: None,
+        execution_time: None,
+        plan: normalize_mysql_node("query_block", query_block),
+        raw: raw.clone(),
+    })
+}
+
+fn estimated_rows(node: &PlanNode) -> Option<f64> {
+    node.plan_rows
+        .or_else(|| node.children.iter().rev().find_map(estimated_rows))
+}
+
+pub async fn estimate_row_count(ext_pool: &DatabasePool, query: &str) -> Option<i64> {
+    let plan = explain_query(ext_pool, query, 0).await.ok()?;
+    estimated_rows(&plan.plan).map(|rows| rows.round() as i64)
+}
+
+async fn explain_query(
+    ext_pool: &DatabasePool,
+    query: &str,
+    is_analyze: i16,
+) -> poem::Result<QueryPlan> {
+    let query_error = |e: sqlx::Error| {
+        eprintln!("Query error: {}", e);
+        common::error_message(StatusCode::BAD_REQUEST, "explain.queryFailed")
+    };
+
+    let dialect = sql_dialect(matches!(ext_pool, DatabasePool::MySql(_)));
+    let is_explainable = parse_statement_info(query, dialect.as_ref()).is_ok_and(|statement| {
+        matches!(
+            statement.kind,
+            SqlStatementKind::Select
+                | SqlStatementKind::Insert
+                | SqlStatementKind::Update
+                | SqlStatementKind::Delete
+        )
+    });
+    if !is_explainable {
+        return Err(common::error_message(
+            StatusCode::BAD_REQUEST,
+            "explain.unsupportedStatement",
+        ));
+    }
+
+    match ext_pool {
+        DatabasePool::Postgres(pg_pool) => {
+            let explain = if is_analyze == 1 {
+                format!("EXPLAIN (ANALYZE, BUFFERS, FORMAT JSON) {}", query)
+            } else {
+                format!("EXPLAIN (FORMAT JSON) {}", query)
+            };
+
+            // ANALYZE benar-benar mengeksekusi query, jadi selalu dijalankan di transaksi yang di-rollback
+            let mut tx = pg_pool.begin().await.map_err(query_error)?;
+            let result = sqlx::query(&explain).fetch_one(&mut *tx).await;
+            let _ = tx.rollback().await;
+
+            let raw = result
+                .and_then(|row| row.try_get::<sqlx::types::Json<Value>, _>(0))
+                .map_err(query_error)?
+                .0;
+            normalize_postgres_plan(&raw, is_analyze)
+        }
+        DatabasePool::MySql(my_pool) => {
+            if is_analyze == 1 {
+                return Err(common::error_message(
+                    StatusCode::BAD_REQUEST,
+                    "explain.analyzeNotSupported",
+                ));
+            }
+
+            let raw: String = sqlx::query(&format!("EXPLAIN FORMAT=JSON {}", query))
+                .fetch_one(my_pool)
+                .await
+                .and_then(|row| row.try_get(0))
+                .map_err(query_error)?;
+            let raw: Value = serde_json::from_str(&raw).map_err(|_| {
+                common::error_message(StatusCode::INTERNAL_SERVER_ERROR, "explain.invalidPlan")
+            })?;
+            normalize_mysql_plan(&raw)
+        }
+    }
+}
+
+#[handler]
+pub async fn query_explain(
+    pool: poem::web::Data<&DbPool>,
+    jwt_auth: crate::auth::middleware::JwtAuth,
+    Path(ext_database_id): Path<i64>,
+    Json(entry): Json<EntryQueryExplain>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(ext_database_id)?;
+
+    if let Err(e) = entry.validate() {
+        return Err(validation_error_response(e));
+    }
+
+    let parts: Vec<String> = split_manual_query(&entry.query)
+        .into_iter()
+        .filter(|part| !is_only_comment(part))
+        .collect();
+    let query = match parts.as_slice() {
+        [query] => query.trim().trim_end_matches(';').to_string(),
+        _ => {
+            return Err(common::error_message(
+                StatusCode::BAD_REQUEST,
+                "explain.singleStatementOnly",
+            ));
+        }
+    };
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    let (ext_pool, mut tunnel, _, _) = get_external_pool(conn, ext_database_id).await?;
+
+    // ANALYZE atas DML tetap menjalankan trigger dan sequence meski di-rollback, jadi ikut aturan kunci database
+    let dialect = sql_dialect(matches!(ext_pool, DatabasePool::MySql(_)));
+    let is_analyze_dml = entry.is_analyze == 1
+        && parse_statement_info(&query, dialect.as_ref())
+            .is_ok_and(|statement| !matches!(statement.kind, SqlStatementKind::Select));
+    if is_analyze_dml && let Err(e) = ensure_not_locked(conn, ext_database_id) {
+        if let Some(tunnel) = tunnel.as_mut() {
+            let _ = tunnel.kill().ok();
+        }
+        return Err(e);
+    }
+
+    let dt_started = Instant::now();
+    let result = explain_query(&ext_pool, &query, entry.is_analyze).await;
+
+    if let Some(mut tunnel) = tunnel {
+        let _ = tunnel.kill().ok();
+    };
+
+    // Hanya ANALYZE yang benar-benar mengeksekusi statement ke database
+    if entry.is_analyze == 1 {
+        let context =
+            ExecutionContext::new(&ext_pool, ext_database_id, jwt_auth.claims.id, "explain");
+        record_execution(
+            conn,
+            &context,
+            &query,
+            dt_started,
+            result.as_ref().map(|_| None).map_err(|e| e.to_string()),
+        );
+    }
+
+    Ok(Json(DataResponse { data: result? }))
+}
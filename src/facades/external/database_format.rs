@@ -0,0 +1,51 @@
+use poem::web::Json;
+use poem::{IntoResponse, handler, http::StatusCode};
+use validator::Validate;
+
+use crate::models::common::DataResponse;
+use crate::models::external::database_format::{EntryQueryFormat, QueryFormatResult};
+use crate::utils::common::{self, validation_error_response};
+use crate::utils::sql_format::{KeywordCase, SqlFormatOptions, format_sql};
+
+#[handler]
+pub fn query_format(
+    _: crate::auth::middleware::JwtAuth,
+    Json(entry_query_format): Json<EntryQueryFormat>,
+) -> poem::Result<impl IntoResponse> {
+    if let Err(e) = entry_query_format.validate() {
+        return Err(validation_error_response(e));
+    }
+
+    let keyword_case = match entry_query_format.keyword_case.as_deref() {
+        None | Some("upper") => KeywordCase::Upper,
+        Some("lower") => KeywordCase::Lower,
+        Some("preserve") => KeywordCase::Preserve,
+        Some(_) => {
+            return Err(common::error_message(
+                StatusCode::BAD_REQUEST,
+                "format.invalidKeywordCase",
+            ));
+        }
+    };
+    let indent = if entry_query_format.is_use_tab == Some(1) {
+        String::from("\t")
+    } else {
+        " ".repeat(entry_query_format.indent_size.unwrap_or(2) as usize)
+    };
+
+    let (query, is_formatted) = format_sql(
+        &entry_query_format.query,
+        &SqlFormatOptions {
+            is_mysql: entry_query_format.mt_database_type_id == 2,
+            keyword_case,
+            indent,
+        },
+    );
+
+    Ok(Json(DataResponse {
+        data: QueryFormatResult {
+            query,
+            is_formatted: if is_formatted { 1 } else { 0 },
+        },
+    }))
+}
@@ -0,0 +1,531 @@
+use std::collections::HashSet;
+use std::time::Instant;
+
+use poem::web::{Json, Multipart};
+use poem::{IntoResponse, handler, http::StatusCode, web::Path};
+use sqlx::database::HasArguments;
+use sqlx::{Database, Encode, Executor, IntoArguments, Pool, Type};
+
+use crate::database_pool::DatabasePool;
+use crate::db::DbPool;
+use crate::facades::external::database_execution::{ExecutionContext, record_execution};
+use crate::facades::external::database_object::fetch_object_structure;
+use crate::facades::external::database_query::{get_external_pool, resolve_schema};
+use crate::facades::external::database_row::{base_type, ensure_not_locked};
+use crate::models::common::DataResponse;
+use crate::models::external::database_import::{
+    ImportColumnMapping, ImportPreview, ImportResult, ImportRowError,
+};
+use crate::models::external::database_object::ObjectColumn;
+use crate::utils::common::{self, validate_id};
+use crate::utils::database::{quote_identifier_mysql, quote_identifier_postgres};
+use crate::utils::import::{coerce_import_value, infer_column_type, parse_import_file};
+
+const PREVIEW_ROW_LIMIT: usize = 20;
+const INFER_ROW_LIMIT: usize = 1000;
+const DEFAULT_BATCH_SIZE: usize = 500;
+const MAX_BIND_PER_QUERY: usize = 60000;
+
+struct ImportForm {
+    file_name: String,
+    data: Vec<u8>,
+    delimiter: char,
+    header_flag: i16,
+    table_name: Option<String>,
+    schema: Option<String>,
+    mappings: Option<Vec<ImportColumnMapping>>,
+    batch_size: usize,
+    abort_on_error: bool,
+}
+
+struct ImportRow {
+    row_number: usize,
+    values: Vec<Option<String>>,
+}
+
+async fn read_import_form(mut multipart: Multipart) -> poem::Result<ImportForm> {
+    let mut form = ImportForm {
+        file_name: String::new(),
+        data: Vec::new(),
+        delimiter: ',',
+        header_flag: 1,
+        table_name: None,
+        schema: None,
+        mappings: None,
+        batch_size: DEFAULT_BATCH_SIZE,
+        abort_on_error: false,
+    };
+
+    let invalid = |key: &str| common::error_message(StatusCode::BAD_REQUEST, key);
+
+    while let Some(field) = multipart.next_field().await? {
+        let name = field.name().unwrap_or("").to_string();
+
+        match name.as_str() {
+            "file" => {
+                form.file_name = field.file_name().unwrap_or("").to_string();
+                form.data = field.bytes().await?.to_vec();
+            }
+            "delimiter" => {
+                let text = field.text().await?;
+                form.delimiter = match text.as_str() {
+                    "\\t" | "tab" => '\t',
+                    _ => text
+                        .chars()
+                        .next()
+                        .ok_or_else(|| invalid("import.invalidDelimiter"))?,
+                };
+            }
+            "headerFlag" => {
+                form.header_flag = field
+                    .text()
+                    .await?
+                    .parse()
+                    .map_err(|_| invalid("import.invalidHeaderFlag"))?;
+            }
+            "tableName" => form.table_name = Some(field.text().await?),
+            "schema" => form.schema = Some(field.text().await?),
+            "mappingList" => {
+                form.mappings = Some(
+                    serde_json::from_str(&field.text().await?)
+                        .map_err(|_| invalid("import.invalidMapping"))?,
+                );
+            }
+            "batchSize" => {
+                form.batch_size = field
+                    .text()
+                    .await?
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|v| *v > 0)
+                    .ok_or_else(|| invalid("import.invalidBatchSize"))?;
+            }
+            "abortOnErrorFlag" => form.abort_on_error = field.text().await? == "1",
+            _ => {}
+        }
+    }
+
+    if form.data.is_empty() {
+        return Err(invalid("import.fileRequired"));
+    }
+
+    Ok(form)
+}
+
+#[handler]
+pub async fn import_preview(
+    _: crate::auth::middleware::JwtAuth,
+    Path(ext_database_id): Path<i64>,
+    multipart: Multipart,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(ext_database_id)?;
+
+    let form = read_import_form(multipart).await?;
+    let (headers, rows) = parse_import_file(
+        &form.file_name,
+        &form.data,
+        form.delimiter,
+        form.header_flag,
+    )?;
+
+    let column_types = (0..headers.len())
+        .map(|i| {
+            infer_column_type(
+                rows.iter()
+                    .take(INFER_ROW_LIMIT)
+                    .map(|row| row[i].as_deref()),
+            )
+            .to_string()
+        })
+        .collect();
+
+    Ok(Json(DataResponse {
+        data: ImportPreview {
+            headers,
+            column_types,
+            total_row: rows.len(),
+            rows: rows.into_iter().take(PREVIEW_ROW_LIMIT).collect(),
+        },
+    }))
+}
+
+// Tanpa mappingList, kolom file dipasangkan dengan kolom tabel yang namanya sama
+fn resolve_mapping(
+    form_mappings: Option<Vec<ImportColumnMapping>>,
+    headers: &[String],
+    columns: &[ObjectColumn],
+) -> poem::Result<Vec<(usize, ObjectColumn)>> {
+    let invalid = || common::error_message(StatusCode::BAD_REQUEST, "import.invalidMapping");
+
+    let mappings = form_mappings.unwrap_or_else(|| {
+        headers
+            .iter()
+            .filter_map(|header| {
+                columns
+                    .iter()
+                    .find(|col| col.nm.eq_ignore_ascii_case(header.trim()))
+                    .map(|col| ImportColumnMapping {
+                        file_column: header.clone(),
+                        table_column: col.nm.clone(),
+                    })
+            })
+            .collect()
+    });
+
+    let mut used = HashSet::new();
+    let mut resolved = Vec::new();
+
+    for mapping in mappings {
+        let index = headers
+            .iter()
+            .position(|h| *h == mapping.file_column)
+            .ok_or_else(invalid)?;
+        let column = columns
+            .iter()
+            .find(|col| col.nm == mapping.table_column)
+            .ok_or_else(invalid)?;
+
+        if !used.insert(column.nm.clone()) {
+            return Err(invalid());
+        }
+        resolved.push((index, column.clone()));
+    }
+
+    if resolved.is_empty() {
+        return Err(invalid());
+    }
+
+    Ok(resolved)
+}
+
+fn build_insert_query(
+    table: &str,
+    columns: &[String],
+    rows: &[&ImportRow],
+    placeholder: impl Fn(usize, usize) -> String,
+) -> String {
+    let mut bind_index = 0;
+    let values = rows
+        .iter()
+        .map(|row| {
+            let cells = row
+                .values
+                .iter()
+                .enumerate()
+                .map(|(i, value)| match value {
+                    // Sel kosong memakai nilai default kolom (NULL bila tidak ada)
+                    None => String::from("DEFAULT"),
+                    Some(_) => {
+                        bind_index += 1;
+                        placeholder(bind_index, i)
+                    }
+                })
+                .collect::<Vec<String>>();
+            format!("({})", cells.join(", "))
+        })
+        .collect::<Vec<String>>();
+
+    format!(
+        "INSERT INTO {} ({}) VALUES {}",
+        table,
+        columns.join(", "),
+        values.join(", ")
+    )
+}
+
+fn database_error_message(e: &sqlx::Error) -> String {
+    match e.as_database_error() {
+        Some(db_error) => db_error.message().to_string(),
+        None => e.to_string(),
+    }
+}
+
+struct InsertTarget<'a> {
+    table: &'a str,
+    column_names: &'a [String],
+    placeholder: &'a (dyn Fn(usize, usize) -> String + Sync),
+}
+
+// Batch dijalankan dalam savepoint, batch yang gagal diulang per baris agar baris bermasalah bisa dilaporkan
+async fn insert_rows<DB>(
+    pool: &Pool<DB>,
+    target: &InsertTarget<'_>,
+    rows: &[ImportRow],
+    batch_size: usize,
+    abort_on_error: bool,
+    errors: &mut Vec<ImportRowError>,
+) -> Result<(usize, bool), sqlx::Error>
+where
+    DB: Database,
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+    for<'q> String: Encode<'q, DB> + Type<DB>,
+{
+    let mut tx = pool.begin().await?;
+    let mut inserted = 0;
+
+    for batch in rows.chunks(batch_size) {
+        let batch: Vec<&ImportRow> = batch.iter().collect();
+        let query = build_insert_query(
+            target.table,
+            target.column_names,
+            &batch,
+            target.placeholder,
+        );
+
+        sqlx::query("SAVEPOINT import_batch")
+            .execute(&mut *tx)
+            .await?;
+        let mut insert = sqlx::query(&query);
+        for value in batch.iter().flat_map(|row| row.values.iter().flatten()) {
+            insert = insert.bind(value.clone());
+        }
+
+        if insert.execute(&mut *tx).await.is_ok() {
+            sqlx::query("RELEASE SAVEPOINT import_batch")
+                .execute(&mut *tx)
+                .await?;
+            inserted += batch.len();
+            continue;
+        }
+
+        sqlx::query("ROLLBACK TO SAVEPOINT import_batch")
+            .execute(&mut *tx)
+            .await?;
+
+        for row in batch {
+            let query = build_insert_query(
+                target.table,
+                target.column_names,
+                &[row],
+                target.placeholder,
+            );
+            let mut insert = sqlx::query(&query);
+            for value in row.values.iter().flatten() {
+                insert = insert.bind(value.clone());
+            }
+
+            sqlx::query("SAVEPOINT import_row")
+                .execute(&mut *tx)
+                .await?;
+            match insert.execute(&mut *tx).await {
+                Ok(_) => {
+                    sqlx::query("RELEASE SAVEPOINT import_row")
+                        .execute(&mut *tx)
+                        .await?;
+                    inserted += 1;
+                }
+                Err(e) => {
+                    sqlx::query("ROLLBACK TO SAVEPOINT import_row")
+                        .execute(&mut *tx)
+                        .await?;
+                    errors.push(ImportRowError {
+                        row_number: row.row_number,
+                        column_nm: None,
+                        message: database_error_message(&e),
+                    });
+                }
+            }
+        }
+    }
+
+    if abort_on_error && !errors.is_empty() {
+        tx.rollback().await?;
+        Ok((0, true))
+    } else {
+        tx.commit().await?;
+        Ok((inserted, false))
+    }
+}
+
+#[handler]
+pub async fn import_run(
+    pool: poem::web::Data<&DbPool>,
+    jwt_auth: crate::auth::middleware::JwtAuth,
+    Path(ext_database_id): Path<i64>,
+    multipart: Multipart,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(ext_database_id)?;
+
+    let form = read_import_form(multipart).await?;
+    let table_name = form
+        .table_name
+        .clone()
+        .filter(|v| !v.trim().is_empty())
+        .ok_or_else(|| common::error_message(StatusCode::BAD_REQUEST, "import.tableRequired"))?;
+    let (headers, rows) = parse_import_file(
+        &form.file_name,
+        &form.data,
+        form.delimiter,
+        form.header_flag,
+    )?;
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    ensure_not_locked(conn, ext_database_id)?;
+    let schema = resolve_schema(conn, ext_database_id, form.schema)?;
+    let (ext_pool, tunnel, _, _) = get_external_pool(conn, ext_database_id).await?;
+
+    let result = async {
+        let structure = fetch_object_structure(&ext_pool, schema.as_deref(), &table_name).await?;
+        if structure.object_type != "table" {
+            return Err(common::error_message(
+                StatusCode::BAD_REQUEST,
+                "import.invalidTable",
+            ));
+        }
+
+        let mapping = resolve_mapping(form.mappings, &headers, &structure.columns)?;
+        let is_mysql = matches!(ext_pool, DatabasePool::MySql(_));
+        let first_row_number = if form.header_flag == 1 { 2 } else { 1 };
+
+        let mut errors = Vec::new();
+        let mut prepared = Vec::new();
+
+        for (i, row) in rows.iter().enumerate() {
+            let row_number = i + first_row_number;
+            let mut values = Vec::new();
+            let mut is_valid = true;
+
+            for (index, column) in &mapping {
+                match row[*index].as_deref() {
+                    None => values.push(None),
+                    Some(raw) => match coerce_import_value(raw, &column.data_type, is_mysql) {
+                        Ok(value) => values.push(Some(value)),
+                        Err(message) => {
+                            is_valid = false;
+                            errors.push(ImportRowError {
+                                row_number,
+                                column_nm: Some(column.nm.clone()),
+                                message,
+                            });
+                        }
+                    },
+                }
+            }
+
+            if is_valid {
+                prepared.push(ImportRow { row_number, values });
+            }
+        }
+
+        let columns: Vec<ObjectColumn> = mapping.into_iter().map(|(_, col)| col).collect();
+        let batch_size = form
+            .batch_size
+            .min((MAX_BIND_PER_QUERY / columns.len()).max(1));
+
+        let context =
+            ExecutionContext::new(&ext_pool, ext_database_id, jwt_auth.claims.id, "import");
+        let dt_started = Instant::now();
+        let quote_identifier = if is_mysql {
+            quote_identifier_mysql
+        } else {
+            quote_identifier_postgres
+        };
+        let table = format!(
+            "{}.{}",
+            quote_identifier(&structure.schema),
+            quote_identifier(&structure.nm)
+        );
+        let column_names: Vec<String> = columns
+            .iter()
+            .map(|col| quote_identifier(&col.nm))
+            .collect();
+        let inserted = match ext_pool {
+            DatabasePool::Postgres(ref pg_pool) => {
+                let placeholder = |bind: usize, i: usize| {
+                    format!("CAST(${} AS {})", bind, base_type(&columns[i].data_type))
+                };
+                let target = InsertTarget {
+                    table: &table,
+                    column_names: &column_names,
+                    placeholder: &placeholder,
+                };
+                insert_rows(
+                    pg_pool,
+                    &target,
+                    &prepared,
+                    batch_size,
+                    form.abort_on_error,
+                    &mut errors,
+                )
+                .await
+            }
+            DatabasePool::MySql(ref my_pool) => {
+                let placeholder = |_: usize, _: usize| String::from("?");
+                let target = InsertTarget {
+                    table: &table,
+                    column_names: &column_names,
+                    placeholder: &placeholder,
+                };
+                insert_rows(
+                    my_pool,
+                    &target,
+                    &prepared,
+                    batch_size,
+                    form.abort_on_error,
+                    &mut errors,
+                )
+                .await
+            }
+        };
+
+        // Satu catatan per import, bentuk statement-nya sama untuk setiap batch
+        let placeholders: Vec<String> = (1..=columns.len())
+            .map(|i| {
+                if is_mysql {
+                    String::from("?")
+                } else {
+                    format!("${}", i)
+                }
+            })
+            .collect();
+        record_execution(
+            conn,
+            &context,
+            &format!(
+                "INSERT INTO {} ({}) VALUES ({})",
+                table,
+                column_names.join(", "),
+                placeholders.join(", ")
+            ),
+            dt_started,
+            inserted
+                .as_ref()
+                .map(|(inserted, _)| Some(*inserted as u64))
+                .map_err(database_error_message),
+        );
+
+        let (inserted, is_rollback) = inserted.map_err(|e| {
+            eprintln!("Import error on {}: {}", table, e);
+            poem::Error::from_status(StatusCode::INTERNAL_SERVER_ERROR)
+        })?;
+
+        errors.sort_by_key(|e| e.row_number);
+        let failed_row = errors
+            .iter()
+            .map(|e| e.row_number)
+            .collect::<HashSet<usize>>()
+            .len();
+
+        Ok(ImportResult {
+            total_row: rows.len(),
+            inserted_row: inserted,
+            failed_row,
+            is_rollback: if is_rollback { 1 } else { 0 },
+            errors,
+        })
+    }
+    .await;
+
+    if let Some(mut tunnel) = tunnel {
+        let _ = tunnel.kill().ok();
+    };
+
+    Ok(Json(DataResponse { data: result? }))
+}
@@ -0,0 +1,423 @@
+use std::str::FromStr;
+use std::time::Instant;
+
+use base64::{Engine, engine::general_purpose};
+use diesel::PgConnection;
+use poem::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::mysql::MySqlArguments;
+use sqlx::query::Query;
+use sqlx::types::BigDecimal;
+use sqlx::{MySql, Pool, Postgres, Row};
+
+use crate::database_pool::DatabasePool;
+use crate::facades::external::database_execution::{ExecutionContext, record_execution};
+use crate::facades::external::database_mask::MaskPolicy;
+use crate::models::common::LoadedMoreResponse;
+use crate::utils::common;
+use crate::utils::database::{
+    KeysetRelation, keyset_relation, quote_identifier_mysql, quote_identifier_postgres,
+    rows_to_json_mysql, rows_to_json_postgres, sql_dialect,
+};
+
+// Tipe biner tidak bisa dibandingkan ulang dari representasi hex pada JSON baris
+const MYSQL_UNSUPPORTED_KEY_TYPES: [&str; 7] = [
+    "binary",
+    "varbinary",
+    "tinyblob",
+    "blob",
+    "mediumblob",
+    "longblob",
+    "bit",
+];
+
+#[derive(Serialize, Deserialize)]
+struct KeysetCursor {
+    #[serde(rename = "keyList")]
+    keys: Vec<String>,
+    #[serde(rename = "valueList")]
+    values: Vec<Value>,
+}
+
+struct KeyColumn {
+    name: String,
+    type_name: String,
+    is_unsigned: bool,
+}
+
+fn query_error(e: sqlx::Error) -> poem::Error {
+    eprintln!("Query error: {}", e);
+    poem::Error::from_status(StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+fn invalid_cursor() -> poem::Error {
+    common::error_message(StatusCode::BAD_REQUEST, "pagination.invalidCursor")
+}
+
+fn encode_cursor(cursor: &KeysetCursor) -> Option<String> {
+    serde_json::to_vec(cursor)
+        .ok()
+        .map(|bytes| general_purpose::URL_SAFE_NO_PAD.encode(bytes))
+}
+
+fn decode_cursor(cursor: &str) -> poem::Result<KeysetCursor> {
+    general_purpose::URL_SAFE_NO_PAD
+        .decode(cursor)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .ok_or_else(invalid_cursor)
+}
+
+fn cursor_value_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+// Unique index dengan kolom NOT NULL, primary key didahulukan lalu index dengan kolom paling sedikit
+async fn key_columns_postgres(
+    pool: &Pool<Postgres>,
+    relation: &KeysetRelation,
+) -> poem::Result<Vec<KeyColumn>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            a.attname::TEXT AS name,
+            pg_catalog.format_type(a.atttypid, a.atttypmod) AS type_name
+        FROM pg_catalog.pg_index ix
+        CROSS JOIN LATERAL unnest(ix.indkey::INT2[]) WITH ORDINALITY AS k(attnum, position)
+        JOIN pg_catalog.pg_attribute a ON a.attrelid = ix.indrelid AND a.attnum = k.attnum
+        WHERE ix.indexrelid = (
+            SELECT candidate.indexrelid
+            FROM pg_catalog.pg_index candidate
+            WHERE candidate.indrelid = to_regclass($1)
+            AND candidate.indisunique
+            AND candidate.indisvalid
+            AND candidate.indpred IS NULL
+            AND candidate.indexprs IS NULL
+            AND candidate.indnatts = candidate.indnkeyatts
+            AND NOT EXISTS (
+                SELECT 1 FROM pg_catalog.pg_attribute col
+                WHERE col.attrelid = candidate.indrelid
+                AND col.attnum = ANY(candidate.indkey::INT2[])
+                AND (NOT col.attnotnull OR col.atttypid = 'bytea'::REGTYPE)
+            )
+            ORDER BY candidate.indisprimary DESC, candidate.indnatts, candidate.indexrelid
+            LIMIT 1
+        )
+        ORDER BY k.position
+    "#,
+    )
+    .bind(&relation.name)
+    .fetch_all(pool)
+    .await
+    .map_err(query_error)?;
+
+    rows.iter()
+        .map(|row| {
+            Ok(KeyColumn {
+                name: row.try_get("name")?,
+                type_name: row.try_get("type_name")?,
+                is_unsigned: false,
+            })
+        })
+        .collect::<Result<Vec<_>, sqlx::Error>>()
+        .map_err(query_error)
+}
+
+async fn key_columns_mysql(
+    pool: &Pool<MySql>,
+    relation: &KeysetRelation,
+) -> poem::Result<Vec<KeyColumn>> {
+    let (schema, table) = match relation.name_parts.as_slice() {
+        [schema, table] => (Some(schema.as_str()), table.as_str()),
+        [table] => (None, table.as_str()),
+        _ => return Ok(vec![]),
+    };
+
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            CAST(s.INDEX_NAME AS CHAR) AS index_name,
+            CAST(s.COLUMN_NAME AS CHAR) AS name,
+            CAST(c.DATA_TYPE AS CHAR) AS type_name,
+            CAST(c.IS_NULLABLE AS CHAR) AS is_nullable,
+            CAST(s.SUB_PART IS NOT NULL AS SIGNED) AS is_prefix,
+            CAST(c.COLUMN_TYPE LIKE '%unsigned%' AS SIGNED) AS is_unsigned
+        FROM information_schema.STATISTICS s
+        JOIN information_schema.COLUMNS c
+            ON c.TABLE_SCHEMA = s.TABLE_SCHEMA
+            AND c.TABLE_NAME = s.TABLE_NAME
+            AND c.COLUMN_NAME = s.COLUMN_NAME
+        WHERE s.TABLE_SCHEMA = COALESCE(?, DATABASE())
+        AND s.TABLE_NAME = ?
+        AND s.NON_UNIQUE = 0
+        ORDER BY s.INDEX_NAME = 'PRIMARY' DESC, s.INDEX_NAME, s.SEQ_IN_INDEX
+    "#,
+    )
+    .bind(schema)
+    .bind(table)
+    .fetch_all(pool)
+    .await
+    .map_err(query_error)?;
+
+    let mut indexes: Vec<(String, Vec<KeyColumn>, bool)> = Vec::new();
+    for row in &rows {
+        let index_name: String = row.try_get("index_name").map_err(query_error)?;
+        let type_name: String = row.try_get("type_name").map_err(query_error)?;
+        let is_nullable: String = row.try_get("is_nullable").map_err(query_error)?;
+        let is_prefix: i64 = row.try_get("is_prefix").map_err(query_error)?;
+        let is_unsigned: i64 = row.try_get("is_unsigned").map_err(query_error)?;
+        let is_usable = is_nullable == "NO"
+            && is_prefix == 0
+            && !MYSQL_UNSUPPORTED_KEY_TYPES.contains(&type_name.to_lowercase().as_str());
+
+        if indexes
+            .last()
+            .is_none_or(|(name, _, _)| *name != index_name)
+        {
+            indexes.push((index_name.clone(), Vec::new(), true));
+        }
+        if let Some((_, columns, usable)) = indexes.last_mut() {
+            columns.push(KeyColumn {
+                name: row.try_get("name").map_err(query_error)?,
+                type_name,
+                is_unsigned: is_unsigned == 1,
+            });
+            *usable &= is_usable;
+        }
+    }
+
+    // Urutan PRIMARY tetap didahulukan, sisanya dipilih yang kolomnya paling sedikit
+    let mut usable: Vec<_> = indexes
+        .into_iter()
+        .enumerate()
+        .filter(|(_, (_, _, usable))| *usable)
+        .collect();
+    usable.sort_by_key(|(i, (name, columns, _))| (name != "PRIMARY", columns.len(), *i));
+    Ok(usable
+        .into_iter()
+        .next()
+        .map(|(_, (_, columns, _))| columns)
+        .unwrap_or_default())
+}
+
+// Nilai cursor diikat sesuai tipe kolom supaya perbandingan MySQL tidak jatuh ke perbandingan teks
+fn bind_mysql<'q>(
+    query: Query<'q, MySql, MySqlArguments>,
+    column: &KeyColumn,
+    value: &'q str,
+) -> poem::Result<Query<'q, MySql, MySqlArguments>> {
+    let bound = match column.type_name.to_lowercase().as_str() {
+        "tinyint" | "smallint" | "mediumint" | "int" | "integer" | "bigint" | "year" => {
+            // tinyint(1) dibaca sebagai boolean pada JSON baris
+            let value = match value {
+                "true" => "1",
+                "false" => "0",
+                other => other,
+            };
+            if column.is_unsigned {
+                query.bind(u64::from_str(value).map_err(|_| invalid_cursor())?)
+            } else {
+                query.bind(i64::from_str(value).map_err(|_| invalid_cursor())?)
+            }
+        }
+        "decimal" | "numeric" => {
+            query.bind(BigDecimal::from_str(value).map_err(|_| invalid_cursor())?)
+        }
+        "float" | "double" | "real" => {
+            query.bind(f64::from_str(value).map_err(|_| invalid_cursor())?)
+        }
+        _ => query.bind(value),
+    };
+    Ok(bound)
+}
+
+fn keyset_query(
+    ext_pool: &DatabasePool,
+    base_query: &str,
+    key_columns: &[KeyColumn],
+    has_cursor: bool,
+    limit: i64,
+) -> String {
+    let is_mysql = matches!(ext_pool, DatabasePool::MySql(_));
+    let quoted: Vec<String> = key_columns
+        .iter()
+        .map(|column| {
+            if is_mysql {
+                quote_identifier_mysql(&column.name)
+            } else {
+                quote_identifier_postgres(&column.name)
+            }
+        })
+        .collect();
+
+    let seek = if has_cursor {
+        let placeholders: Vec<String> = key_columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                if is_mysql {
+                    "?".to_string()
+                } else {
+                    format!("${}::{}", i + 1, column.type_name)
+                }
+            })
+            .collect();
+        format!(
+            " WHERE ({}) > ({})",
+            quoted.join(", "),
+            placeholders.join(", ")
+        )
+    } else {
+        String::new()
+    };
+
+    format!(
+        "SELECT * FROM ({}) keyset_rows{} ORDER BY {} LIMIT {}",
+        base_query.trim().trim_end_matches(';'),
+        seek,
+        quoted.join(", "),
+        limit
+    )
+}
+
+// Mengembalikan None jika query tidak bisa memakai keyset sehingga pemanggil kembali ke OFFSET
+pub async fn query_with_keyset(
+    conn: &mut PgConnection,
+    context: &ExecutionContext,
+    ext_pool: &DatabasePool,
+    base_query: &str,
+    cursor: &str,
+    length: i64,
+    mask: &MaskPolicy,
+) -> poem::Result<Option<LoadedMoreResponse<Value>>> {
+    let cursor = if cursor.is_empty() {
+        None
+    } else {
+        Some(decode_cursor(cursor)?)
+    };
+
+    let dialect = sql_dialect(matches!(ext_pool, DatabasePool::MySql(_)));
+    let key_columns = match keyset_relation(base_query, dialect.as_ref()) {
+        Some(relation) => {
+            let key_columns = match ext_pool {
+                DatabasePool::Postgres(pg_pool) => key_columns_postgres(pg_pool, &relation).await?,
+                DatabasePool::MySql(my_pool) => key_columns_mysql(my_pool, &relation).await?,
+            };
+            // Semua kolom key harus ikut terpilih agar nilai cursor bisa diambil dari baris terakhir,
+            // dan tidak boleh tersamarkan karena nilai aslinya akan terbawa di cursor
+            let is_masked = key_columns
+                .iter()
+                .any(|column| mask.is_masked(&column.name));
+            let is_projected = relation.projection.as_ref().is_none_or(|projection| {
+                key_columns.iter().all(|column| {
+                    projection
+                        .iter()
+                        .any(|name| name.eq_ignore_ascii_case(&column.name))
+                })
+            });
+            if is_projected && !is_masked {
+                key_columns
+            } else {
+                Vec::new()
+            }
+        }
+        None => Vec::new(),
+    };
+
+    if key_columns.is_empty() {
+        return match cursor {
+            Some(_) => Err(invalid_cursor()),
+            None => Ok(None),
+        };
+    }
+
+    // Nama kolom pada cursor harus sama dengan key hasil deteksi, jadi tidak ada identifier dari klien
+    let values: Vec<Option<String>> = match &cursor {
+        Some(cursor) => {
+            let is_same_key = cursor.keys.len() == key_columns.len()
+                && cursor
+                    .keys
+                    .iter()
+                    .zip(&key_columns)
+                    .all(|(key, column)| *key == column.name);
+            if !is_same_key || cursor.values.len() != key_columns.len() {
+                return Err(invalid_cursor());
+            }
+            cursor.values.iter().map(cursor_value_text).collect()
+        }
+        None => Vec::new(),
+    };
+    if values.iter().any(Option::is_none) {
+        return Err(invalid_cursor());
+    }
+
+    let query = keyset_query(
+        ext_pool,
+        base_query,
+        &key_columns,
+        cursor.is_some(),
+        length + 1,
+    );
+    let dt_started = Instant::now();
+    let fetched = match ext_pool {
+        DatabasePool::Postgres(pg_pool) => {
+            let mut sql_query = sqlx::query(&query);
+            for value in &values {
+                sql_query = sql_query.bind(value);
+            }
+            sql_query
+                .fetch_all(pg_pool)
+                .await
+                .map(|rows| rows_to_json_postgres(&rows))
+        }
+        DatabasePool::MySql(my_pool) => {
+            let mut sql_query = sqlx::query(&query);
+            for (column, value) in key_columns.iter().zip(&values) {
+                sql_query = bind_mysql(sql_query, column, value.as_deref().unwrap_or_default())?;
+            }
+            sql_query
+                .fetch_all(my_pool)
+                .await
+                .map(|rows| rows_to_json_mysql(&rows))
+        }
+    };
+    record_execution(
+        conn,
+        context,
+        &query,
+        dt_started,
+        fetched
+            .as_ref()
+            .map(|rows| Some(rows.len() as u64))
+            .map_err(|e| e.to_string()),
+    );
+    let mut data = fetched.map_err(query_error)?;
+
+    let mut loaded = 0;
+    if data.len() > length as usize {
+        data.pop();
+        loaded = 1;
+    }
+
+    let next_cursor = match data.last() {
+        Some(last_row) if loaded == 1 => encode_cursor(&KeysetCursor {
+            keys: key_columns.iter().map(|c| c.name.clone()).collect(),
+            values: key_columns
+                .iter()
+                .map(|c| last_row.get(&c.name).cloned().unwrap_or(Value::Null))
+                .collect(),
+        }),
+        _ => None,
+    };
+
+    Ok(Some(LoadedMoreResponse {
+        loaded,
+        data,
+        cursor: next_cursor,
+    }))
+}
@@ -0,0 +1,638 @@
+use std::collections::HashMap;
+use std::ops::ControlFlow;
+
+use chrono::Utc;
+use diesel::prelude::*;
+use poem::IntoResponse;
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Json, Path},
+};
+use regex::{Regex, RegexBuilder};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use sqlparser::ast::{Expr, Query, SelectItem, SetExpr, Statement, Visit, Visitor};
+use sqlparser::parser::Parser;
+use validator::Validate;
+
+use crate::auth::middleware::ensure_admin;
+use crate::db::DbPool;
+use crate::models::common::DataResponse;
+use crate::models::external::database_mask::{DatabaseMask, EntryDatabaseMask};
+use crate::schema::{tbl_ext_database, tbl_ext_database_mask, tbl_user_role};
+use crate::utils::common::{self, validate_id, validation_error_response};
+use crate::utils::database::{parse_statement_info, sql_dialect};
+
+// Urutan dari yang paling tertutup, dipakai saat beberapa aturan cocok dengan kolom yang sama
+const STRATEGY_LIST: [&str; 4] = ["redact", "hash", "format", "partial"];
+const REDACTED_VALUE: &str = "****";
+
+struct MaskRule {
+    mask: DatabaseMask,
+    pattern: Option<Regex>,
+}
+
+pub struct MaskPolicy {
+    rules: Vec<MaskRule>,
+    // Kolom output hasil alias atau ekspresi dari kolom tersamarkan, menunjuk indeks aturan
+    derived: HashMap<String, usize>,
+}
+
+struct ProjectionItem {
+    alias: Option<String>,
+    sources: Vec<String>,
+    is_plain: bool,
+}
+
+#[derive(Default)]
+struct ProjectionCollector {
+    items: Vec<ProjectionItem>,
+}
+
+fn collect_select_items(body: &SetExpr, items: &mut Vec<ProjectionItem>) {
+    match body {
+        SetExpr::Select(select) => {
+            for item in &select.projection {
+                let (alias, expr) = match item {
+                    SelectItem::UnnamedExpr(expr) => (None, expr),
+                    SelectItem::ExprWithAlias { expr, alias } => (Some(alias.value.clone()), expr),
+                    _ => continue,
+                };
+                let mut sources = SourceColumnCollector::default();
+                let _ = expr.visit(&mut sources);
+                items.push(ProjectionItem {
+                    alias,
+                    sources: sources.columns,
+                    is_plain: matches!(expr, Expr::Identifier(_) | Expr::CompoundIdentifier(_)),
+                });
+            }
+        }
+        SetExpr::SetOperation { left, right, .. } => {
+            collect_select_items(left, items);
+            collect_select_items(right, items);
+        }
+        _ => {}
+    }
+}
+
+impl Visitor for ProjectionCollector {
+    type Break = ();
+
+    fn pre_visit_query(&mut self, query: &Query) -> ControlFlow<Self::Break> {
+        collect_select_items(&query.body, &mut self.items);
+        ControlFlow::Continue(())
+    }
+}
+
+#[derive(Default)]
+struct SourceColumnCollector {
+    columns: Vec<String>,
+}
+
+impl Visitor for SourceColumnCollector {
+    type Break = ();
+
+    fn pre_visit_expr(&mut self, expr: &Expr) -> ControlFlow<Self::Break> {
+        match expr {
+            Expr::Identifier(ident) => self.columns.push(ident.value.clone()),
+            Expr::CompoundIdentifier(parts) => {
+                if let Some(ident) = parts.last() {
+                    self.columns.push(ident.value.clone());
+                }
+            }
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+fn compile_pattern(pattern: &str) -> Option<Regex> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .build()
+        .ok()
+}
+
+fn strategy_rank(strategy: &str) -> usize {
+    STRATEGY_LIST
+        .iter()
+        .position(|s| *s == strategy)
+        .unwrap_or(0)
+}
+
+fn normalize_name(name: &str) -> String {
+    name.split('.')
+        .map(|part| part.trim_matches(|c| c == '"' || c == '`'))
+        .collect::<Vec<_>>()
+        .join(".")
+        .to_lowercase()
+}
+
+// Aturan tanpa schema cocok dengan nama tabel terakhir, aturan dengan schema harus sama persis
+fn is_same_table(rule_table: &str, query_table: &str) -> bool {
+    let rule_table = normalize_name(rule_table);
+    let query_table = normalize_name(query_table);
+    rule_table == query_table
+        || (!rule_table.contains('.')
+            && query_table.rsplit('.').next() == Some(rule_table.as_str()))
+}
+
+fn mask_text(mask: &DatabaseMask, text: &str) -> String {
+    match mask.strategy.as_str() {
+        "partial" => {
+            let chars: Vec<char> = text.chars().collect();
+            let prefix = mask.visible_prefix.max(0) as usize;
+            let suffix = mask.visible_suffix.max(0) as usize;
+            if prefix + suffix >= chars.len() {
+                return "*".repeat(chars.len());
+            }
+            chars
+                .iter()
+                .enumerate()
+                .map(|(i, c)| {
+                    if i < prefix || i >= chars.len() - suffix {
+                        *c
+                    } else {
+                        '*'
+                    }
+                })
+                .collect()
+        }
+        "hash" => Sha256::digest(text.as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect(),
+        // Karakter # pada format diisi karakter asli di posisi yang sama, sisanya tetap literal
+        "format" => {
+            let chars: Vec<char> = text.chars().collect();
+            mask.mask_format
+                .as_deref()
+                .unwrap_or(REDACTED_VALUE)
+                .chars()
+                .enumerate()
+                .map(|(i, c)| match c {
+                    '#' => chars.get(i).copied().unwrap_or('*'),
+                    other => other,
+                })
+                .collect()
+        }
+        _ => REDACTED_VALUE.to_string(),
+    }
+}
+
+fn rule_index(rules: &[MaskRule], derived: &HashMap<String, usize>, column: &str) -> Option<usize> {
+    rules
+        .iter()
+        .enumerate()
+        .filter(|(_, rule)| {
+            rule.mask
+                .column_name
+                .as_deref()
+                .is_some_and(|name| name.eq_ignore_ascii_case(column))
+                || rule
+                    .pattern
+                    .as_ref()
+                    .is_some_and(|pattern| pattern.is_match(column))
+        })
+        .map(|(i, _)| i)
+        .chain(derived.get(&column.to_lowercase()).copied())
+        .min_by_key(|i| strategy_rank(&rules[*i].mask.strategy))
+}
+
+// Alias dan ekspresi ditelusuri berulang sampai stabil agar turunan bertingkat (subquery, CTE) ikut tertutup.
+// Ekspresi tanpa alias ditolak karena nama kolom hasilnya berbeda di tiap database.
+fn derived_columns(
+    rules: &[MaskRule],
+    statement: &Statement,
+) -> poem::Result<HashMap<String, usize>> {
+    let mut collector = ProjectionCollector::default();
+    let _ = statement.visit(&mut collector);
+
+    let mut derived: HashMap<String, usize> = HashMap::new();
+    loop {
+        let mut is_changed = false;
+        for item in &collector.items {
+            let Some(index) = item
+                .sources
+                .iter()
+                .filter_map(|column| rule_index(rules, &derived, column))
+                .min_by_key(|i| strategy_rank(&rules[*i].mask.strategy))
+            else {
+                continue;
+            };
+            match &item.alias {
+                Some(alias) => {
+                    let alias = alias.to_lowercase();
+                    if derived.get(&alias) != Some(&index) {
+                        derived.insert(alias, index);
+                        is_changed = true;
+                    }
+                }
+                None if !item.is_plain => {
+                    return Err(common::error_message(
+                        StatusCode::FORBIDDEN,
+                        "mask.unnamedDerivedColumn",
+                    ));
+                }
+                None => {}
+            }
+        }
+        if !is_changed {
+            return Ok(derived);
+        }
+    }
+}
+
+impl MaskPolicy {
+    fn rule_for(&self, column: &str) -> Option<&DatabaseMask> {
+        rule_index(&self.rules, &self.derived, column).map(|i| &self.rules[i].mask)
+    }
+
+    pub fn is_masked(&self, column: &str) -> bool {
+        self.rule_for(column).is_some()
+    }
+
+    pub fn mask_row(&self, row: &mut Map<String, Value>) {
+        if self.rules.is_empty() {
+            return;
+        }
+        for (column, value) in row.iter_mut() {
+            if let Some(mask) = self.rule_for(column) {
+                *value = match &*value {
+                    Value::Null => Value::Null,
+                    Value::String(text) => Value::String(mask_text(mask, text)),
+                    other => Value::String(mask_text(mask, &other.to_string())),
+                };
+            }
+        }
+    }
+
+    pub fn mask_rows(&self, rows: &mut [Value]) {
+        for row in rows.iter_mut() {
+            if let Value::Object(map) = row {
+                self.mask_row(map);
+            }
+        }
+    }
+
+    // Kolom tersamarkan selalu berupa teks sehingga export bertipe (parquet, arrow, SQL) tetap konsisten
+    pub fn mask_columns(&self, columns: &mut [Value]) {
+        for column in columns.iter_mut() {
+            let is_masked = column
+                .get("name")
+                .and_then(Value::as_str)
+                .is_some_and(|name| self.is_masked(name));
+            if is_masked && let Value::Object(map) = column {
+                map.insert("type".to_string(), Value::String("TEXT".to_string()));
+                map.insert("logicalType".to_string(), Value::String("text".to_string()));
+            }
+        }
+    }
+}
+
+pub fn user_roles(conn: &mut PgConnection, user_id: i64) -> Vec<i16> {
+    tbl_user_role::table
+        .filter(tbl_user_role::user_id.eq(user_id))
+        .filter(tbl_user_role::is_del.eq(0))
+        .select(tbl_user_role::mt_role_id)
+        .load::<i16>(conn)
+        .unwrap_or_default()
+}
+
+// Aturan bertabel hanya berlaku jika query menyentuh tabel tersebut; query yang tidak bisa diurai ditolak selama ada aturan
+pub fn load_mask_policy(
+    conn: &mut PgConnection,
+    ext_database_id: i64,
+    roles: &[i16],
+    query: &str,
+) -> poem::Result<MaskPolicy> {
+    let masks = tbl_ext_database_mask::table
+        .filter(tbl_ext_database_mask::ext_database_id.eq(ext_database_id))
+        .filter(tbl_ext_database_mask::is_del.eq(0))
+        .filter(
+            tbl_ext_database_mask::mt_role_id
+                .is_null()
+                .or(tbl_ext_database_mask::mt_role_id.eq_any(roles.to_vec())),
+        )
+        .load::<DatabaseMask>(conn)
+        .map_err(|e| {
+            eprintln!("Loading error: {}", e);
+            common::error_message(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "information.internalServerError",
+            )
+        })?;
+
+    if masks.is_empty() {
+        return Ok(MaskPolicy {
+            rules: vec![],
+            derived: HashMap::new(),
+        });
+    }
+
+    let mt_database_type_id = tbl_ext_database::table
+        .filter(tbl_ext_database::id.eq(ext_database_id))
+        .select(tbl_ext_database::mt_database_type_id)
+        .first::<i16>(conn)
+        .map_err(|_| common::error_message(StatusCode::NOT_FOUND, "information.notFound"))?;
+
+    let dialect = sql_dialect(mt_database_type_id == 2);
+    let tables = parse_statement_info(query, dialect.as_ref())
+        .ok()
+        .map(|statement| statement.tables);
+    let statement = Parser::parse_sql(dialect.as_ref(), query)
+        .ok()
+        .filter(|statements| statements.len() == 1)
+        .and_then(|mut statements| statements.pop());
+
+    let rules = masks
+        .into_iter()
+        .filter(|mask| match (&mask.table_name, &tables) {
+            (Some(table_name), Some(tables)) if !table_name.trim().is_empty() => {
+                tables.iter().any(|table| is_same_table(table_name, table))
+            }
+            _ => true,
+        })
+        .map(|mask| MaskRule {
+            pattern: mask.column_pattern.as_deref().and_then(compile_pattern),
+            mask,
+        })
+        .collect::<Vec<_>>();
+
+    if rules.is_empty() {
+        return Ok(MaskPolicy {
+            rules,
+            derived: HashMap::new(),
+        });
+    }
+
+    // Tanpa proyeksi yang terurai, alias kolom tersamarkan tidak bisa dilacak sehingga query ditolak
+    let Some(statement) = statement else {
+        return Err(common::error_message(
+            StatusCode::FORBIDDEN,
+            "mask.unparseable",
+        ));
+    };
+    let derived = derived_columns(&rules, &statement)?;
+
+    Ok(MaskPolicy { rules, derived })
+}
+
+fn validate_mask(entry_mask: &EntryDatabaseMask) -> poem::Result<()> {
+    if !STRATEGY_LIST.contains(&entry_mask.strategy.as_str()) {
+        return Err(common::error_message(
+            StatusCode::BAD_REQUEST,
+            "mask.invalidStrategy",
+        ));
+    }
+
+    let column_name = entry_mask
+        .column_name
+        .as_deref()
+        .filter(|name| !name.trim().is_empty());
+    let column_pattern = entry_mask
+        .column_pattern
+        .as_deref()
+        .filter(|pattern| !pattern.trim().is_empty());
+    if column_name.is_none() && column_pattern.is_none() {
+        return Err(common::error_message(
+            StatusCode::BAD_REQUEST,
+            "mask.columnRequired",
+        ));
+    }
+    if column_pattern.is_some_and(|pattern| compile_pattern(pattern).is_none()) {
+        return Err(common::error_message(
+            StatusCode::BAD_REQUEST,
+            "mask.invalidPattern",
+        ));
+    }
+
+    if entry_mask.strategy == "format"
+        && entry_mask
+            .mask_format
+            .as_deref()
+            .is_none_or(|format| format.is_empty())
+    {
+        return Err(common::error_message(
+            StatusCode::BAD_REQUEST,
+            "mask.formatRequired",
+        ));
+    }
+
+    Ok(())
+}
+
+fn get_mask(conn: &mut PgConnection, mask_id: i64) -> poem::Result<DatabaseMask> {
+    tbl_ext_database_mask::table
+        .filter(tbl_ext_database_mask::id.eq(mask_id))
+        .filter(tbl_ext_database_mask::is_del.eq(0))
+        .first::<DatabaseMask>(conn)
+        .map_err(|_| common::error_message(StatusCode::NOT_FOUND, "information.notFound"))
+}
+
+#[handler]
+pub fn mask_list(
+    pool: poem::web::Data<&DbPool>,
+    _: crate::auth::middleware::JwtAuth,
+    Path(ext_database_id): Path<i64>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(ext_database_id)?;
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    let data = tbl_ext_database_mask::table
+        .filter(tbl_ext_database_mask::ext_database_id.eq(ext_database_id))
+        .filter(tbl_ext_database_mask::is_del.eq(0))
+        .order(tbl_ext_database_mask::dt_created.asc())
+        .load::<DatabaseMask>(conn)
+        .map_err(|e| {
+            eprintln!("Loading error: {}", e);
+            common::error_message(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "information.internalServerError",
+            )
+        })?;
+
+    Ok(Json(DataResponse { data }))
+}
+
+#[handler]
+pub fn mask_get(
+    pool: poem::web::Data<&DbPool>,
+    _: crate::auth::middleware::JwtAuth,
+    Path(mask_id): Path<i64>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(mask_id)?;
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    Ok(Json(DataResponse {
+        data: get_mask(conn, mask_id)?,
+    }))
+}
+
+#[handler]
+pub fn mask_add(
+    pool: poem::web::Data<&DbPool>,
+    jwt_auth: crate::auth::middleware::JwtAuth,
+    Path(ext_database_id): Path<i64>,
+    Json(entry_mask): Json<EntryDatabaseMask>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(ext_database_id)?;
+
+    if let Err(e) = entry_mask.validate() {
+        return Err(validation_error_response(e));
+    }
+    validate_mask(&entry_mask)?;
+
+    ensure_admin(&jwt_auth.claims)?;
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    tbl_ext_database::table
+        .filter(tbl_ext_database::id.eq(ext_database_id))
+        .filter(tbl_ext_database::is_del.eq(0))
+        .select(tbl_ext_database::id)
+        .first::<i64>(conn)
+        .map_err(|_| common::error_message(StatusCode::NOT_FOUND, "information.notFound"))?;
+
+    let mask = DatabaseMask {
+        id: common::generate_id(),
+        ext_database_id,
+        table_name: entry_mask.table_name,
+        column_name: entry_mask.column_name,
+        column_pattern: entry_mask.column_pattern,
+        strategy: entry_mask.strategy,
+        visible_prefix: entry_mask.visible_prefix,
+        visible_suffix: entry_mask.visible_suffix,
+        mask_format: entry_mask.mask_format,
+        mt_role_id: entry_mask.mt_role_id,
+        is_del: 0,
+        created_by: jwt_auth.claims.id,
+        dt_created: Utc::now().naive_utc(),
+        updated_by: None,
+        dt_updated: None,
+        version: 0,
+    };
+
+    let inserted = diesel::insert_into(tbl_ext_database_mask::table)
+        .values(&mask)
+        .get_result::<DatabaseMask>(conn)
+        .map_err(|e| {
+            eprintln!("Inserting error: {}", e);
+            common::error_message(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "information.internalServerError",
+            )
+        })?;
+
+    Ok((StatusCode::CREATED, Json(DataResponse { data: inserted })))
+}
+
+#[handler]
+pub fn mask_update(
+    pool: poem::web::Data<&DbPool>,
+    jwt_auth: crate::auth::middleware::JwtAuth,
+    Path(mask_id): Path<i64>,
+    Json(mut entry_mask): Json<EntryDatabaseMask>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(mask_id)?;
+
+    if let Err(e) = entry_mask.validate() {
+        return Err(validation_error_response(e));
+    }
+    validate_mask(&entry_mask)?;
+
+    ensure_admin(&jwt_auth.claims)?;
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    entry_mask.version += 1;
+
+    let updated = diesel::update(
+        tbl_ext_database_mask::table
+            .filter(tbl_ext_database_mask::id.eq(mask_id))
+            .filter(tbl_ext_database_mask::is_del.eq(0))
+            .filter(tbl_ext_database_mask::version.eq(entry_mask.version - 1)),
+    )
+    .set((
+        &entry_mask,
+        tbl_ext_database_mask::updated_by.eq(Some(jwt_auth.claims.id)),
+        tbl_ext_database_mask::dt_updated.eq(Some(Utc::now().naive_utc())),
+    ))
+    .get_result::<DatabaseMask>(conn)
+    .map_err(|e| {
+        eprintln!("Updating error: {}", e);
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.internalServerError",
+        )
+    })?;
+
+    Ok(Json(DataResponse { data: updated }))
+}
+
+#[handler]
+pub fn mask_delete(
+    pool: poem::web::Data<&DbPool>,
+    jwt_auth: crate::auth::middleware::JwtAuth,
+    Path(mask_id): Path<i64>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(mask_id)?;
+
+    ensure_admin(&jwt_auth.claims)?;
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    diesel::update(
+        tbl_ext_database_mask::table
+            .filter(tbl_ext_database_mask::id.eq(mask_id))
+            .filter(tbl_ext_database_mask::is_del.eq(0)),
+    )
+    .set((
+        tbl_ext_database_mask::is_del.eq(1),
+        tbl_ext_database_mask::updated_by.eq(Some(jwt_auth.claims.id)),
+        tbl_ext_database_mask::dt_updated.eq(Some(Utc::now().naive_utc())),
+    ))
+    .get_result::<DatabaseMask>(conn)
+    .map_err(|e| match e {
+        diesel::result::Error::NotFound => {
+            common::error_message(StatusCode::NOT_FOUND, "information.notFound")
+        }
+        e => {
+            eprintln!("Soft Deleting error: {}", e);
+            common::error_message(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "information.internalServerError",
+            )
+        }
+    })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
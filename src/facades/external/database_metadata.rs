@@ -0,0 +1,315 @@
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use diesel::prelude::*;
+use poem::web::{Json, Query};
+use poem::{IntoResponse, handler, http::StatusCode, web::Path};
+use sqlx::{MySql, Pool, Postgres};
+
+use crate::database_pool::DatabasePool;
+use crate::db::DbPool;
+use crate::facades::external::database_query::get_external_pool;
+use crate::models::common::DataResponse;
+use crate::models::external::database_metadata::{
+    DatabaseMetadata, MetadataColumn, MetadataFunction, MetadataObject, MetadataParam,
+};
+use crate::schema::tbl_ext_database;
+use crate::utils::common::{self, validate_id};
+
+const METADATA_CACHE_TTL: Duration = Duration::from_secs(600);
+
+const METADATA_CACHE_CAPACITY: usize = 100;
+
+// Cache per ext_database_id, dibuang setelah DDL dijalankan atau konfigurasi database diubah
+static METADATA_CACHE: LazyLock<Mutex<HashMap<i64, (Instant, DatabaseMetadata)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+type ColumnRow = (String, String, String, Option<String>, Option<String>);
+type FunctionRow = (String, String, String, String, Option<String>);
+
+fn query_error(e: sqlx::Error) -> poem::Error {
+    eprintln!("Query error: {}", e);
+    poem::Error::from_status(StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+fn cached_metadata(ext_database_id: i64) -> Option<DatabaseMetadata> {
+    let cache = METADATA_CACHE.lock().ok()?;
+    cache
+        .get(&ext_database_id)
+        .filter(|(dt_loaded, _)| dt_loaded.elapsed() < METADATA_CACHE_TTL)
+        .map(|(_, metadata)| metadata.clone())
+}
+
+// Entri kedaluwarsa dibuang saat menyimpan, jika masih penuh entri tertua yang dikeluarkan
+fn store_metadata(ext_database_id: i64, metadata: &DatabaseMetadata) {
+    if let Ok(mut cache) = METADATA_CACHE.lock() {
+        cache.retain(|_, (dt_loaded, _)| dt_loaded.elapsed() < METADATA_CACHE_TTL);
+        if cache.len() >= METADATA_CACHE_CAPACITY
+            && !cache.contains_key(&ext_database_id)
+            && let Some(oldest) = cache
+                .iter()
+                .min_by_key(|(_, (dt_loaded, _))| *dt_loaded)
+                .map(|(id, _)| *id)
+        {
+            cache.remove(&oldest);
+        }
+        cache.insert(ext_database_id, (Instant::now(), metadata.clone()));
+    }
+}
+
+pub fn invalidate_metadata(ext_database_id: i64) {
+    if let Ok(mut cache) = METADATA_CACHE.lock() {
+        cache.remove(&ext_database_id);
+    }
+}
+
+// Baris kolom sudah terurut per objek sehingga cukup dikelompokkan berurutan
+fn group_columns(rows: Vec<ColumnRow>) -> Vec<MetadataObject> {
+    let mut objects: Vec<MetadataObject> = Vec::new();
+    for (schema, nm, object_type, column_nm, data_type) in rows {
+        if objects
+            .last()
+            .is_none_or(|object| object.schema != schema || object.nm != nm)
+        {
+            objects.push(MetadataObject {
+                schema,
+                nm,
+                object_type,
+                columns: Vec::new(),
+            });
+        }
+        if let (Some(object), Some(column_nm)) = (objects.last_mut(), column_nm) {
+            object.columns.push(MetadataColumn {
+                nm: column_nm,
+                data_type: data_type.unwrap_or_default(),
+            });
+        }
+    }
+    objects
+}
+
+fn to_functions(rows: Vec<FunctionRow>) -> Vec<MetadataFunction> {
+    rows.into_iter()
+        .map(
+            |(schema, nm, object_type, arguments, return_type)| MetadataFunction {
+                signature: format!("{}({})", nm, arguments),
+                schema,
+                nm,
+                object_type,
+                return_type,
+            },
+        )
+        .collect()
+}
+
+async fn load_metadata_postgres(pool: &Pool<Postgres>) -> poem::Result<DatabaseMetadata> {
+    let schemas = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT nspname::TEXT
+        FROM pg_catalog.pg_namespace
+        WHERE nspname NOT LIKE 'pg\_%'
+        AND nspname <> 'information_schema'
+        ORDER BY 1
+    "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(query_error)?;
+
+    let columns = sqlx::query_as::<_, ColumnRow>(
+        r#"
+        SELECT
+            n.nspname::TEXT AS schema_name,
+            c.relname::TEXT AS object_name,
+            CASE c.relkind
+                WHEN 'v' THEN 'view'
+                WHEN 'm' THEN 'materialized view'
+                WHEN 'f' THEN 'foreign table'
+                ELSE 'table'
+            END AS object_type,
+            a.attname::TEXT AS column_name,
+            pg_catalog.format_type(a.atttypid, a.atttypmod) AS data_type
+        FROM pg_catalog.pg_class c
+        JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+        LEFT JOIN pg_catalog.pg_attribute a
+            ON a.attrelid = c.oid AND a.attnum > 0 AND NOT a.attisdropped
+        WHERE c.relkind IN ('r', 'p', 'v', 'm', 'f')
+        AND n.nspname NOT LIKE 'pg\_%'
+        AND n.nspname <> 'information_schema'
+        ORDER BY n.nspname, c.relname, a.attnum
+    "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(query_error)?;
+
+    let functions = sqlx::query_as::<_, FunctionRow>(
+        r#"
+        SELECT
+            n.nspname::TEXT AS schema_name,
+            p.proname::TEXT AS name,
+            CASE p.prokind WHEN 'p' THEN 'procedure' ELSE 'function' END AS object_type,
+            pg_catalog.pg_get_function_identity_arguments(p.oid) AS arguments,
+            pg_catalog.pg_get_function_result(p.oid) AS return_type
+        FROM pg_catalog.pg_proc p
+        JOIN pg_catalog.pg_namespace n ON n.oid = p.pronamespace
+        WHERE p.prokind IN ('f', 'p')
+        AND n.nspname NOT LIKE 'pg\_%'
+        AND n.nspname <> 'information_schema'
+        ORDER BY 1, 2
+    "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(query_error)?;
+
+    let keywords = sqlx::query_scalar::<_, String>(
+        "SELECT upper(word) FROM pg_catalog.pg_get_keywords() ORDER BY 1",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(query_error)?;
+
+    Ok(DatabaseMetadata {
+        schemas,
+        objects: group_columns(columns),
+        functions: to_functions(functions),
+        keywords,
+        dt_cached: Utc::now().naive_utc(),
+    })
+}
+
+async fn load_metadata_mysql(pool: &Pool<MySql>) -> poem::Result<DatabaseMetadata> {
+    let schemas = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT CAST(SCHEMA_NAME AS CHAR)
+        FROM information_schema.SCHEMATA
+        WHERE SCHEMA_NAME NOT IN ('mysql', 'information_schema', 'performance_schema', 'sys')
+        ORDER BY 1
+    "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(query_error)?;
+
+    let columns = sqlx::query_as::<_, ColumnRow>(
+        r#"
+        SELECT
+            CAST(t.TABLE_SCHEMA AS CHAR) AS schema_name,
+            CAST(t.TABLE_NAME AS CHAR) AS object_name,
+            CAST(CASE WHEN t.TABLE_TYPE = 'VIEW' THEN 'view' ELSE 'table' END AS CHAR) AS object_type,
+            CAST(c.COLUMN_NAME AS CHAR) AS column_name,
+            CAST(c.COLUMN_TYPE AS CHAR) AS data_type
+        FROM information_schema.TABLES t
+        LEFT JOIN information_schema.COLUMNS c
+            ON c.TABLE_SCHEMA = t.TABLE_SCHEMA AND c.TABLE_NAME = t.TABLE_NAME
+        WHERE t.TABLE_SCHEMA NOT IN ('mysql', 'information_schema', 'performance_schema', 'sys')
+        ORDER BY t.TABLE_SCHEMA, t.TABLE_NAME, c.ORDINAL_POSITION
+    "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(query_error)?;
+
+    let functions = sqlx::query_as::<_, FunctionRow>(
+        r#"
+        SELECT
+            CAST(r.ROUTINE_SCHEMA AS CHAR) AS schema_name,
+            CAST(r.ROUTINE_NAME AS CHAR) AS name,
+            CAST(LOWER(r.ROUTINE_TYPE) AS CHAR) AS object_type,
+            CAST(COALESCE(GROUP_CONCAT(
+                CONCAT_WS(' ', p.PARAMETER_MODE, p.PARAMETER_NAME, p.DTD_IDENTIFIER)
+                ORDER BY p.ORDINAL_POSITION SEPARATOR ', '
+            ), '') AS CHAR) AS arguments,
+            CAST(r.DTD_IDENTIFIER AS CHAR) AS return_type
+        FROM information_schema.ROUTINES r
+        LEFT JOIN information_schema.PARAMETERS p
+            ON p.SPECIFIC_SCHEMA = r.ROUTINE_SCHEMA
+            AND p.SPECIFIC_NAME = r.ROUTINE_NAME
+            AND p.ORDINAL_POSITION > 0
+        WHERE r.ROUTINE_SCHEMA NOT IN ('mysql', 'information_schema', 'performance_schema', 'sys')
+        GROUP BY r.ROUTINE_SCHEMA, r.ROUTINE_NAME, r.ROUTINE_TYPE, r.DTD_IDENTIFIER
+        ORDER BY 1, 2
+    "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(query_error)?;
+
+    // information_schema.KEYWORDS baru ada sejak MySQL 8.0, versi lain memakai daftar dari parser
+    let keywords = match sqlx::query_scalar::<_, String>(
+        "SELECT CAST(WORD AS CHAR) FROM information_schema.KEYWORDS ORDER BY 1",
+    )
+    .fetch_all(pool)
+    .await
+    {
+        Ok(keywords) => keywords,
+        Err(_) => sqlparser::keywords::ALL_KEYWORDS
+            .iter()
+            .map(|word| word.to_string())
+            .collect(),
+    };
+
+    Ok(DatabaseMetadata {
+        schemas,
+        objects: group_columns(columns),
+        functions: to_functions(functions),
+        keywords,
+        dt_cached: Utc::now().naive_utc(),
+    })
+}
+
+#[handler]
+pub async fn metadata_get(
+    pool: poem::web::Data<&DbPool>,
+    _: crate::auth::middleware::JwtAuth,
+    Path(ext_database_id): Path<i64>,
+    Query(param): Query<MetadataParam>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(ext_database_id)?;
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    // Cache tidak dipakai lagi untuk database yang sudah dihapus
+    let is_exist = tbl_ext_database::table
+        .filter(tbl_ext_database::id.eq(ext_database_id))
+        .filter(tbl_ext_database::is_del.eq(0))
+        .select(tbl_ext_database::id)
+        .first::<i64>(conn)
+        .is_ok();
+    if !is_exist {
+        invalidate_metadata(ext_database_id);
+        return Err(common::error_message(
+            StatusCode::NOT_FOUND,
+            "information.notFound",
+        ));
+    }
+
+    if param.is_refresh != Some(1)
+        && let Some(metadata) = cached_metadata(ext_database_id)
+    {
+        return Ok(Json(DataResponse { data: metadata }));
+    }
+
+    let (ext_pool, tunnel, _, _) = get_external_pool(conn, ext_database_id).await?;
+    let metadata = match &ext_pool {
+        DatabasePool::Postgres(pg_pool) => load_metadata_postgres(pg_pool).await,
+        DatabasePool::MySql(my_pool) => load_metadata_mysql(my_pool).await,
+    };
+
+    if let Some(mut tunnel) = tunnel {
+        let _ = tunnel.kill().ok();
+    };
+
+    let metadata = metadata?;
+    store_metadata(ext_database_id, &metadata);
+
+    Ok(Json(DataResponse { data: metadata }))
+}
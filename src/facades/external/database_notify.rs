@@ -0,0 +1,339 @@
+use std::collections::HashMap;
+use std::process::Child;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+use chrono::Utc;
+use futures_util::{SinkExt, StreamExt};
+use poem::web::Query;
+use poem::web::websocket::{Message, WebSocket};
+use poem::{IntoResponse, handler, http::StatusCode, web::Path};
+use regex::Regex;
+use sqlx::postgres::PgListener;
+use sqlx::{Pool, Postgres};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, mpsc, oneshot};
+
+use crate::database_pool::DatabasePool;
+use crate::db::DbPool;
+use crate::facades::external::database_query::get_external_pool;
+use crate::models::external::database_notify::{NotifyMessage, NotifyParam};
+use crate::utils::common::{self, validate_id};
+
+const MAX_CHANNEL: usize = 10;
+const SUBSCRIBER_BUFFER: usize = 256;
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+static CHANNEL_NAME: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[A-Za-z_][A-Za-z0-9_$]{0,62}$").unwrap());
+
+type ListenerKey = (i64, String);
+
+// Satu koneksi LISTEN per database dan channel, dipakai bersama oleh semua subscriber
+struct ChannelListener {
+    id: i64,
+    sender: broadcast::Sender<NotifyMessage>,
+    _stop: oneshot::Sender<()>,
+}
+
+static LISTENERS: LazyLock<Mutex<HashMap<ListenerKey, ChannelListener>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn notify_message(channel: &str, message: &str) -> NotifyMessage {
+    NotifyMessage {
+        channel: channel.to_string(),
+        payload: None,
+        process_id: None,
+        message: Some(message.to_string()),
+        dt_received: Utc::now().naive_utc(),
+    }
+}
+
+fn subscribe_existing(key: &ListenerKey) -> Option<broadcast::Receiver<NotifyMessage>> {
+    let listeners = LISTENERS.lock().ok()?;
+    listeners
+        .get(key)
+        .map(|listener| listener.sender.subscribe())
+}
+
+// Listener dilepas saat tidak ada subscriber, stop sender ikut terhapus sehingga task berhenti
+fn release_listener(key: &ListenerKey) {
+    if let Ok(mut listeners) = LISTENERS.lock()
+        && listeners
+            .get(key)
+            .is_some_and(|listener| listener.sender.receiver_count() == 0)
+    {
+        listeners.remove(key);
+    }
+}
+
+fn remove_listener(key: &ListenerKey, id: i64) {
+    if let Ok(mut listeners) = LISTENERS.lock()
+        && listeners.get(key).is_some_and(|listener| listener.id == id)
+    {
+        listeners.remove(key);
+    }
+}
+
+enum Registration {
+    Existing(broadcast::Receiver<NotifyMessage>),
+    Created(
+        i64,
+        broadcast::Sender<NotifyMessage>,
+        broadcast::Receiver<NotifyMessage>,
+        oneshot::Receiver<()>,
+    ),
+}
+
+fn register_listener(key: &ListenerKey) -> poem::Result<Registration> {
+    let mut listeners = LISTENERS.lock().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.internalServerError",
+        )
+    })?;
+
+    if let Some(existing) = listeners.get(key) {
+        return Ok(Registration::Existing(existing.sender.subscribe()));
+    }
+
+    let id = common::generate_id();
+    let (sender, receiver) = broadcast::channel(SUBSCRIBER_BUFFER);
+    let (stop_sender, stop_receiver) = oneshot::channel();
+    listeners.insert(
+        key.clone(),
+        ChannelListener {
+            id,
+            sender: sender.clone(),
+            _stop: stop_sender,
+        },
+    );
+    Ok(Registration::Created(id, sender, receiver, stop_receiver))
+}
+
+async fn run_listener(
+    key: ListenerKey,
+    id: i64,
+    mut listener: PgListener,
+    pg_pool: Pool<Postgres>,
+    tunnel: Option<Child>,
+    sender: broadcast::Sender<NotifyMessage>,
+    mut stop: oneshot::Receiver<()>,
+) {
+    let mut idle_check = tokio::time::interval(IDLE_CHECK_INTERVAL);
+    idle_check.tick().await;
+
+    loop {
+        tokio::select! {
+            _ = &mut stop => break,
+            // Subscriber yang gagal upgrade tidak sempat melepas listener
+            _ = idle_check.tick() => release_listener(&key),
+            received = listener.recv() => match received {
+                Ok(notification) => {
+                    let _ = sender.send(NotifyMessage {
+                        channel: notification.channel().to_string(),
+                        payload: Some(notification.payload().to_string()),
+                        process_id: Some(notification.process_id()),
+                        message: None,
+                        dt_received: Utc::now().naive_utc(),
+                    });
+                }
+                Err(e) => {
+                    eprintln!("Query error: {}", e);
+                    let _ = sender.send(notify_message(&key.1, "notify.connectionLost"));
+                    remove_listener(&key, id);
+                    break;
+                }
+            },
+        }
+    }
+
+    drop(listener);
+    pg_pool.close().await;
+    if let Some(mut tunnel) = tunnel {
+        let _ = tunnel.kill().ok();
+    };
+}
+
+async fn subscribe(
+    conn: &mut diesel::PgConnection,
+    ext_database_id: i64,
+    channel: &str,
+) -> poem::Result<broadcast::Receiver<NotifyMessage>> {
+    let key = (ext_database_id, channel.to_string());
+    if let Some(receiver) = subscribe_existing(&key) {
+        return Ok(receiver);
+    }
+
+    let (ext_pool, mut tunnel, _, _) = get_external_pool(conn, ext_database_id).await?;
+    let kill_tunnel = |tunnel: &mut Option<Child>| {
+        if let Some(mut tunnel) = tunnel.take() {
+            let _ = tunnel.kill().ok();
+        };
+    };
+
+    let DatabasePool::Postgres(pg_pool) = ext_pool else {
+        kill_tunnel(&mut tunnel);
+        return Err(common::error_message(
+            StatusCode::BAD_REQUEST,
+            "notify.postgresOnly",
+        ));
+    };
+
+    let listener = async {
+        let mut listener = PgListener::connect_with(&pg_pool).await?;
+        listener.listen(channel).await?;
+        Ok::<_, sqlx::Error>(listener)
+    }
+    .await;
+    let listener = match listener {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Query error: {}", e);
+            pg_pool.close().await;
+            kill_tunnel(&mut tunnel);
+            return Err(common::error_message(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "notify.listenFailed",
+            ));
+        }
+    };
+
+    // Subscriber lain bisa lebih dulu membuat listener selama koneksi dibuka
+    match register_listener(&key)? {
+        Registration::Existing(receiver) => {
+            drop(listener);
+            pg_pool.close().await;
+            kill_tunnel(&mut tunnel);
+            Ok(receiver)
+        }
+        Registration::Created(id, sender, receiver, stop_receiver) => {
+            tokio::spawn(run_listener(
+                key,
+                id,
+                listener,
+                pg_pool,
+                tunnel,
+                sender,
+                stop_receiver,
+            ));
+            Ok(receiver)
+        }
+    }
+}
+
+fn parse_channels(channels: &str) -> poem::Result<Vec<String>> {
+    let mut result: Vec<String> = Vec::new();
+    for channel in channels.split(',').map(str::trim).filter(|c| !c.is_empty()) {
+        if !CHANNEL_NAME.is_match(channel) {
+            return Err(common::error_message(
+                StatusCode::BAD_REQUEST,
+                "notify.invalidChannel",
+            ));
+        }
+        if !result.iter().any(|c| c == channel) {
+            result.push(channel.to_string());
+        }
+    }
+
+    if result.is_empty() {
+        return Err(common::error_message(
+            StatusCode::BAD_REQUEST,
+            "notify.channelRequired",
+        ));
+    }
+    if result.len() > MAX_CHANNEL {
+        return Err(common::error_message(
+            StatusCode::BAD_REQUEST,
+            "notify.tooManyChannels",
+        ));
+    }
+    Ok(result)
+}
+
+#[handler]
+pub async fn notify_listen(
+    pool: poem::web::Data<&DbPool>,
+    _: crate::auth::middleware::JwtAuth,
+    Path(ext_database_id): Path<i64>,
+    Query(param): Query<NotifyParam>,
+    ws: WebSocket,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(ext_database_id)?;
+    let channels = parse_channels(&param.channels)?;
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    let mut receivers = Vec::new();
+    for channel in channels {
+        match subscribe(conn, ext_database_id, &channel).await {
+            Ok(receiver) => receivers.push((channel, receiver)),
+            Err(e) => {
+                let keys: Vec<ListenerKey> = receivers
+                    .into_iter()
+                    .map(|(channel, _)| (ext_database_id, channel))
+                    .collect();
+                keys.iter().for_each(release_listener);
+                return Err(e);
+            }
+        }
+    }
+
+    Ok(ws.on_upgrade(move |socket| async move {
+        let (mut sink, mut stream) = socket.split();
+        let (tx, mut rx) = mpsc::channel::<NotifyMessage>(SUBSCRIBER_BUFFER);
+
+        let mut keys = Vec::new();
+        let mut forwarders = Vec::new();
+        for (channel, mut receiver) in receivers {
+            keys.push((ext_database_id, channel.clone()));
+            let tx = tx.clone();
+            forwarders.push(tokio::spawn(async move {
+                loop {
+                    let message = match receiver.recv().await {
+                        Ok(message) => message,
+                        // Subscriber yang lambat kehilangan sebagian notifikasi, bukan koneksinya
+                        Err(RecvError::Lagged(_)) => notify_message(&channel, "notify.lagged"),
+                        Err(RecvError::Closed) => break,
+                    };
+                    if tx.send(message).await.is_err() {
+                        break;
+                    }
+                }
+            }));
+        }
+        drop(tx);
+
+        loop {
+            tokio::select! {
+                message = rx.recv() => match message {
+                    Some(message) => {
+                        let text = serde_json::to_string(&message).unwrap_or_default();
+                        if sink.send(Message::Text(text)).await.is_err() {
+                            break;
+                        }
+                    }
+                    None => break,
+                },
+                incoming = stream.next() => match incoming {
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    _ => {}
+                },
+            }
+        }
+
+        for forwarder in &forwarders {
+            forwarder.abort();
+        }
+        for forwarder in forwarders {
+            let _ = forwarder.await;
+        }
+        keys.iter().for_each(release_listener);
+        let _ = sink.close().await;
+    }))
+}
@@ -0,0 +1,1015 @@
+use poem::web::Json;
+use poem::{
+    IntoResponse, handler,
+    http::StatusCode,
+    web::{Path, Query},
+};
+use sqlx::mysql::MySqlRow;
+use sqlx::{MySql, Pool, Postgres, Row};
+
+use crate::database_pool::DatabasePool;
+use crate::db::DbPool;
+use crate::facades::external::database_query::{get_external_pool, resolve_schema};
+use crate::models::common::DataResponse;
+use crate::models::external::database::SchemaParam;
+use crate::models::external::database_object::{
+    ObjectColumn, ObjectConstraint, ObjectIndex, ObjectStructure, ObjectTrigger,
+};
+use crate::utils::common::{self, validate_id};
+use crate::utils::database::{
+    quote_identifier_mysql, quote_identifier_postgres, quote_literal_postgres,
+};
+
+fn query_error(e: sqlx::Error) -> poem::Error {
+    eprintln!("Query error: {}", e);
+    poem::Error::from_status(StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|v| !v.is_empty())
+}
+
+fn split_list(value: Option<String>) -> Vec<String> {
+    value
+        .map(|v| {
+            v.split(',')
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn referential_action_postgres(action: &str) -> Option<String> {
+    match action {
+        "a" => Some("NO ACTION".to_string()),
+        "r" => Some("RESTRICT".to_string()),
+        "c" => Some("CASCADE".to_string()),
+        "n" => Some("SET NULL".to_string()),
+        "d" => Some("SET DEFAULT".to_string()),
+        _ => None,
+    }
+}
+
+pub async fn fetch_object_structure(
+    ext_pool: &DatabasePool,
+    schema: Option<&str>,
+    object_name: &str,
+) -> poem::Result<ObjectStructure> {
+    match ext_pool {
+        DatabasePool::Postgres(pg_pool) => {
+            fetch_object_structure_postgres(pg_pool, schema, object_name).await
+        }
+        DatabasePool::MySql(my_pool) => {
+            fetch_object_structure_mysql(my_pool, schema, object_name).await
+        }
+    }
+}
+
+/// Daftar tabel, view, function dan procedure dalam satu schema beserta nama schema yang dipakai.
+pub async fn list_schema_objects(
+    ext_pool: &DatabasePool,
+    schema: Option<&str>,
+) -> poem::Result<(String, Vec<(String, String)>)> {
+    match ext_pool {
+        DatabasePool::Postgres(pg_pool) => {
+            let schema_name: String =
+                sqlx::query_scalar("SELECT COALESCE($1::TEXT, current_schema()::TEXT)")
+                    .bind(schema)
+                    .fetch_one(pg_pool)
+                    .await
+                    .map_err(query_error)?;
+
+            let objects = sqlx::query_as::<_, (String, String)>(
+                r#"
+                SELECT
+                    c.relname::TEXT AS name,
+                    CASE c.relkind
+                        WHEN 'v' THEN 'view'
+                        WHEN 'm' THEN 'materialized view'
+                        ELSE 'table'
+                    END AS object_type
+                FROM pg_catalog.pg_class c
+                JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+                WHERE n.nspname = $1
+                AND c.relkind IN ('r', 'p', 'v', 'm')
+                UNION
+                SELECT
+                    p.proname::TEXT AS name,
+                    CASE p.prokind WHEN 'p' THEN 'procedure' ELSE 'function' END AS object_type
+                FROM pg_catalog.pg_proc p
+                JOIN pg_catalog.pg_namespace n ON n.oid = p.pronamespace
+                WHERE n.nspname = $1
+                AND p.prokind IN ('f', 'p')
+                AND NOT EXISTS (
+                    SELECT 1 FROM pg_catalog.pg_depend d
+                    WHERE d.objid = p.oid AND d.deptype = 'e'
+                )
+                ORDER BY 1
+            "#,
+            )
+            .bind(&schema_name)
+            .fetch_all(pg_pool)
+            .await
+            .map_err(query_error)?;
+
+            Ok((schema_name, objects))
+        }
+        DatabasePool::MySql(my_pool) => {
+            let schema_name: String =
+                sqlx::query_scalar("SELECT CAST(COALESCE(?, DATABASE()) AS CHAR)")
+                    .bind(schema)
+                    .fetch_one(my_pool)
+                    .await
+                    .map_err(query_error)?;
+
+            let objects = sqlx::query_as::<_, (String, String)>(
+                r#"
+                SELECT
+                    CAST(TABLE_NAME AS CHAR) AS name,
+                    CAST(CASE WHEN TABLE_TYPE = 'VIEW' THEN 'view' ELSE 'table' END AS CHAR) AS object_type
+                FROM information_schema.TABLES
+                WHERE TABLE_SCHEMA = ?
+                UNION
+                SELECT
+                    CAST(ROUTINE_NAME AS CHAR) AS name,
+                    CAST(LOWER(ROUTINE_TYPE) AS CHAR) AS object_type
+                FROM information_schema.ROUTINES
+                WHERE ROUTINE_SCHEMA = ?
+                ORDER BY 1
+            "#,
+            )
+            .bind(&schema_name)
+            .bind(&schema_name)
+            .fetch_all(my_pool)
+            .await
+            .map_err(query_error)?;
+
+            Ok((schema_name, objects))
+        }
+    }
+}
+
+async fn fetch_object_structure_postgres(
+    pool: &Pool<Postgres>,
+    schema: Option<&str>,
+    object_name: &str,
+) -> poem::Result<ObjectStructure> {
+    let relation = sqlx::query(
+        r#"
+        SELECT
+            c.oid::INT8 AS oid,
+            n.nspname::TEXT AS schema_name,
+            CASE c.relkind
+                WHEN 'v' THEN 'view'
+                WHEN 'm' THEN 'materialized view'
+                WHEN 'f' THEN 'foreign table'
+                ELSE 'table'
+            END AS object_type,
+            pg_catalog.obj_description(c.oid, 'pg_class') AS comment
+        FROM pg_catalog.pg_class c
+        JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+        WHERE n.nspname = COALESCE($1::TEXT, current_schema()::TEXT)
+        AND c.relname = $2
+        AND c.relkind IN ('r', 'p', 'v', 'm', 'f')
+    "#,
+    )
+    .bind(schema)
+    .bind(object_name)
+    .fetch_optional(pool)
+    .await
+    .map_err(query_error)?;
+
+    let Some(relation) = relation else {
+        return fetch_routine_structure_postgres(pool, schema, object_name).await;
+    };
+
+    let oid: i64 = relation.try_get("oid").map_err(query_error)?;
+    let schema_name: String = relation.try_get("schema_name").map_err(query_error)?;
+    let object_type: String = relation.try_get("object_type").map_err(query_error)?;
+    let comment: Option<String> = relation.try_get("comment").map_err(query_error)?;
+
+    let columns = sqlx::query(
+        r#"
+        SELECT
+            a.attnum::INT8 AS position,
+            a.attname::TEXT AS column_name,
+            pg_catalog.format_type(a.atttypid, a.atttypmod) AS data_type,
+            NOT a.attnotnull AS nullable,
+            pg_catalog.pg_get_expr(d.adbin, d.adrelid) AS default_value,
+            pg_catalog.col_description(a.attrelid, a.attnum) AS comment,
+            CASE a.attidentity WHEN 'a' THEN 'ALWAYS' WHEN 'd' THEN 'BY DEFAULT' END AS identity_generation,
+            a.attgenerated = 's' AS is_generated
+        FROM pg_catalog.pg_attribute a
+        LEFT JOIN pg_catalog.pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum
+        WHERE a.attrelid = $1::INT8::OID
+        AND a.attnum > 0
+        AND NOT a.attisdropped
+        ORDER BY a.attnum
+    "#,
+    )
+    .bind(oid)
+    .fetch_all(pool)
+    .await
+    .map_err(query_error)?
+    .iter()
+    .map(|row| {
+        Ok(ObjectColumn {
+            position: row.try_get("position")?,
+            nm: row.try_get("column_name")?,
+            data_type: row.try_get("data_type")?,
+            nullable: row.try_get("nullable")?,
+            default_value: row.try_get("default_value")?,
+            comment: row.try_get("comment")?,
+            identity_generation: row.try_get("identity_generation")?,
+            is_generated: row.try_get("is_generated")?,
+        })
+    })
+    .collect::<Result<Vec<_>, sqlx::Error>>()
+    .map_err(query_error)?;
+
+    let constraints = sqlx::query(
+        r#"
+        SELECT
+            con.conname::TEXT AS name,
+            con.contype::TEXT AS constraint_type,
+            ARRAY(
+                SELECT a.attname::TEXT
+                FROM unnest(con.conkey) WITH ORDINALITY AS k(attnum, ord)
+                JOIN pg_catalog.pg_attribute a ON a.attrelid = con.conrelid AND a.attnum = k.attnum
+                ORDER BY k.ord
+            ) AS columns,
+            CASE WHEN con.contype = 'f' THEN con.confrelid::regclass::TEXT END AS referenced_table,
+            ARRAY(
+                SELECT a.attname::TEXT
+                FROM unnest(con.confkey) WITH ORDINALITY AS k(attnum, ord)
+                JOIN pg_catalog.pg_attribute a ON a.attrelid = con.confrelid AND a.attnum = k.attnum
+                ORDER BY k.ord
+            ) AS referenced_columns,
+            con.confupdtype::TEXT AS on_update,
+            con.confdeltype::TEXT AS on_delete,
+            pg_catalog.pg_get_constraintdef(con.oid, true) AS definition
+        FROM pg_catalog.pg_constraint con
+        WHERE con.conrelid = $1::INT8::OID
+        AND con.contype IN ('p', 'u', 'c', 'f')
+        ORDER BY con.conname
+    "#,
+    )
+    .bind(oid)
+    .fetch_all(pool)
+    .await
+    .map_err(query_error)?
+    .iter()
+    .map(|row| {
+        let constraint_type: String = row.try_get("constraint_type")?;
+        let on_update: String = row.try_get("on_update")?;
+        let on_delete: String = row.try_get("on_delete")?;
+        Ok(ObjectConstraint {
+            nm: row.try_get("name")?,
+            constraint_type: match constraint_type.as_str() {
+                "p" => "PRIMARY KEY",
+                "u" => "UNIQUE",
+                "f" => "FOREIGN KEY",
+                _ => "CHECK",
+            }
+            .to_string(),
+            columns: row.try_get("columns")?,
+            referenced_table: row.try_get("referenced_table")?,
+            referenced_columns: row.try_get("referenced_columns")?,
+            on_update: referential_action_postgres(&on_update),
+            on_delete: referential_action_postgres(&on_delete),
+            definition: row.try_get("definition")?,
+        })
+    })
+    .collect::<Result<Vec<_>, sqlx::Error>>()
+    .map_err(query_error)?;
+
+    let index_rows = sqlx::query(
+        r#"
+        SELECT
+            i.relname::TEXT AS name,
+            ARRAY(
+                SELECT pg_catalog.pg_get_indexdef(ix.indexrelid, k::INT4, true)
+                FROM generate_series(1, ix.indnatts) AS k
+            ) AS columns,
+            ix.indisunique AS is_unique,
+            ix.indisprimary AS is_primary,
+            am.amname::TEXT AS index_type,
+            pg_catalog.pg_get_indexdef(ix.indexrelid) AS definition,
+            EXISTS (
+                SELECT 1 FROM pg_catalog.pg_constraint con
+                WHERE con.conindid = ix.indexrelid AND con.conrelid = ix.indrelid
+            ) AS is_constraint
+        FROM pg_catalog.pg_index ix
+        JOIN pg_catalog.pg_class i ON i.oid = ix.indexrelid
+        JOIN pg_catalog.pg_am am ON am.oid = i.relam
+        WHERE ix.indrelid = $1::INT8::OID
+        ORDER BY i.relname
+    "#,
+    )
+    .bind(oid)
+    .fetch_all(pool)
+    .await
+    .map_err(query_error)?;
+
+    let mut indexes = Vec::new();
+    let mut standalone_index_ddl = Vec::new();
+    for row in &index_rows {
+        let is_unique: bool = row.try_get("is_unique").map_err(query_error)?;
+        let is_primary: bool = row.try_get("is_primary").map_err(query_error)?;
+        let is_constraint: bool = row.try_get("is_constraint").map_err(query_error)?;
+        let definition: String = row.try_get("definition").map_err(query_error)?;
+        if !is_constraint {
+            standalone_index_ddl.push(format!("{};", definition));
+        }
+        indexes.push(ObjectIndex {
+            nm: row.try_get("name").map_err(query_error)?,
+            columns: row.try_get("columns").map_err(query_error)?,
+            is_unique: if is_unique { 1 } else { 0 },
+            is_primary: if is_primary { 1 } else { 0 },
+            index_type: row.try_get("index_type").map_err(query_error)?,
+            definition,
+        });
+    }
+
+    let triggers = sqlx::query(
+        r#"
+        SELECT
+            t.tgname::TEXT AS name,
+            CASE
+                WHEN t.tgtype::INT4 & 2 = 2 THEN 'BEFORE'
+                WHEN t.tgtype::INT4 & 64 = 64 THEN 'INSTEAD OF'
+                ELSE 'AFTER'
+            END AS timing,
+            concat_ws(
+                ' OR ',
+                CASE WHEN t.tgtype::INT4 & 4 = 4 THEN 'INSERT' END,
+                CASE WHEN t.tgtype::INT4 & 16 = 16 THEN 'UPDATE' END,
+                CASE WHEN t.tgtype::INT4 & 8 = 8 THEN 'DELETE' END,
+                CASE WHEN t.tgtype::INT4 & 32 = 32 THEN 'TRUNCATE' END
+            ) AS event,
+            pg_catalog.pg_get_triggerdef(t.oid, true) AS definition
+        FROM pg_catalog.pg_trigger t
+        WHERE t.tgrelid = $1::INT8::OID
+        AND NOT t.tgisinternal
+        ORDER BY t.tgname
+    "#,
+    )
+    .bind(oid)
+    .fetch_all(pool)
+    .await
+    .map_err(query_error)?
+    .iter()
+    .map(|row| {
+        Ok(ObjectTrigger {
+            nm: row.try_get("name")?,
+            timing: row.try_get("timing")?,
+            event: row.try_get("event")?,
+            definition: row.try_get("definition")?,
+        })
+    })
+    .collect::<Result<Vec<_>, sqlx::Error>>()
+    .map_err(query_error)?;
+
+    let qualified_name = format!(
+        "{}.{}",
+        quote_identifier_postgres(&schema_name),
+        quote_identifier_postgres(object_name)
+    );
+
+    let mut ddl = String::new();
+    match object_type.as_str() {
+        "view" | "materialized view" => {
+            let view_definition: String =
+                sqlx::query_scalar("SELECT pg_catalog.pg_get_viewdef($1::INT8::OID, true)")
+                    .bind(oid)
+                    .fetch_one(pool)
+                    .await
+                    .map_err(query_error)?;
+            ddl.push_str(&format!(
+                "CREATE {} {} AS\n{}\n",
+                if object_type == "view" {
+                    "OR REPLACE VIEW"
+                } else {
+                    "MATERIALIZED VIEW"
+                },
+                qualified_name,
+                view_definition.trim_end()
+            ));
+        }
+        _ => {
+            let mut lines: Vec<String> = columns
+                .iter()
+                .map(|column| format!("    {}", column_definition_postgres(column)))
+                .collect();
+
+            for constraint_type in ["PRIMARY KEY", "UNIQUE", "CHECK", "FOREIGN KEY"] {
+                for constraint in constraints
+                    .iter()
+                    .filter(|c| c.constraint_type == constraint_type)
+                {
+                    lines.push(format!(
+                        "    CONSTRAINT {} {}",
+                        quote_identifier_postgres(&constraint.nm),
+                        constraint.definition
+                    ));
+                }
+            }
+
+            ddl.push_str(&format!(
+                "CREATE TABLE {} (\n{}\n);\n",
+                qualified_name,
+                lines.join(",\n")
+            ));
+        }
+    }
+
+    for index_ddl in &standalone_index_ddl {
+        ddl.push_str(&format!("{}\n", index_ddl));
+    }
+    for trigger in &triggers {
+        ddl.push_str(&format!("{};\n", trigger.definition));
+    }
+    if let Some(comment) = &comment {
+        ddl.push_str(&format!(
+            "COMMENT ON {} {} IS {};\n",
+            object_type.to_uppercase(),
+            qualified_name,
+            quote_literal_postgres(comment)
+        ));
+    }
+    for column in &columns {
+        if let Some(column_comment) = &column.comment {
+            ddl.push_str(&format!(
+                "COMMENT ON COLUMN {}.{} IS {};\n",
+                qualified_name,
+                quote_identifier_postgres(&column.nm),
+                quote_literal_postgres(column_comment)
+            ));
+        }
+    }
+
+    Ok(ObjectStructure {
+        nm: object_name.to_string(),
+        schema: schema_name,
+        object_type,
+        comment,
+        columns,
+        primary_key: constraints
+            .iter()
+            .find(|c| c.constraint_type == "PRIMARY KEY")
+            .cloned(),
+        foreign_keys: constraints
+            .iter()
+            .filter(|c| c.constraint_type == "FOREIGN KEY")
+            .cloned()
+            .collect(),
+        unique_keys: constraints
+            .iter()
+            .filter(|c| c.constraint_type == "UNIQUE")
+            .cloned()
+            .collect(),
+        check_constraints: constraints
+            .iter()
+            .filter(|c| c.constraint_type == "CHECK")
+            .cloned()
+            .collect(),
+        indexes,
+        triggers,
+        ddl,
+    })
+}
+
+async fn fetch_routine_structure_postgres(
+    pool: &Pool<Postgres>,
+    schema: Option<&str>,
+    object_name: &str,
+) -> poem::Result<ObjectStructure> {
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            n.nspname::TEXT AS schema_name,
+            CASE p.prokind WHEN 'p' THEN 'procedure' ELSE 'function' END AS object_type,
+            pg_catalog.obj_description(p.oid, 'pg_proc') AS comment,
+            pg_catalog.pg_get_functiondef(p.oid) AS definition
+        FROM pg_catalog.pg_proc p
+        JOIN pg_catalog.pg_namespace n ON n.oid = p.pronamespace
+        WHERE n.nspname = COALESCE($1::TEXT, current_schema()::TEXT)
+        AND p.proname = $2
+        AND p.prokind IN ('f', 'p')
+        ORDER BY p.oid
+    "#,
+    )
+    .bind(schema)
+    .bind(object_name)
+    .fetch_all(pool)
+    .await
+    .map_err(query_error)?;
+
+    let Some(first) = rows.first() else {
+        return Err(common::error_message(
+            StatusCode::NOT_FOUND,
+            "information.notFound",
+        ));
+    };
+
+    let mut definitions = Vec::new();
+    for row in &rows {
+        let definition: String = row.try_get("definition").map_err(query_error)?;
+        definitions.push(format!("{};\n", definition.trim_end()));
+    }
+
+    Ok(ObjectStructure {
+        nm: object_name.to_string(),
+        schema: first.try_get("schema_name").map_err(query_error)?,
+        object_type: first.try_get("object_type").map_err(query_error)?,
+        comment: first.try_get("comment").map_err(query_error)?,
+        columns: vec![],
+        primary_key: None,
+        foreign_keys: vec![],
+        unique_keys: vec![],
+        check_constraints: vec![],
+        indexes: vec![],
+        triggers: vec![],
+        ddl: definitions.join("\n"),
+    })
+}
+
+pub fn show_create_statement(row: &MySqlRow, index: usize) -> Result<String, sqlx::Error> {
+    row.try_get::<String, _>(index).or_else(|_| {
+        row.try_get::<Vec<u8>, _>(index)
+            .map(|bytes| String::from_utf8_lossy(&bytes).to_string())
+    })
+}
+
+async fn fetch_object_structure_mysql(
+    pool: &Pool<MySql>,
+    schema: Option<&str>,
+    object_name: &str,
+) -> poem::Result<ObjectStructure> {
+    let relation = sqlx::query(
+        r#"
+        SELECT
+            CAST(TABLE_SCHEMA AS CHAR) AS schema_name,
+            CAST(CASE WHEN TABLE_TYPE = 'VIEW' THEN 'view' ELSE 'table' END AS CHAR) AS object_type,
+            CAST(TABLE_COMMENT AS CHAR) AS comment
+        FROM information_schema.TABLES
+        WHERE TABLE_SCHEMA = COALESCE(?, DATABASE())
+        AND TABLE_NAME = ?
+    "#,
+    )
+    .bind(schema)
+    .bind(object_name)
+    .fetch_optional(pool)
+    .await
+    .map_err(query_error)?;
+
+    let Some(relation) = relation else {
+        return fetch_routine_structure_mysql(pool, schema, object_name).await;
+    };
+
+    let schema_name: String = relation.try_get("schema_name").map_err(query_error)?;
+    let object_type: String = relation.try_get("object_type").map_err(query_error)?;
+    let comment: Option<String> = non_empty(relation.try_get("comment").map_err(query_error)?);
+
+    let qualified_name = format!(
+        "{}.{}",
+        quote_identifier_mysql(&schema_name),
+        quote_identifier_mysql(object_name)
+    );
+
+    if object_type == "view" {
+        let row = sqlx::query(&format!("SHOW CREATE VIEW {}", qualified_name))
+            .fetch_one(pool)
+            .await
+            .map_err(query_error)?;
+        let columns = fetch_columns_mysql(pool, &schema_name, object_name).await?;
+
+        return Ok(ObjectStructure {
+            nm: object_name.to_string(),
+            schema: schema_name,
+            object_type,
+            comment: None,
+            columns,
+            primary_key: None,
+            foreign_keys: vec![],
+            unique_keys: vec![],
+            check_constraints: vec![],
+            indexes: vec![],
+            triggers: vec![],
+            ddl: format!(
+                "{};\n",
+                show_create_statement(&row, 1).map_err(query_error)?
+            ),
+        });
+    }
+
+    let columns = fetch_columns_mysql(pool, &schema_name, object_name).await?;
+
+    let constraints = sqlx::query(
+        r#"
+        SELECT
+            CAST(tc.CONSTRAINT_NAME AS CHAR) AS name,
+            CAST(tc.CONSTRAINT_TYPE AS CHAR) AS constraint_type,
+            CAST(GROUP_CONCAT(kcu.COLUMN_NAME ORDER BY kcu.ORDINAL_POSITION SEPARATOR ',') AS CHAR) AS columns,
+            CAST(MAX(kcu.REFERENCED_TABLE_NAME) AS CHAR) AS referenced_table,
+            CAST(GROUP_CONCAT(kcu.REFERENCED_COLUMN_NAME ORDER BY kcu.ORDINAL_POSITION SEPARATOR ',') AS CHAR) AS referenced_columns,
+            CAST(MAX(rc.UPDATE_RULE) AS CHAR) AS on_update,
+            CAST(MAX(rc.DELETE_RULE) AS CHAR) AS on_delete
+        FROM information_schema.TABLE_CONSTRAINTS tc
+        JOIN information_schema.KEY_COLUMN_USAGE kcu
+            ON kcu.CONSTRAINT_SCHEMA = tc.CONSTRAINT_SCHEMA
+            AND kcu.CONSTRAINT_NAME = tc.CONSTRAINT_NAME
+            AND kcu.TABLE_NAME = tc.TABLE_NAME
+        LEFT JOIN information_schema.REFERENTIAL_CONSTRAINTS rc
+            ON rc.CONSTRAINT_SCHEMA = tc.CONSTRAINT_SCHEMA
+            AND rc.CONSTRAINT_NAME = tc.CONSTRAINT_NAME
+            AND rc.TABLE_NAME = tc.TABLE_NAME
+        WHERE tc.TABLE_SCHEMA = ?
+        AND tc.TABLE_NAME = ?
+        AND tc.CONSTRAINT_TYPE IN ('PRIMARY KEY', 'UNIQUE', 'FOREIGN KEY')
+        GROUP BY tc.CONSTRAINT_NAME, tc.CONSTRAINT_TYPE
+        ORDER BY tc.CONSTRAINT_NAME
+    "#,
+    )
+    .bind(&schema_name)
+    .bind(object_name)
+    .fetch_all(pool)
+    .await
+    .map_err(query_error)?
+    .iter()
+    .map(|row| {
+        let constraint_type: String = row.try_get("constraint_type")?;
+        let columns = split_list(row.try_get("columns")?);
+        let referenced_table: Option<String> = row.try_get("referenced_table")?;
+        let referenced_columns = split_list(row.try_get("referenced_columns")?);
+        let on_update: Option<String> = row.try_get("on_update")?;
+        let on_delete: Option<String> = row.try_get("on_delete")?;
+        let quoted_columns = columns
+            .iter()
+            .map(|c| quote_identifier_mysql(c))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let definition = match constraint_type.as_str() {
+            "FOREIGN KEY" => format!(
+                "FOREIGN KEY ({}) REFERENCES {} ({}) ON DELETE {} ON UPDATE {}",
+                quoted_columns,
+                quote_identifier_mysql(referenced_table.as_deref().unwrap_or_default()),
+                referenced_columns
+                    .iter()
+                    .map(|c| quote_identifier_mysql(c))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                on_delete.as_deref().unwrap_or("RESTRICT"),
+                on_update.as_deref().unwrap_or("RESTRICT")
+            ),
+            _ => format!("{} ({})", constraint_type, quoted_columns),
+        };
+
+        Ok(ObjectConstraint {
+            nm: row.try_get("name")?,
+            constraint_type,
+            columns,
+            referenced_table,
+            referenced_columns,
+            on_update,
+            on_delete,
+            definition,
+        })
+    })
+    .collect::<Result<Vec<_>, sqlx::Error>>()
+    .map_err(query_error)?;
+
+    // CHECK_CONSTRAINTS only exists from MySQL 8.0.16, older servers simply report none
+    let check_constraints = sqlx::query(
+        r#"
+        SELECT
+            CAST(tc.CONSTRAINT_NAME AS CHAR) AS name,
+            CAST(cc.CHECK_CLAUSE AS CHAR) AS check_clause
+        FROM information_schema.TABLE_CONSTRAINTS tc
+        JOIN information_schema.CHECK_CONSTRAINTS cc
+            ON cc.CONSTRAINT_SCHEMA = tc.CONSTRAINT_SCHEMA
+            AND cc.CONSTRAINT_NAME = tc.CONSTRAINT_NAME
+        WHERE tc.TABLE_SCHEMA = ?
+        AND tc.TABLE_NAME = ?
+        AND tc.CONSTRAINT_TYPE = 'CHECK'
+        ORDER BY tc.CONSTRAINT_NAME
+    "#,
+    )
+    .bind(&schema_name)
+    .bind(object_name)
+    .fetch_all(pool)
+    .await
+    .unwrap_or_default()
+    .iter()
+    .filter_map(|row| {
+        let check_clause: String = row.try_get("check_clause").ok()?;
+        Some(ObjectConstraint {
+            nm: row.try_get("name").ok()?,
+            constraint_type: "CHECK".to_string(),
+            columns: vec![],
+            referenced_table: None,
+            referenced_columns: vec![],
+            on_update: None,
+            on_delete: None,
+            definition: format!("CHECK ({})", check_clause),
+        })
+    })
+    .collect::<Vec<_>>();
+
+    let indexes = sqlx::query(
+        r#"
+        SELECT
+            CAST(INDEX_NAME AS CHAR) AS name,
+            CAST(GROUP_CONCAT(COLUMN_NAME ORDER BY SEQ_IN_INDEX SEPARATOR ',') AS CHAR) AS columns,
+            CAST(MIN(NON_UNIQUE) AS SIGNED) AS non_unique,
+            CAST(MAX(INDEX_TYPE) AS CHAR) AS index_type
+        FROM information_schema.STATISTICS
+        WHERE TABLE_SCHEMA = ?
+        AND TABLE_NAME = ?
+        GROUP BY INDEX_NAME
+        ORDER BY INDEX_NAME
+    "#,
+    )
+    .bind(&schema_name)
+    .bind(object_name)
+    .fetch_all(pool)
+    .await
+    .map_err(query_error)?
+    .iter()
+    .map(|row| {
+        let nm: String = row.try_get("name")?;
+        let columns = split_list(row.try_get("columns")?);
+        let non_unique: i64 = row.try_get("non_unique")?;
+        let index_type: Option<String> = row.try_get("index_type")?;
+        let is_primary = nm == "PRIMARY";
+        let quoted_columns = columns
+            .iter()
+            .map(|c| quote_identifier_mysql(c))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let definition = if is_primary {
+            format!(
+                "ALTER TABLE {} ADD PRIMARY KEY ({})",
+                qualified_name, quoted_columns
+            )
+        } else {
+            format!(
+                "CREATE {}INDEX {} ON {} ({}){}",
+                if non_unique == 0 { "UNIQUE " } else { "" },
+                quote_identifier_mysql(&nm),
+                qualified_name,
+                quoted_columns,
+                index_type
+                    .as_deref()
+                    .map(|t| format!(" USING {}", t))
+                    .unwrap_or_default()
+            )
+        };
+
+        Ok(ObjectIndex {
+            nm,
+            columns,
+            is_unique: if non_unique == 0 { 1 } else { 0 },
+            is_primary: if is_primary { 1 } else { 0 },
+            index_type,
+            definition,
+        })
+    })
+    .collect::<Result<Vec<_>, sqlx::Error>>()
+    .map_err(query_error)?;
+
+    let triggers = sqlx::query(
+        r#"
+        SELECT
+            CAST(TRIGGER_NAME AS CHAR) AS name,
+            CAST(ACTION_TIMING AS CHAR) AS timing,
+            CAST(EVENT_MANIPULATION AS CHAR) AS event,
+            CAST(ACTION_STATEMENT AS CHAR) AS action_statement
+        FROM information_schema.TRIGGERS
+        WHERE EVENT_OBJECT_SCHEMA = ?
+        AND EVENT_OBJECT_TABLE = ?
+        ORDER BY TRIGGER_NAME
+    "#,
+    )
+    .bind(&schema_name)
+    .bind(object_name)
+    .fetch_all(pool)
+    .await
+    .map_err(query_error)?
+    .iter()
+    .map(|row| {
+        let nm: String = row.try_get("name")?;
+        let timing: String = row.try_get("timing")?;
+        let event: String = row.try_get("event")?;
+        let action_statement: String = row.try_get("action_statement")?;
+        Ok(ObjectTrigger {
+            definition: format!(
+                "CREATE TRIGGER {} {} {} ON {} FOR EACH ROW {}",
+                quote_identifier_mysql(&nm),
+                timing,
+                event,
+                qualified_name,
+                action_statement
+            ),
+            nm,
+            timing,
+            event,
+        })
+    })
+    .collect::<Result<Vec<_>, sqlx::Error>>()
+    .map_err(query_error)?;
+
+    let row = sqlx::query(&format!("SHOW CREATE TABLE {}", qualified_name))
+        .fetch_one(pool)
+        .await
+        .map_err(query_error)?;
+    let mut ddl = format!(
+        "{};\n",
+        show_create_statement(&row, 1).map_err(query_error)?
+    );
+    for trigger in &triggers {
+        ddl.push_str(&format!("{};\n", trigger.definition));
+    }
+
+    Ok(ObjectStructure {
+        nm: object_name.to_string(),
+        schema: schema_name,
+        object_type,
+        comment,
+        columns,
+        primary_key: constraints
+            .iter()
+            .find(|c| c.constraint_type == "PRIMARY KEY")
+            .cloned(),
+        foreign_keys: constraints
+            .iter()
+            .filter(|c| c.constraint_type == "FOREIGN KEY")
+            .cloned()
+            .collect(),
+        unique_keys: constraints
+            .iter()
+            .filter(|c| c.constraint_type == "UNIQUE")
+            .cloned()
+            .collect(),
+        check_constraints,
+        indexes,
+        triggers,
+        ddl,
+    })
+}
+
+// Identity dan kolom generated harus ditulis ulang apa adanya, bukan sebagai DEFAULT biasa
+pub fn column_definition_postgres(column: &ObjectColumn) -> String {
+    let mut definition = format!(
+        "{} {}",
+        quote_identifier_postgres(&column.nm),
+        column.data_type
+    );
+    match (&column.identity_generation, &column.default_value) {
+        (Some(generation), _) => {
+            definition.push_str(&format!(" GENERATED {} AS IDENTITY", generation));
+        }
+        (None, Some(expression)) if column.is_generated => {
+            definition.push_str(&format!(" GENERATED ALWAYS AS ({}) STORED", expression));
+        }
+        (None, Some(default_value)) => {
+            definition.push_str(&format!(" DEFAULT {}", default_value));
+        }
+        (None, None) => {}
+    }
+    if !column.nullable {
+        definition.push_str(" NOT NULL");
+    }
+    definition
+}
+
+async fn fetch_columns_mysql(
+    pool: &Pool<MySql>,
+    schema_name: &str,
+    object_name: &str,
+) -> poem::Result<Vec<ObjectColumn>> {
+    sqlx::query(
+        r#"
+        SELECT
+            CAST(ORDINAL_POSITION AS SIGNED) AS position,
+            CAST(COLUMN_NAME AS CHAR) AS column_name,
+            CAST(COLUMN_TYPE AS CHAR) AS data_type,
+            CAST(IS_NULLABLE = 'YES' AS SIGNED) AS nullable,
+            CAST(COLUMN_DEFAULT AS CHAR) AS default_value,
+            CAST(COLUMN_COMMENT AS CHAR) AS comment,
+            CAST(EXTRA IN ('VIRTUAL GENERATED', 'STORED GENERATED') AS SIGNED) AS is_generated
+        FROM information_schema.COLUMNS
+        WHERE TABLE_SCHEMA = ?
+        AND TABLE_NAME = ?
+        ORDER BY ORDINAL_POSITION
+    "#,
+    )
+    .bind(schema_name)
+    .bind(object_name)
+    .fetch_all(pool)
+    .await
+    .map_err(query_error)?
+    .iter()
+    .map(|row| {
+        let nullable: i64 = row.try_get("nullable")?;
+        let is_generated: i64 = row.try_get("is_generated")?;
+        Ok(ObjectColumn {
+            position: row.try_get("position")?,
+            nm: row.try_get("column_name")?,
+            data_type: row.try_get("data_type")?,
+            nullable: nullable == 1,
+            default_value: row.try_get("default_value")?,
+            comment: non_empty(row.try_get("comment")?),
+            identity_generation: None,
+            is_generated: is_generated == 1,
+        })
+    })
+    .collect::<Result<Vec<_>, sqlx::Error>>()
+    .map_err(query_error)
+}
+
+async fn fetch_routine_structure_mysql(
+    pool: &Pool<MySql>,
+    schema: Option<&str>,
+    object_name: &str,
+) -> poem::Result<ObjectStructure> {
+    let routine = sqlx::query(
+        r#"
+        SELECT
+            CAST(ROUTINE_SCHEMA AS CHAR) AS schema_name,
+            CAST(ROUTINE_TYPE AS CHAR) AS routine_type,
+            CAST(ROUTINE_COMMENT AS CHAR) AS comment
+        FROM information_schema.ROUTINES
+        WHERE ROUTINE_SCHEMA = COALESCE(?, DATABASE())
+        AND ROUTINE_NAME = ?
+    "#,
+    )
+    .bind(schema)
+    .bind(object_name)
+    .fetch_optional(pool)
+    .await
+    .map_err(query_error)?
+    .ok_or_else(|| common::error_message(StatusCode::NOT_FOUND, "information.notFound"))?;
+
+    let schema_name: String = routine.try_get("schema_name").map_err(query_error)?;
+    let routine_type: String = routine.try_get("routine_type").map_err(query_error)?;
+
+    let row = sqlx::query(&format!(
+        "SHOW CREATE {} {}.{}",
+        routine_type,
+        quote_identifier_mysql(&schema_name),
+        quote_identifier_mysql(object_name)
+    ))
+    .fetch_one(pool)
+    .await
+    .map_err(query_error)?;
+
+    Ok(ObjectStructure {
+        nm: object_name.to_string(),
+        schema: schema_name,
+        object_type: routine_type.to_lowercase(),
+        comment: non_empty(routine.try_get("comment").map_err(query_error)?),
+        columns: vec![],
+        primary_key: None,
+        foreign_keys: vec![],
+        unique_keys: vec![],
+        check_constraints: vec![],
+        indexes: vec![],
+        triggers: vec![],
+        ddl: format!(
+            "{};\n",
+            show_create_statement(&row, 2).map_err(query_error)?
+        ),
+    })
+}
+
+#[handler]
+pub async fn query_object_structure(
+    pool: poem::web::Data<&DbPool>,
+    _: crate::auth::middleware::JwtAuth,
+    Path((ext_database_id, object_name)): Path<(i64, String)>,
+    Query(schema_param): Query<SchemaParam>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(ext_database_id)?;
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    let schema = resolve_schema(conn, ext_database_id, schema_param.schema)?;
+    let (ext_pool, tunnel, _, _) = get_external_pool(conn, ext_database_id).await?;
+
+    let structure = fetch_object_structure(&ext_pool, schema.as_deref(), &object_name).await;
+
+    if let Some(mut tunnel) = tunnel {
+        let _ = tunnel.kill().ok();
+    };
+
+    Ok(Json(DataResponse { data: structure? }))
+}
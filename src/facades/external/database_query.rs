@@ -1,36 +1,65 @@
 use std::process::Child;
+use std::time::Instant;
 
+use chrono::NaiveDateTime;
 use diesel::prelude::*;
 use diesel::{ExpressionMethods, PgConnection};
 use poem::web::{Json, Query};
-use poem::{IntoResponse, handler, http::StatusCode, web::Path};
+use poem::{Body, IntoResponse, handler, http::StatusCode, web::Path};
+use sqlparser::dialect::Dialect;
 use sqlx::mysql::MySqlPoolOptions;
-use sqlx::postgres::PgPoolOptions;
-use sqlx::{Column, Pool, Row};
+use sqlx::postgres::{PgPoolOptions, PgRow};
+use sqlx::{Column, Executor, Pool, Row};
 use sqlx::{MySql, Postgres};
+use tokio::io;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::{Stream, StreamExt};
+use tokio_util::bytes::Bytes;
 
+use crate::auth::model::Claims;
 use crate::database_pool::DatabasePool;
+use crate::facades::external::database_execution::{
+    ExecutionContext, record_execution, record_execution_pooled,
+};
+use crate::facades::external::database_explain::estimate_row_count;
+use crate::facades::external::database_keyset::query_with_keyset;
+use crate::facades::external::database_mask::{MaskPolicy, load_mask_policy};
+use crate::facades::external::database_metadata::invalidate_metadata;
+use crate::facades::external::database_snapshot::{
+    capture_snapshot, get_snapshot, with_snapshot_header,
+};
 use crate::facades::external::server_command::start_ssh_tunnel;
 use crate::models::common::{
-    DataResponse, LoadedMoreResponse, PaginatedLoadedMoreResponse, PaginatedResponse, Pagination,
+    DataResponse, EstimatedPaginatedResponse, LoadedMoreResponse, PaginatedLoadedMoreResponse,
+    PaginatedResponse, Pagination,
+};
+use crate::models::external::database::{
+    EntryQueryManual, ExternalDatabaseQuery, QueryManual, SchemaParam,
 };
-use crate::models::external::database::{EntryQueryManual, ExternalDatabaseQuery, QueryManual};
 use crate::schema::{tbl_ext_database_query, tbl_query_manual};
 use crate::utils::common::{encode_special_chars, parse_pagination, validate_id};
+use crate::utils::crypto::reveal_credential;
 use crate::utils::database::{
+    SqlStatementInfo, SqlStatementKind, columns_info_mysql, columns_info_postgres,
     convert_to_count_query, extract_columns_info_mysql, extract_columns_info_postgres,
-    extract_query_parts, is_only_comment, is_sql_type, rows_to_csv_string,
-    rows_to_insert_query_string, rows_to_json_mysql, rows_to_json_postgres, rows_to_json_string,
-    rows_to_update_query_string, rows_to_xlsx_bytes, rows_to_xml_string, split_manual_query,
+    is_only_comment, parse_statement_info, quote_identifier_mysql, quote_identifier_postgres,
+    quote_literal_mysql, quote_literal_postgres, row_to_csv_line, row_to_json_mysql,
+    row_to_json_postgres, row_to_xml_element, rows_to_arrow_bytes, rows_to_insert_query_string,
+    rows_to_json_mysql, rows_to_json_postgres, rows_to_parquet_bytes, rows_to_update_query_string,
+    rows_to_xlsx_bytes, split_manual_query, sql_dialect, strip_query_ordering,
 };
 use crate::{
     db::DbPool,
     schema::{tbl_ext_database, tbl_mt_database_type},
     utils::common,
 };
-use serde_json::{Value, json};
+use serde_json::{Map, Value, json};
 
-fn get_manual_query(conn: &mut PgConnection, query_manual_id: i64) -> poem::Result<(i64, String)> {
+pub fn get_manual_query(
+    conn: &mut PgConnection,
+    query_manual_id: i64,
+) -> poem::Result<(i64, String)> {
     tbl_query_manual::table
         .filter(tbl_query_manual::id.eq(query_manual_id))
         .select((tbl_query_manual::ext_database_id, tbl_query_manual::query))
@@ -38,10 +67,27 @@ fn get_manual_query(conn: &mut PgConnection, query_manual_id: i64) -> poem::Resu
         .map_err(|_| common::error_message(StatusCode::NOT_FOUND, "information.notFound"))
 }
 
+pub fn is_mysql_database(conn: &mut PgConnection, ext_database_id: i64) -> poem::Result<bool> {
+    let mt_database_type_id = tbl_ext_database::table
+        .filter(tbl_ext_database::id.eq(ext_database_id))
+        .select(tbl_ext_database::mt_database_type_id)
+        .first::<i16>(conn)
+        .map_err(|_| common::error_message(StatusCode::NOT_FOUND, "information.notFound"))?;
+    Ok(mt_database_type_id == 2)
+}
+
+// Dialect parser mengikuti jenis database tanpa perlu membuka koneksi
+pub fn database_dialect(
+    conn: &mut PgConnection,
+    ext_database_id: i64,
+) -> poem::Result<Box<dyn Dialect + Send + Sync>> {
+    Ok(sql_dialect(is_mysql_database(conn, ext_database_id)?))
+}
+
 async fn get_query_manual_pool(
     conn: &mut PgConnection,
     query_manual_id: i64,
-) -> poem::Result<(DatabasePool, String, i16, String)> {
+) -> poem::Result<(i64, DatabasePool, String, i16, String)> {
     let (ext_database_id, query_string) = get_manual_query(conn, query_manual_id)?;
     let (pool, tunnel, is_use_page, pagination) = get_external_pool(conn, ext_database_id).await?;
 
@@ -49,69 +95,366 @@ async fn get_query_manual_pool(
         let _ = tunnel.kill().ok();
     };
 
-    Ok((pool, query_string, is_use_page, pagination))
+    Ok((ext_database_id, pool, query_string, is_use_page, pagination))
 }
 
+// Masking diterapkan setelah snapshot dibaca, jadi satu snapshot tetap aman dipakai lintas role
 async fn get_query_manual_row(
     conn: &mut PgConnection,
     query_manual_id: i64,
-) -> poem::Result<(Vec<Value>, Vec<String>, String)> {
-    let (ext_pool, query_str, is_use_page, _) =
+    claims: &Claims,
+) -> poem::Result<(
+    Vec<Value>,
+    Vec<String>,
+    String,
+    Vec<Value>,
+    Option<NaiveDateTime>,
+)> {
+    let roles = claims.role.as_deref().unwrap_or_default();
+    let (ext_database_id, query_str) = get_manual_query(conn, query_manual_id)?;
+    let mask = load_mask_policy(conn, ext_database_id, roles, &query_str)?;
+
+    let (mut rows, headers, query_str, mut columns, dt_captured) =
+        fetch_query_manual_row(conn, query_manual_id, claims.id).await?;
+    mask.mask_rows(&mut rows);
+    mask.mask_columns(&mut columns);
+
+    Ok((rows, headers, query_str, columns, dt_captured))
+}
+
+// Hasil kosong tidak membawa metadata kolom, daftar kolom diambil dari describe statement
+pub async fn describe_columns_postgres(pg_pool: &Pool<Postgres>, query_str: &str) -> Vec<Value> {
+    match pg_pool.describe(query_str).await {
+        Ok(describe) => columns_info_postgres(describe.columns()),
+        Err(e) => {
+            eprintln!("Query error: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+pub async fn describe_columns_mysql(my_pool: &Pool<MySql>, query_str: &str) -> Vec<Value> {
+    match my_pool.describe(query_str).await {
+        Ok(describe) => columns_info_mysql(describe.columns()),
+        Err(e) => {
+            eprintln!("Query error: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+pub fn column_headers(columns: &[Value]) -> Vec<String> {
+    columns
+        .iter()
+        .filter_map(|column| column.get("name").and_then(Value::as_str))
+        .map(String::from)
+        .collect()
+}
+
+async fn fetch_query_manual_row(
+    conn: &mut PgConnection,
+    query_manual_id: i64,
+    user_id: i64,
+) -> poem::Result<(
+    Vec<Value>,
+    Vec<String>,
+    String,
+    Vec<Value>,
+    Option<NaiveDateTime>,
+)> {
+    if let Some(snapshot) = get_snapshot(conn, query_manual_id) {
+        let (_, query_str) = get_manual_query(conn, query_manual_id)?;
+        return Ok((
+            snapshot.rows,
+            snapshot.headers,
+            query_str,
+            snapshot.columns,
+            Some(snapshot.dt_captured),
+        ));
+    }
+
+    let (ext_database_id, ext_pool, query_str, is_use_page, _) =
         get_query_manual_pool(conn, query_manual_id).await?;
 
     if 1 == is_use_page {
+        let context = ExecutionContext::new(&ext_pool, ext_database_id, user_id, "export");
+        let dt_started = Instant::now();
         match &ext_pool {
             DatabasePool::Postgres(pg_pool) => {
-                let rows = sqlx::query(&query_str)
-                    .fetch_all(pg_pool)
-                    .await
-                    .map_err(|e| {
-                        eprintln!("Query error: {}", e);
-                        poem::Error::from_status(StatusCode::INTERNAL_SERVER_ERROR)
-                    })?;
-
-                let headers = if let Some(row) = rows.first() {
-                    row.columns()
-                        .iter()
-                        .map(|col| col.name().to_string())
-                        .collect()
-                } else {
-                    Vec::new()
-                };
+                let fetched = sqlx::query(&query_str).fetch_all(pg_pool).await;
+                record_execution(
+                    conn,
+                    &context,
+                    &query_str,
+                    dt_started,
+                    fetched
+                        .as_ref()
+                        .map(|rows| Some(rows.len() as u64))
+                        .map_err(|e| e.to_string()),
+                );
+                let rows = fetched.map_err(|e| {
+                    eprintln!("Query error: {}", e);
+                    poem::Error::from_status(StatusCode::INTERNAL_SERVER_ERROR)
+                })?;
 
                 let results = rows_to_json_postgres(&rows);
+                let columns = if rows.is_empty() {
+                    describe_columns_postgres(pg_pool, &query_str).await
+                } else {
+                    extract_columns_info_postgres(&rows)
+                };
+                let headers = column_headers(&columns);
 
-                Ok((results, headers, query_str))
+                Ok((results, headers, query_str, columns, None))
             }
             DatabasePool::MySql(my_pool) => {
-                let rows = sqlx::query(&query_str)
-                    .fetch_all(my_pool)
-                    .await
-                    .map_err(|e| {
-                        eprintln!("Query error: {}", e);
-                        poem::Error::from_status(StatusCode::INTERNAL_SERVER_ERROR)
-                    })?;
-
-                let headers = if let Some(row) = rows.first() {
-                    row.columns()
-                        .iter()
-                        .map(|col| col.name().to_string())
-                        .collect()
-                } else {
-                    Vec::new()
-                };
+                let fetched = sqlx::query(&query_str).fetch_all(my_pool).await;
+                record_execution(
+                    conn,
+                    &context,
+                    &query_str,
+                    dt_started,
+                    fetched
+                        .as_ref()
+                        .map(|rows| Some(rows.len() as u64))
+                        .map_err(|e| e.to_string()),
+                );
+                let rows = fetched.map_err(|e| {
+                    eprintln!("Query error: {}", e);
+                    poem::Error::from_status(StatusCode::INTERNAL_SERVER_ERROR)
+                })?;
 
                 let results = rows_to_json_mysql(&rows);
+                let columns = if rows.is_empty() {
+                    describe_columns_mysql(my_pool, &query_str).await
+                } else {
+                    extract_columns_info_mysql(&rows)
+                };
+                let headers = column_headers(&columns);
 
-                Ok((results, headers, query_str))
+                Ok((results, headers, query_str, columns, None))
             }
         }
     } else {
-        Ok((Vec::new(), Vec::new(), query_str))
+        Ok((Vec::new(), Vec::new(), query_str, Vec::new(), None))
+    }
+}
+
+const EXPORT_CHUNK_SIZE: usize = 64 * 1024;
+
+enum ExportFormat {
+    Csv { header_flag: i16, delimiter: String },
+    Ndjson,
+    Xml { name: String },
+}
+
+impl ExportFormat {
+    fn prefix(&self, headers: &[String]) -> String {
+        match self {
+            ExportFormat::Csv {
+                header_flag,
+                delimiter,
+            } if *header_flag == 1 => format!("{}\n", headers.join(delimiter)),
+            ExportFormat::Xml { .. } => String::from("<List>\n"),
+            _ => String::new(),
+        }
+    }
+
+    fn row(&self, map: Map<String, Value>, headers: &[String]) -> String {
+        match self {
+            ExportFormat::Csv { delimiter, .. } => row_to_csv_line(delimiter, &map, headers),
+            ExportFormat::Ndjson => {
+                let mut obj = Map::new();
+                for header in headers {
+                    obj.insert(
+                        header.clone(),
+                        map.get(header).cloned().unwrap_or(Value::Null),
+                    );
+                }
+                let mut line = Value::Object(obj).to_string();
+                line.push('\n');
+                line
+            }
+            ExportFormat::Xml { name } => row_to_xml_element(name, &map, headers),
+        }
+    }
+
+    fn suffix(&self) -> &'static str {
+        match self {
+            ExportFormat::Xml { .. } => "</List>\n",
+            _ => "",
+        }
+    }
+}
+
+async fn send_export_rows<R, S>(
+    mut rows: S,
+    format: &ExportFormat,
+    to_json: fn(&R) -> Map<String, Value>,
+    mask: &MaskPolicy,
+    tx: &mpsc::Sender<Result<Bytes, io::Error>>,
+) -> Result<u64, io::Error>
+where
+    R: Row,
+    S: Stream<Item = Result<R, sqlx::Error>> + Unpin,
+{
+    let mut headers: Option<Vec<String>> = None;
+    let mut buffer = String::new();
+    let mut row_count = 0;
+
+    while let Some(row) = rows.next().await {
+        let row = row.map_err(|e| {
+            eprintln!("Query error: {}", e);
+            io::Error::other(e.to_string())
+        })?;
+
+        let is_first = headers.is_none();
+        let headers = headers.get_or_insert_with(|| {
+            let headers: Vec<String> = row
+                .columns()
+                .iter()
+                .map(|col| col.name().to_string())
+                .collect();
+            buffer.push_str(&format.prefix(&headers));
+            headers
+        });
+        let mut row = to_json(&row);
+        mask.mask_row(&mut row);
+        row_count += 1;
+        buffer.push_str(&format.row(row, headers));
+
+        // Baris pertama langsung dikirim agar respons bisa segera dimulai
+        if is_first || buffer.len() >= EXPORT_CHUNK_SIZE {
+            let chunk = Bytes::from(std::mem::take(&mut buffer));
+            if tx.send(Ok(chunk)).await.is_err() {
+                return Ok(row_count);
+            }
+        }
+    }
+
+    if headers.is_some() {
+        buffer.push_str(format.suffix());
+    } else if let ExportFormat::Xml { .. } = format {
+        buffer.push_str(&format.prefix(&[]));
+        buffer.push_str(format.suffix());
+    }
+
+    if !buffer.is_empty() {
+        let _ = tx.send(Ok(Bytes::from(buffer))).await;
+    }
+
+    Ok(row_count)
+}
+
+async fn stream_query_manual_row(
+    conn: &mut PgConnection,
+    db_pool: &DbPool,
+    query_manual_id: i64,
+    format: ExportFormat,
+    claims: &Claims,
+) -> poem::Result<(Body, Option<NaiveDateTime>)> {
+    let roles = claims.role.as_deref().unwrap_or_default();
+    let (ext_database_id, query_str) = get_manual_query(conn, query_manual_id)?;
+    let mask = load_mask_policy(conn, ext_database_id, roles, &query_str)?;
+
+    // Snapshot yang masih berlaku dipakai langsung tanpa menjalankan ulang query
+    if let Some(snapshot) = get_snapshot(conn, query_manual_id) {
+        let mut buffer = String::new();
+        if !snapshot.rows.is_empty() {
+            buffer.push_str(&format.prefix(&snapshot.headers));
+            for row in snapshot.rows {
+                if let Value::Object(mut map) = row {
+                    mask.mask_row(&mut map);
+                    buffer.push_str(&format.row(map, &snapshot.headers));
+                }
+            }
+            buffer.push_str(format.suffix());
+        } else if let ExportFormat::Xml { .. } = format {
+            buffer.push_str(&format.prefix(&[]));
+            buffer.push_str(format.suffix());
+        }
+        return Ok((Body::from_string(buffer), Some(snapshot.dt_captured)));
+    }
+
+    let (ext_pool, tunnel, is_use_page, _) = get_external_pool(conn, ext_database_id).await?;
+    let context = ExecutionContext::new(&ext_pool, ext_database_id, claims.id, "export");
+    let db_pool = db_pool.clone();
+
+    let (tx, mut rx) = mpsc::channel::<Result<Bytes, io::Error>>(16);
+
+    tokio::spawn(async move {
+        let result = if 1 == is_use_page {
+            let dt_started = Instant::now();
+            let sent = match &ext_pool {
+                DatabasePool::Postgres(pg_pool) => {
+                    let rows = sqlx::query(&query_str).fetch(pg_pool);
+                    send_export_rows(rows, &format, row_to_json_postgres, &mask, &tx).await
+                }
+                DatabasePool::MySql(my_pool) => {
+                    let rows = sqlx::query(&query_str).fetch(my_pool);
+                    send_export_rows(rows, &format, row_to_json_mysql, &mask, &tx).await
+                }
+            };
+            record_execution_pooled(
+                &db_pool,
+                &context,
+                &query_str,
+                dt_started,
+                sent.as_ref()
+                    .map(|rows| Some(*rows))
+                    .map_err(|e| e.to_string()),
+            );
+            sent
+        } else {
+            send_export_rows(
+                tokio_stream::empty::<Result<PgRow, sqlx::Error>>(),
+                &format,
+                row_to_json_postgres,
+                &mask,
+                &tx,
+            )
+            .await
+        };
+
+        if let Err(e) = result {
+            let _ = tx.send(Err(e)).await;
+        }
+
+        // Tunnel baru ditutup setelah seluruh baris selesai dibaca
+        if let Some(mut tunnel) = tunnel {
+            let _ = tunnel.kill().ok();
+        };
+    });
+
+    // Kesalahan sebelum baris pertama masih bisa dikembalikan sebagai status error
+    match rx.recv().await {
+        Some(Ok(first)) => Ok((
+            Body::from_bytes_stream(tokio_stream::once(Ok(first)).chain(ReceiverStream::new(rx))),
+            None,
+        )),
+        Some(Err(_)) => Err(poem::Error::from_status(StatusCode::INTERNAL_SERVER_ERROR)),
+        None => Ok((Body::empty(), None)),
     }
 }
 
-async fn get_external_pool(
+pub fn build_database_url(
+    template: &str,
+    username: &str,
+    password: &str,
+    host: &str,
+    port: u16,
+    db_name: &str,
+) -> String {
+    template
+        .replace("{0}", username)
+        .replace("{1}", &encode_special_chars(password))
+        // .replace("{2}", &db_connection);
+        .replace("{2}", host)
+        .replace("{3}", &port.to_string())
+        .replace("{4}", db_name)
+}
+
+pub async fn get_external_pool(
     conn: &mut PgConnection,
     ext_database_id: i64,
 ) -> poem::Result<(DatabasePool, Option<Child>, i16, String)> {
@@ -146,7 +489,10 @@ async fn get_external_pool(
         .first::<(String, String)>(conn)
         .map_err(|_| common::error_message(StatusCode::NOT_FOUND, "information.notFound"))?;
 
-    let (tunnel, target_ip, target_port): (Option<Child>, String, u16) =
+    // Password dibuka sebelum tunnel dibuat supaya kegagalan dekripsi tidak meninggalkan proses ssh
+    let password = reveal_credential(&password)?;
+
+    let (mut tunnel, target_ip, target_port): (Option<Child>, String, u16) =
         if let Some(id) = ext_server_id {
             let (tunnel_process, local_port) = start_ssh_tunnel(conn, id, &ip, port)?;
             (Some(tunnel_process), "localhost".into(), local_port)
@@ -154,64 +500,99 @@ async fn get_external_pool(
             (None, ip.clone(), port as u16)
         };
 
-    let url = url
-        .replace("{0}", &username)
-        .replace("{1}", &encode_special_chars(&password))
-        // .replace("{2}", &db_connection);
-        .replace("{2}", &target_ip)
-        .replace("{3}", &target_port.to_string())
-        .replace("{4}", &db_name);
+    let url = build_database_url(
+        &url,
+        &username,
+        &password,
+        &target_ip,
+        target_port,
+        &db_name,
+    );
     // println!("DB connection string : {}", url);
 
     let pool = match mt_database_type_id {
-        1 => {
-            let pool = PgPoolOptions::new()
-                .max_connections(5)
-                .connect(&url)
-                .await
-                .map_err(|e| {
-                    eprintln!("PostgreSQL connection error: {}", e);
-                    poem::Error::from_status(StatusCode::INTERNAL_SERVER_ERROR)
-                })?;
-            DatabasePool::Postgres(pool)
-        }
-        2 => {
-            let pool = MySqlPoolOptions::new()
-                .max_connections(5)
-                .connect(&url)
-                .await
-                .map_err(|e| {
-                    eprintln!("MySQL connection error: {}", e);
-                    poem::Error::from_status(StatusCode::INTERNAL_SERVER_ERROR)
-                })?;
-            DatabasePool::MySql(pool)
-        }
+        1 => PgPoolOptions::new()
+            .max_connections(5)
+            .connect(&url)
+            .await
+            .map(DatabasePool::Postgres)
+            .map_err(|e| {
+                eprintln!("PostgreSQL connection error: {}", e);
+                poem::Error::from_status(StatusCode::INTERNAL_SERVER_ERROR)
+            }),
+        2 => MySqlPoolOptions::new()
+            .max_connections(5)
+            .connect(&url)
+            .await
+            .map(DatabasePool::MySql)
+            .map_err(|e| {
+                eprintln!("MySQL connection error: {}", e);
+                poem::Error::from_status(StatusCode::INTERNAL_SERVER_ERROR)
+            }),
         _ => {
             eprintln!("Unsupported database type ID: {}", mt_database_type_id);
-            return Err(poem::Error::from_status(StatusCode::BAD_REQUEST));
+            Err(poem::Error::from_status(StatusCode::BAD_REQUEST))
         }
     };
 
-    Ok((pool, tunnel, is_use_page, pagination))
+    match pool {
+        Ok(pool) => Ok((pool, tunnel, is_use_page, pagination)),
+        Err(e) => {
+            if let Some(tunnel) = tunnel.as_mut() {
+                let _ = tunnel.kill().ok();
+            }
+            Err(e)
+        }
+    }
 }
 
 pub async fn query_with_pagination(
     conn: &mut PgConnection,
+    claims: &Claims,
     ext_database_id: i64,
     query: &str,
     start: i64,
     length: i64,
+    cursor: Option<&str>,
 ) -> poem::Result<PaginatedLoadedMoreResponse<Value>> {
+    let roles = claims.role.as_deref().unwrap_or_default();
+    let count_estimate_threshold = tbl_ext_database::table
+        .filter(tbl_ext_database::id.eq(ext_database_id))
+        .select(tbl_ext_database::count_estimate_threshold)
+        .first::<Option<i64>>(conn)
+        .map_err(|_| common::error_message(StatusCode::NOT_FOUND, "information.notFound"))?;
+    let mask = load_mask_policy(conn, ext_database_id, roles, query)?;
     let (ext_pool, tunnel, is_use_page, pagination) =
         get_external_pool(conn, ext_database_id).await?;
-    let respone = match ext_pool {
-        DatabasePool::Postgres(ref pg_pool) => {
-            query_with_pagination_postgres(pg_pool, &pagination, query, is_use_page, start, length)
-                .await
+    let context = ExecutionContext::new(&ext_pool, ext_database_id, claims.id, "page");
+    let dt_started = Instant::now();
+
+    let count = if is_use_page == 1 {
+        count_query_total(&ext_pool, query, count_estimate_threshold)
+            .await
+            .map(Some)
+    } else {
+        Ok(None)
+    };
+
+    // Tanpa paging, cursor (kosong untuk halaman pertama) mengaktifkan mode keyset
+    let keyset = match cursor {
+        Some(cursor) if is_use_page != 1 => {
+            query_with_keyset(conn, &context, &ext_pool, query, cursor, length, &mask).await
         }
-        DatabasePool::MySql(ref my_pool) => {
-            query_with_pagination_mysql(my_pool, &pagination, query, is_use_page, start, length)
-                .await
+        _ => Ok(None),
+    };
+    // Query keyset sudah dicatat sendiri lengkap dengan kondisi seek-nya
+    let is_keyset = !matches!(keyset, Ok(None));
+
+    let respone = match (count, keyset, &ext_pool) {
+        (Err(e), _, _) | (_, Err(e), _) => Err(e),
+        (_, Ok(Some(keyset)), _) => Ok(PaginatedLoadedMoreResponse::LoadedMore(keyset)),
+        (Ok(count), _, DatabasePool::Postgres(pg_pool)) => {
+            query_with_pagination_postgres(pg_pool, &pagination, query, count, start, length).await
+        }
+        (Ok(count), _, DatabasePool::MySql(my_pool)) => {
+            query_with_pagination_mysql(my_pool, &pagination, query, count, start, length).await
         }
     };
 
@@ -219,29 +600,81 @@ pub async fn query_with_pagination(
         let _ = tunnel.kill().ok();
     };
 
-    respone
+    if !is_keyset {
+        let outcome = match &respone {
+            Ok(PaginatedLoadedMoreResponse::Paginated(response)) => Ok(response.data.len()),
+            Ok(PaginatedLoadedMoreResponse::Estimated(response)) => Ok(response.data.len()),
+            Ok(PaginatedLoadedMoreResponse::LoadedMore(response)) => Ok(response.data.len()),
+            Err(e) => Err(e.to_string()),
+        };
+        record_execution(
+            conn,
+            &context,
+            query,
+            dt_started,
+            outcome.map(|rows| Some(rows as u64)),
+        );
+    }
+
+    respone.map(|mut response| {
+        let data = match &mut response {
+            PaginatedLoadedMoreResponse::Paginated(response) => &mut response.data,
+            PaginatedLoadedMoreResponse::Estimated(response) => &mut response.data,
+            PaginatedLoadedMoreResponse::LoadedMore(response) => &mut response.data,
+        };
+        mask.mask_rows(data);
+        response
+    })
+}
+
+async fn count_query_total(
+    ext_pool: &DatabasePool,
+    query: &str,
+    count_estimate_threshold: Option<i64>,
+) -> poem::Result<(i64, i16)> {
+    let dialect = sql_dialect(matches!(ext_pool, DatabasePool::MySql(_)));
+
+    // Tabel besar cukup memakai estimasi planner jika sudah melewati ambang batas
+    if let Some(threshold) = count_estimate_threshold {
+        let stripped_query = strip_query_ordering(query, dialect.as_ref());
+        if let Some(estimate) = estimate_row_count(ext_pool, &stripped_query).await
+            && estimate >= threshold
+        {
+            return Ok((estimate, 1));
+        }
+    }
+
+    let count_query = convert_to_count_query(query, dialect.as_ref());
+    let total = match ext_pool {
+        DatabasePool::Postgres(pg_pool) => {
+            sqlx::query_scalar::<_, i64>(&count_query)
+                .fetch_one(pg_pool)
+                .await
+        }
+        DatabasePool::MySql(my_pool) => {
+            sqlx::query_scalar::<_, i64>(&count_query)
+                .fetch_one(my_pool)
+                .await
+        }
+    }
+    .map_err(|e| {
+        eprintln!("Query error: {}", e);
+        poem::Error::from_status(StatusCode::INTERNAL_SERVER_ERROR)
+    })?;
+
+    Ok((total, 0))
 }
 
 async fn query_with_pagination_postgres(
     pool: &Pool<Postgres>,
     pagination: &str,
     base_query: &str,
-    is_use_page: i16,
+    count: Option<(i64, i16)>,
     start: i64,
     length: i64,
 ) -> poem::Result<PaginatedLoadedMoreResponse<Value>> {
-    let total = if is_use_page == 1 {
-        if let Some(count_query) = convert_to_count_query(base_query) {
-            sqlx::query_scalar::<_, i64>(&count_query)
-                .fetch_one(pool)
-                .await
-                .unwrap_or(0)
-        } else {
-            0
-        }
-    } else {
-        0
-    };
+    let is_use_page = if count.is_some() { 1 } else { 0 };
+    let (total, is_estimated) = count.unwrap_or((0, 0));
 
     let mut data = if total > 0 || is_use_page != 1 {
         let limit = if is_use_page == 1 { length } else { length + 1 };
@@ -263,7 +696,15 @@ async fn query_with_pagination_postgres(
         vec![]
     };
 
-    if is_use_page == 1 {
+    if is_estimated == 1 {
+        Ok(PaginatedLoadedMoreResponse::Estimated(
+            EstimatedPaginatedResponse {
+                total,
+                is_estimated,
+                data,
+            },
+        ))
+    } else if is_use_page == 1 {
         Ok(PaginatedLoadedMoreResponse::Paginated(PaginatedResponse {
             total,
             data,
@@ -275,7 +716,11 @@ async fn query_with_pagination_postgres(
             loaded = 1;
         }
         Ok(PaginatedLoadedMoreResponse::LoadedMore(
-            LoadedMoreResponse { loaded, data },
+            LoadedMoreResponse {
+                loaded,
+                data,
+                cursor: None,
+            },
         ))
     }
 }
@@ -284,22 +729,12 @@ async fn query_with_pagination_mysql(
     pool: &Pool<MySql>,
     pagination: &str,
     base_query: &str,
-    is_use_page: i16,
+    count: Option<(i64, i16)>,
     start: i64,
     length: i64,
 ) -> poem::Result<PaginatedLoadedMoreResponse<Value>> {
-    let total = if is_use_page == 1 {
-        if let Some(count_query) = convert_to_count_query(base_query) {
-            sqlx::query_scalar::<_, i64>(&count_query)
-                .fetch_one(pool)
-                .await
-                .unwrap_or(0)
-        } else {
-            0
-        }
-    } else {
-        0
-    };
+    let is_use_page = if count.is_some() { 1 } else { 0 };
+    let (total, is_estimated) = count.unwrap_or((0, 0));
 
     let mut data = if total > 0 || is_use_page != 1 {
         let limit = if is_use_page == 1 { length } else { length + 1 };
@@ -321,7 +756,15 @@ async fn query_with_pagination_mysql(
         vec![]
     };
 
-    if is_use_page == 1 {
+    if is_estimated == 1 {
+        Ok(PaginatedLoadedMoreResponse::Estimated(
+            EstimatedPaginatedResponse {
+                total,
+                is_estimated,
+                data,
+            },
+        ))
+    } else if is_use_page == 1 {
         Ok(PaginatedLoadedMoreResponse::Paginated(PaginatedResponse {
             total,
             data,
@@ -333,48 +776,62 @@ async fn query_with_pagination_mysql(
             loaded = 1;
         }
         Ok(PaginatedLoadedMoreResponse::LoadedMore(
-            LoadedMoreResponse { loaded, data },
+            LoadedMoreResponse {
+                loaded,
+                data,
+                cursor: None,
+            },
         ))
     }
 }
 
-async fn run_and_extract_columns(
+pub async fn run_and_extract_columns(
     conn: &mut PgConnection,
     ext_database_id: i64,
+    user_id: i64,
     raw_query: &str,
 ) -> poem::Result<Vec<serde_json::Value>> {
     let (ext_pool, tunnel, _, pagination) = get_external_pool(conn, ext_database_id).await?;
+    let context = ExecutionContext::new(&ext_pool, ext_database_id, user_id, "run");
 
     let query = pagination
         .replace("{0}", raw_query)
         .replace("{1}", "0")
         .replace("{2}", "1");
 
+    let dt_started = Instant::now();
     let columns_info = match &ext_pool {
-        DatabasePool::Postgres(_) => {
-            let rows = ext_pool
-                .fetch_all_postgres(&query)
-                .await
-                .map_err(|_| poem::Error::from_status(StatusCode::INTERNAL_SERVER_ERROR))?;
-            extract_columns_info_postgres(&rows)
-        }
-        DatabasePool::MySql(_) => {
-            let rows = ext_pool
-                .fetch_all_mysql(&query)
-                .await
-                .map_err(|_| poem::Error::from_status(StatusCode::INTERNAL_SERVER_ERROR))?;
-            extract_columns_info_mysql(&rows)
-        }
+        DatabasePool::Postgres(_) => ext_pool
+            .fetch_all_postgres(&query)
+            .await
+            .map(|rows| (rows.len(), extract_columns_info_postgres(&rows))),
+        DatabasePool::MySql(_) => ext_pool
+            .fetch_all_mysql(&query)
+            .await
+            .map(|rows| (rows.len(), extract_columns_info_mysql(&rows))),
     };
 
     if let Some(mut tunnel) = tunnel {
         let _ = tunnel.kill().ok();
     };
 
-    Ok(columns_info)
+    record_execution(
+        conn,
+        &context,
+        raw_query,
+        dt_started,
+        columns_info
+            .as_ref()
+            .map(|(rows, _)| Some(*rows as u64))
+            .map_err(|e| e.to_string()),
+    );
+
+    columns_info
+        .map(|(_, columns_info)| columns_info)
+        .map_err(|_| poem::Error::from_status(StatusCode::INTERNAL_SERVER_ERROR))
 }
 
-fn get_whitelist_query(conn: &mut PgConnection, query_id: i64) -> poem::Result<(i64, String)> {
+pub fn get_whitelist_query(conn: &mut PgConnection, query_id: i64) -> poem::Result<(i64, String)> {
     tbl_ext_database_query::table
         .filter(tbl_ext_database_query::id.eq(query_id))
         .filter(tbl_ext_database_query::is_del.eq(0))
@@ -386,6 +843,59 @@ fn get_whitelist_query(conn: &mut PgConnection, query_id: i64) -> poem::Result<(
         .map_err(|_| common::error_message(StatusCode::NOT_FOUND, "information.notFound"))
 }
 
+fn get_schema_setting(
+    conn: &mut PgConnection,
+    ext_database_id: i64,
+) -> poem::Result<(Option<String>, i16)> {
+    tbl_ext_database::table
+        .filter(tbl_ext_database::id.eq(ext_database_id))
+        .filter(tbl_ext_database::is_del.eq(0))
+        .select((
+            tbl_ext_database::default_schema,
+            tbl_ext_database::mt_database_type_id,
+        ))
+        .first::<(Option<String>, i16)>(conn)
+        .map_err(|_| common::error_message(StatusCode::NOT_FOUND, "information.notFound"))
+}
+
+pub fn resolve_schema(
+    conn: &mut PgConnection,
+    ext_database_id: i64,
+    schema: Option<String>,
+) -> poem::Result<Option<String>> {
+    match schema.filter(|s| !s.trim().is_empty()) {
+        Some(schema) => Ok(Some(schema)),
+        None => Ok(get_schema_setting(conn, ext_database_id)?
+            .0
+            .filter(|s| !s.trim().is_empty())),
+    }
+}
+
+fn qualify_object_name(
+    conn: &mut PgConnection,
+    ext_database_id: i64,
+    schema: Option<String>,
+    object_name: &str,
+) -> poem::Result<String> {
+    let (default_schema, mt_database_type_id) = get_schema_setting(conn, ext_database_id)?;
+    match schema
+        .filter(|s| !s.trim().is_empty())
+        .or(default_schema.filter(|s| !s.trim().is_empty()))
+    {
+        Some(schema) if mt_database_type_id == 2 => Ok(format!(
+            "{}.{}",
+            quote_identifier_mysql(&schema),
+            object_name
+        )),
+        Some(schema) => Ok(format!(
+            "{}.{}",
+            quote_identifier_postgres(&schema),
+            object_name
+        )),
+        None => Ok(object_name.to_string()),
+    }
+}
+
 #[handler]
 pub async fn connect(
     pool: poem::web::Data<&DbPool>,
@@ -412,12 +922,87 @@ pub async fn connect(
     }))
 }
 
+#[handler]
+pub async fn query_schema_list(
+    pool: poem::web::Data<&DbPool>,
+    _: crate::auth::middleware::JwtAuth,
+    Path(ext_database_id): Path<i64>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(ext_database_id)?;
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    let default_schema = resolve_schema(conn, ext_database_id, None)?;
+    let (ext_pool, mut tunnel, _, _) = get_external_pool(conn, ext_database_id).await?;
+
+    let schemas: Result<Vec<(String, bool)>, sqlx::Error> = match ext_pool {
+        DatabasePool::Postgres(ref pg_pool) => {
+            sqlx::query_as(
+                r#"
+                SELECT
+                    nspname::TEXT AS schema_name,
+                    nspname = COALESCE($1::TEXT, current_schema()::TEXT) AS is_default
+                FROM pg_catalog.pg_namespace
+                WHERE nspname NOT LIKE 'pg\_%'
+                AND nspname <> 'information_schema'
+                ORDER BY nspname
+            "#,
+            )
+            .bind(&default_schema)
+            .fetch_all(pg_pool)
+            .await
+        }
+        DatabasePool::MySql(ref my_pool) => sqlx::query_as::<_, (String, i64)>(
+            r#"
+                SELECT
+                    CAST(SCHEMA_NAME AS CHAR) AS schema_name,
+                    CAST(SCHEMA_NAME = COALESCE(?, DATABASE()) AS SIGNED) AS is_default
+                FROM information_schema.SCHEMATA
+                WHERE SCHEMA_NAME NOT IN ('information_schema', 'performance_schema', 'mysql', 'sys')
+                ORDER BY SCHEMA_NAME
+            "#,
+        )
+        .bind(&default_schema)
+        .fetch_all(my_pool)
+        .await
+        .map(|rows| {
+            rows.into_iter()
+                .map(|(name, is_default)| (name, is_default == 1))
+                .collect()
+        }),
+    };
+
+    if let Some(mut tunnel) = tunnel {
+        let _ = tunnel.kill().ok();
+    };
+
+    let schemas = schemas.map_err(|e| {
+        eprintln!("Query error: {}", e);
+        poem::Error::from_status(StatusCode::INTERNAL_SERVER_ERROR)
+    })?;
+
+    Ok(Json(DataResponse {
+        data: schemas
+            .into_iter()
+            .map(|(name, is_default)| {
+                json!({ "name": name, "defaultFlag": if is_default { 1 } else { 0 } })
+            })
+            .collect::<Vec<Value>>(),
+    }))
+}
+
 #[handler]
 pub async fn query_object_list(
     pool: poem::web::Data<&DbPool>,
     _: crate::auth::middleware::JwtAuth,
     Path(ext_database_id): Path<i64>,
     Query(pagination): Query<Pagination>,
+    Query(schema_param): Query<SchemaParam>,
 ) -> poem::Result<impl IntoResponse> {
     validate_id(ext_database_id)?;
 
@@ -430,11 +1015,17 @@ pub async fn query_object_list(
         )
     })?;
 
+    let schema = resolve_schema(conn, ext_database_id, schema_param.schema)?;
     let (ext_pool, mut tunnel, _, pagination) = get_external_pool(conn, ext_database_id).await?;
 
     let response = match ext_pool {
         DatabasePool::Postgres(ref pg_pool) => {
-            let query = r#"
+            let schema = schema
+                .as_deref()
+                .map(quote_literal_postgres)
+                .unwrap_or_else(|| String::from("current_schema()"));
+            let query = &format!(
+                r#"
             SELECT
                 objects.object_id,
                 objects.object_name,
@@ -446,7 +1037,8 @@ pub async fn query_object_list(
                     'view' AS object_type
                 FROM pg_catalog.pg_views views
                 LEFT JOIN pg_class ON pg_class.relname = views.viewname
-                WHERE views.schemaname = 'public'
+                    AND pg_class.relnamespace = (SELECT oid FROM pg_catalog.pg_namespace WHERE nspname = {0})
+                WHERE views.schemaname = {0}
     
                 UNION ALL
     
@@ -456,7 +1048,8 @@ pub async fn query_object_list(
                     'table' AS object_type
                 FROM pg_catalog.pg_tables tables
                 LEFT JOIN pg_class ON pg_class.relname = tables.tablename
-                WHERE tables.schemaname = 'public'
+                    AND pg_class.relnamespace = (SELECT oid FROM pg_catalog.pg_namespace WHERE nspname = {0})
+                WHERE tables.schemaname = {0}
     
                 UNION ALL
     
@@ -467,17 +1060,26 @@ pub async fn query_object_list(
                     'function' AS object_type
                 FROM pg_catalog.pg_proc functions
                 WHERE functions.pronamespace IN (
-                    SELECT oid FROM pg_catalog.pg_namespace WHERE nspname = 'public'
+                    SELECT oid FROM pg_catalog.pg_namespace WHERE nspname = {0}
                 )
             ) objects
             WHERE 1 = 1
-            --{1}
-            --ORDER BY {2}
-        "#;
-            query_with_pagination_postgres(pg_pool, &pagination, query, 1, start, length).await?
+            --{{1}}
+            --ORDER BY {{2}}
+        "#,
+                schema
+            );
+            let count = count_query_total(&ext_pool, query, None).await?;
+            query_with_pagination_postgres(pg_pool, &pagination, query, Some(count), start, length)
+                .await?
         }
         DatabasePool::MySql(ref my_pool) => {
-            let query = r#"
+            let schema = schema
+                .as_deref()
+                .map(quote_literal_mysql)
+                .unwrap_or_else(|| String::from("DATABASE()"));
+            let query = &format!(
+                r#"
             SELECT
                 object_id,
                 object_name,
@@ -488,7 +1090,7 @@ pub async fn query_object_list(
                     TABLE_NAME AS object_name,
                     CASE WHEN TABLE_TYPE = 'BASE TABLE' THEN 'table' ELSE 'view' END AS object_type
                 FROM information_schema.tables
-                WHERE TABLE_SCHEMA = DATABASE()
+                WHERE TABLE_SCHEMA = {0}
 
                 UNION ALL
 
@@ -497,11 +1099,15 @@ pub async fn query_object_list(
                     ROUTINE_NAME AS object_name,
                     ROUTINE_TYPE AS object_type
                 FROM information_schema.routines
-                WHERE ROUTINE_SCHEMA = DATABASE()
+                WHERE ROUTINE_SCHEMA = {0}
             ) AS objects
             WHERE 1 = 1
-        "#;
-            query_with_pagination_mysql(my_pool, &pagination, query, 1, start, length).await?
+        "#,
+                schema
+            );
+            let count = count_query_total(&ext_pool, query, None).await?;
+            query_with_pagination_mysql(my_pool, &pagination, query, Some(count), start, length)
+                .await?
         }
     };
 
@@ -602,7 +1208,8 @@ pub async fn query_manual_run(
         )
     })?;
 
-    let (ext_pool, mut tunnel, _, pagination) = get_external_pool(conn, ext_database_id).await?;
+    let (ext_pool, mut tunnel, is_use_page, pagination) =
+        get_external_pool(conn, ext_database_id).await?;
 
     let mut success_response: Option<Value> = None;
     let mut results: Vec<Value> = Vec::new();
@@ -612,36 +1219,55 @@ pub async fn query_manual_run(
     let mut last_query: Option<String> = None;
     let mut last_affected = 0;
 
+    let context = ExecutionContext::new(&ext_pool, ext_database_id, jwt_auth.claims.id, "manual");
+    let dialect = sql_dialect(matches!(ext_pool, DatabasePool::MySql(_)));
     let parts = split_manual_query(&entry_manual_ext_database.query);
     for part in parts {
         let mut affected = 0;
         let mut error: Option<String> = None;
 
-        match extract_query_parts(&part) {
-            Some((name, action)) => {
-                if is_sql_type(&part, "(SELECT|WITH)") && results.is_empty() {
+        if is_only_comment(&part) {
+            continue;
+        }
+
+        match parse_statement_info(&part, dialect.as_ref()) {
+            Ok(statement) => {
+                let name = statement.name.clone();
+                let action = statement.kind.action().to_string();
+
+                if statement.kind == SqlStatementKind::Select && results.is_empty() {
                     let query = pagination
                         .replace("{0}", &part)
                         .replace("{1}", "0")
                         .replace("{2}", "1");
 
-                    let columns_info = match &ext_pool {
-                        DatabasePool::Postgres(_) => {
-                            match ext_pool.fetch_all_postgres(&query).await {
-                                Ok(rows) => extract_columns_info_postgres(&rows),
-                                Err(e) => {
-                                    results.push(json!({ "message": format!("{}", e) }));
-                                    continue;
-                                }
-                            }
+                    let dt_started = Instant::now();
+                    let fetched = match &ext_pool {
+                        DatabasePool::Postgres(_) => ext_pool
+                            .fetch_all_postgres(&query)
+                            .await
+                            .map(|rows| (rows.len(), extract_columns_info_postgres(&rows))),
+                        DatabasePool::MySql(_) => ext_pool
+                            .fetch_all_mysql(&query)
+                            .await
+                            .map(|rows| (rows.len(), extract_columns_info_mysql(&rows))),
+                    };
+                    let columns_info = match fetched {
+                        Ok((rows, columns_info)) => {
+                            record_execution(
+                                conn,
+                                &context,
+                                &part,
+                                dt_started,
+                                Ok(Some(rows as u64)),
+                            );
+                            columns_info
+                        }
+                        Err(e) => {
+                            record_execution(conn, &context, &part, dt_started, Err(e.to_string()));
+                            results.push(json!({ "message": format!("{}", e) }));
+                            continue;
                         }
-                        DatabasePool::MySql(_) => match ext_pool.fetch_all_mysql(&query).await {
-                            Ok(rows) => extract_columns_info_mysql(&rows),
-                            Err(e) => {
-                                results.push(json!({ "message": format!("{}", e) }));
-                                continue;
-                            }
-                        },
                     };
 
                     match diesel::insert_into(tbl_query_manual::table)
@@ -649,6 +1275,9 @@ pub async fn query_manual_run(
                             id: common::generate_id(),
                             ext_database_id,
                             query: part.to_string(),
+                            title: None,
+                            tags: None,
+                            is_favourite: 0,
                             created_by: jwt_auth.claims.id,
                             dt_created: chrono::Utc::now().naive_utc(),
                             updated_by: None,
@@ -658,10 +1287,33 @@ pub async fn query_manual_run(
                         .get_result::<QueryManual>(conn)
                     {
                         Ok(inserted) => {
-                            success_response = Some(json!({
+                            let mut response = json!({
                                 "id": inserted.id,
-                                "header": columns_info
-                            }));
+                                "header": columns_info,
+                                "tableList": statement.tables,
+                            });
+                            // Snapshot hanya untuk database yang mengizinkan export penuh
+                            if entry_manual_ext_database.is_snapshot == 1 && is_use_page == 1 {
+                                match capture_snapshot(
+                                    conn,
+                                    &ext_pool,
+                                    &context,
+                                    inserted.id,
+                                    &part,
+                                    jwt_auth.claims.id,
+                                )
+                                .await
+                                {
+                                    Ok(dt_captured) => {
+                                        response["snapshotDate"] = json!(dt_captured);
+                                    }
+                                    Err(message) => {
+                                        response["snapshotDate"] = Value::Null;
+                                        response["snapshotMessage"] = json!(message);
+                                    }
+                                }
+                            }
+                            success_response = Some(response);
                         }
                         Err(e) => {
                             eprintln!("Inserting error: {}", e);
@@ -671,18 +1323,49 @@ pub async fn query_manual_run(
                             );
                         }
                     }
-                } else if is_sql_type(&part, "(DROP|CREATE|ALTER)") {
-                    match ext_pool.execute(&part).await {
-                        Ok(_) => affected = 1,
+                } else if matches!(
+                    statement.kind,
+                    SqlStatementKind::Create | SqlStatementKind::Alter | SqlStatementKind::Drop
+                ) {
+                    let dt_started = Instant::now();
+                    let executed = ext_pool.execute(&part).await;
+                    record_execution(
+                        conn,
+                        &context,
+                        &part,
+                        dt_started,
+                        executed.as_ref().map(|_| None).map_err(|e| e.to_string()),
+                    );
+                    match executed {
+                        Ok(_) => {
+                            affected = 1;
+                            invalidate_metadata(ext_database_id);
+                        }
                         Err(e) => error = Some(format!("{}", e)),
                     }
-                } else if is_sql_type(&part, "(INSERT|(UPDATE|DELETE)\\s.+\\s?WHERE)") {
-                    match ext_pool.execute(&part).await {
+                } else if matches!(
+                    statement.kind,
+                    SqlStatementKind::Insert | SqlStatementKind::Update | SqlStatementKind::Delete
+                ) && !statement.is_unbounded
+                {
+                    let dt_started = Instant::now();
+                    let executed = ext_pool.execute(&part).await;
+                    record_execution(
+                        conn,
+                        &context,
+                        &part,
+                        dt_started,
+                        executed
+                            .as_ref()
+                            .map(|rows| Some(*rows))
+                            .map_err(|e| e.to_string()),
+                    );
+                    match executed {
                         Ok(rows) => affected = rows,
                         Err(e) => error = Some(format!("{}", e)),
                     }
-                } else if is_only_comment(&part) {
-                    continue;
+                } else if statement.is_unbounded {
+                    error = Some(String::from("UPDATE/DELETE without WHERE is not allowed"));
                 } else {
                     error = Some(String::from("Abnormal"));
                 }
@@ -734,9 +1417,9 @@ pub async fn query_manual_run(
                     }
                 }
             }
-            None => {
+            Err(e) => {
                 println!("No match found for part: {}", part);
-                results.push(json!({ "error": &part }));
+                results.push(json!({ "error": &part, "message": e }));
             }
         }
     }
@@ -767,7 +1450,7 @@ pub async fn query_manual_run(
 #[handler]
 pub async fn query_manual_list(
     pool: poem::web::Data<&DbPool>,
-    _: crate::auth::middleware::JwtAuth,
+    jwt_auth: crate::auth::middleware::JwtAuth,
     Path(query_manual_id): Path<i64>,
     Query(pagination): Query<Pagination>,
 ) -> poem::Result<impl IntoResponse> {
@@ -783,15 +1466,23 @@ pub async fn query_manual_list(
     })?;
 
     let (ext_database_id, query_string) = get_manual_query(conn, query_manual_id)?;
-    let response =
-        query_with_pagination(conn, ext_database_id, &query_string, start, length).await?;
+    let response = query_with_pagination(
+        conn,
+        &jwt_auth.claims,
+        ext_database_id,
+        &query_string,
+        start,
+        length,
+        pagination.cursor.as_deref(),
+    )
+    .await?;
     Ok(Json(response))
 }
 
 #[handler]
 pub async fn query_manual_all_list(
     pool: poem::web::Data<&DbPool>,
-    _: crate::auth::middleware::JwtAuth,
+    jwt_auth: crate::auth::middleware::JwtAuth,
     Path(query_manual_id): Path<i64>,
 ) -> poem::Result<impl IntoResponse> {
     validate_id(query_manual_id)?;
@@ -803,14 +1494,18 @@ pub async fn query_manual_all_list(
         )
     })?;
 
-    let (results, _, _) = get_query_manual_row(conn, query_manual_id).await?;
-    Ok(Json(DataResponse { data: results }))
+    let (results, _, _, _, dt_captured) =
+        get_query_manual_row(conn, query_manual_id, &jwt_auth.claims).await?;
+    Ok(with_snapshot_header(
+        Json(DataResponse { data: results }),
+        dt_captured,
+    ))
 }
 
 #[handler]
 pub async fn query_manual_sql_insert(
     pool: poem::web::Data<&DbPool>,
-    _: crate::auth::middleware::JwtAuth,
+    jwt_auth: crate::auth::middleware::JwtAuth,
     Path((query_manual_id, include_column_name_flag, number_line_per_action)): Path<(
         i64,
         i16,
@@ -826,19 +1521,26 @@ pub async fn query_manual_sql_insert(
         )
     })?;
 
-    let (rows, headers, query_str) = get_query_manual_row(conn, query_manual_id).await?;
-    match extract_query_parts(&query_str) {
-        Some((name, _)) => {
+    let (rows, headers, query_str, columns, dt_captured) =
+        get_query_manual_row(conn, query_manual_id, &jwt_auth.claims).await?;
+    let (ext_database_id, _) = get_manual_query(conn, query_manual_id)?;
+    let dialect = database_dialect(conn, ext_database_id)?;
+    match parse_statement_info(&query_str, dialect.as_ref()) {
+        Ok(SqlStatementInfo { name, .. }) => {
             let results = rows_to_insert_query_string(
                 &name,
                 include_column_name_flag,
                 number_line_per_action,
                 rows,
                 headers,
+                &columns,
             );
-            Ok(Json(DataResponse { data: results }))
+            Ok(with_snapshot_header(
+                Json(DataResponse { data: results }),
+                dt_captured,
+            ))
         }
-        None => Err(common::error_message(
+        Err(_) => Err(common::error_message(
             StatusCode::INTERNAL_SERVER_ERROR,
             "information.notFound",
         )),
@@ -848,7 +1550,7 @@ pub async fn query_manual_sql_insert(
 #[handler]
 pub async fn query_manual_sql_update(
     pool: poem::web::Data<&DbPool>,
-    _: crate::auth::middleware::JwtAuth,
+    jwt_auth: crate::auth::middleware::JwtAuth,
     Path((query_manual_id, multiple_line_flag, first_amount_conditioned)): Path<(i64, i16, i16)>,
 ) -> poem::Result<impl IntoResponse> {
     validate_id(query_manual_id)?;
@@ -860,19 +1562,26 @@ pub async fn query_manual_sql_update(
         )
     })?;
 
-    let (rows, headers, query_str) = get_query_manual_row(conn, query_manual_id).await?;
-    match extract_query_parts(&query_str) {
-        Some((name, _)) => {
+    let (rows, headers, query_str, columns, dt_captured) =
+        get_query_manual_row(conn, query_manual_id, &jwt_auth.claims).await?;
+    let (ext_database_id, _) = get_manual_query(conn, query_manual_id)?;
+    let dialect = database_dialect(conn, ext_database_id)?;
+    match parse_statement_info(&query_str, dialect.as_ref()) {
+        Ok(SqlStatementInfo { name, .. }) => {
             let results = rows_to_update_query_string(
                 &name,
                 multiple_line_flag,
                 first_amount_conditioned,
                 rows,
                 headers,
+                &columns,
             );
-            Ok(Json(DataResponse { data: results }))
+            Ok(with_snapshot_header(
+                Json(DataResponse { data: results }),
+                dt_captured,
+            ))
         }
-        None => Err(common::error_message(
+        Err(_) => Err(common::error_message(
             StatusCode::INTERNAL_SERVER_ERROR,
             "information.notFound",
         )),
@@ -882,7 +1591,7 @@ pub async fn query_manual_sql_update(
 #[handler]
 pub async fn query_manual_xlsx(
     pool: poem::web::Data<&DbPool>,
-    _: crate::auth::middleware::JwtAuth,
+    jwt_auth: crate::auth::middleware::JwtAuth,
     Path((query_manual_id, first_amount_combined)): Path<(i64, i16)>,
 ) -> poem::Result<impl IntoResponse> {
     validate_id(query_manual_id)?;
@@ -894,26 +1603,94 @@ pub async fn query_manual_xlsx(
         )
     })?;
 
-    let (rows, headers, _) = get_query_manual_row(conn, query_manual_id).await?;
+    let (rows, headers, _, _, dt_captured) =
+        get_query_manual_row(conn, query_manual_id, &jwt_auth.claims).await?;
     let results = rows_to_xlsx_bytes(first_amount_combined, rows, headers)?;
 
-    Ok(poem::Response::builder()
-        .status(StatusCode::OK)
-        .header(
-            "Content-Type",
-            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    Ok(with_snapshot_header(
+        poem::Response::builder()
+            .status(StatusCode::OK)
+            .header(
+                "Content-Type",
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+            )
+            .header(
+                "Content-Disposition",
+                "attachment; filename=\"export.xlsx\"",
+            )
+            .body(results),
+        dt_captured,
+    ))
+}
+
+#[handler]
+pub async fn query_manual_parquet(
+    pool: poem::web::Data<&DbPool>,
+    jwt_auth: crate::auth::middleware::JwtAuth,
+    Path(query_manual_id): Path<i64>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(query_manual_id)?;
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
         )
-        .header(
-            "Content-Disposition",
-            "attachment; filename=\"export.xlsx\"",
+    })?;
+
+    let (rows, _, _, columns, dt_captured) =
+        get_query_manual_row(conn, query_manual_id, &jwt_auth.claims).await?;
+    let results = rows_to_parquet_bytes(&rows, &columns)?;
+
+    Ok(with_snapshot_header(
+        poem::Response::builder()
+            .status(StatusCode::OK)
+            .header("Content-Type", "application/vnd.apache.parquet")
+            .header(
+                "Content-Disposition",
+                "attachment; filename=\"export.parquet\"",
+            )
+            .body(results),
+        dt_captured,
+    ))
+}
+
+#[handler]
+pub async fn query_manual_arrow(
+    pool: poem::web::Data<&DbPool>,
+    jwt_auth: crate::auth::middleware::JwtAuth,
+    Path(query_manual_id): Path<i64>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(query_manual_id)?;
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
         )
-        .body(results))
+    })?;
+
+    let (rows, _, _, columns, dt_captured) =
+        get_query_manual_row(conn, query_manual_id, &jwt_auth.claims).await?;
+    let results = rows_to_arrow_bytes(&rows, &columns)?;
+
+    Ok(with_snapshot_header(
+        poem::Response::builder()
+            .status(StatusCode::OK)
+            .header("Content-Type", "application/vnd.apache.arrow.file")
+            .header(
+                "Content-Disposition",
+                "attachment; filename=\"export.arrow\"",
+            )
+            .body(results),
+        dt_captured,
+    ))
 }
 
 #[handler]
 pub async fn query_manual_csv(
     pool: poem::web::Data<&DbPool>,
-    _: crate::auth::middleware::JwtAuth,
+    jwt_auth: crate::auth::middleware::JwtAuth,
     Path((query_manual_id, header_flag, delimiter)): Path<(i64, i16, String)>,
 ) -> poem::Result<impl IntoResponse> {
     validate_id(query_manual_id)?;
@@ -925,15 +1702,27 @@ pub async fn query_manual_csv(
         )
     })?;
 
-    let (rows, headers, _) = get_query_manual_row(conn, query_manual_id).await?;
-    let results = rows_to_csv_string(header_flag, &delimiter, rows, headers);
-    Ok(Json(DataResponse { data: results }))
+    let format = ExportFormat::Csv {
+        header_flag,
+        delimiter,
+    };
+    let (body, dt_captured) =
+        stream_query_manual_row(conn, &pool, query_manual_id, format, &jwt_auth.claims).await?;
+
+    Ok(with_snapshot_header(
+        poem::Response::builder()
+            .status(StatusCode::OK)
+            .header("Content-Type", "text/csv; charset=utf-8")
+            .header("Content-Disposition", "attachment; filename=\"export.csv\"")
+            .body(body),
+        dt_captured,
+    ))
 }
 
 #[handler]
 pub async fn query_manual_json(
     pool: poem::web::Data<&DbPool>,
-    _: crate::auth::middleware::JwtAuth,
+    jwt_auth: crate::auth::middleware::JwtAuth,
     Path(query_manual_id): Path<i64>,
 ) -> poem::Result<impl IntoResponse> {
     validate_id(query_manual_id)?;
@@ -945,15 +1734,32 @@ pub async fn query_manual_json(
         )
     })?;
 
-    let (rows, headers, _) = get_query_manual_row(conn, query_manual_id).await?;
-    let results = rows_to_json_string(rows, headers);
-    Ok(Json(DataResponse { data: results }))
+    let (body, dt_captured) = stream_query_manual_row(
+        conn,
+        &pool,
+        query_manual_id,
+        ExportFormat::Ndjson,
+        &jwt_auth.claims,
+    )
+    .await?;
+
+    Ok(with_snapshot_header(
+        poem::Response::builder()
+            .status(StatusCode::OK)
+            .header("Content-Type", "application/x-ndjson")
+            .header(
+                "Content-Disposition",
+                "attachment; filename=\"export.ndjson\"",
+            )
+            .body(body),
+        dt_captured,
+    ))
 }
 
 #[handler]
 pub async fn query_manual_xml(
     pool: poem::web::Data<&DbPool>,
-    _: crate::auth::middleware::JwtAuth,
+    jwt_auth: crate::auth::middleware::JwtAuth,
     Path(query_manual_id): Path<i64>,
 ) -> poem::Result<impl IntoResponse> {
     validate_id(query_manual_id)?;
@@ -965,13 +1771,29 @@ pub async fn query_manual_xml(
         )
     })?;
 
-    let (rows, headers, query_str) = get_query_manual_row(conn, query_manual_id).await?;
-    match extract_query_parts(&query_str) {
-        Some((name, _)) => {
-            let results = rows_to_xml_string(&name, rows, headers);
-            Ok(Json(DataResponse { data: results }))
+    let (ext_database_id, query_str) = get_manual_query(conn, query_manual_id)?;
+    let dialect = database_dialect(conn, ext_database_id)?;
+    match parse_statement_info(&query_str, dialect.as_ref()) {
+        Ok(SqlStatementInfo { name, .. }) => {
+            let (body, dt_captured) = stream_query_manual_row(
+                conn,
+                &pool,
+                query_manual_id,
+                ExportFormat::Xml { name },
+                &jwt_auth.claims,
+            )
+            .await?;
+
+            Ok(with_snapshot_header(
+                poem::Response::builder()
+                    .status(StatusCode::OK)
+                    .header("Content-Type", "application/xml; charset=utf-8")
+                    .header("Content-Disposition", "attachment; filename=\"export.xml\"")
+                    .body(body),
+                dt_captured,
+            ))
         }
-        None => Err(common::error_message(
+        Err(_) => Err(common::error_message(
             StatusCode::INTERNAL_SERVER_ERROR,
             "information.notFound",
         )),
@@ -981,8 +1803,9 @@ pub async fn query_manual_xml(
 #[handler]
 pub async fn query_exact_object_run(
     pool: poem::web::Data<&DbPool>,
-    _: crate::auth::middleware::JwtAuth,
+    jwt_auth: crate::auth::middleware::JwtAuth,
     Path((ext_database_id, entity_name)): Path<(i64, String)>,
+    Query(schema_param): Query<SchemaParam>,
 ) -> poem::Result<impl IntoResponse> {
     validate_id(ext_database_id)?;
 
@@ -993,8 +1816,11 @@ pub async fn query_exact_object_run(
         )
     })?;
 
+    let entity_name =
+        qualify_object_name(conn, ext_database_id, schema_param.schema, &entity_name)?;
     let query = &format!("SELECT * FROM {0}", entity_name);
-    let columns_info = run_and_extract_columns(conn, ext_database_id, &query).await?;
+    let columns_info =
+        run_and_extract_columns(conn, ext_database_id, jwt_auth.claims.id, query).await?;
 
     Ok(Json(DataResponse {
         data: Value::Array(columns_info),
@@ -1004,9 +1830,10 @@ pub async fn query_exact_object_run(
 #[handler]
 pub async fn query_exact_object_list(
     pool: poem::web::Data<&DbPool>,
-    _: crate::auth::middleware::JwtAuth,
+    jwt_auth: crate::auth::middleware::JwtAuth,
     Path((ext_database_id, entity_name)): Path<(i64, String)>,
     Query(pagination): Query<Pagination>,
+    Query(schema_param): Query<SchemaParam>,
 ) -> poem::Result<impl IntoResponse> {
     validate_id(ext_database_id)?;
 
@@ -1019,15 +1846,26 @@ pub async fn query_exact_object_list(
         )
     })?;
 
+    let entity_name =
+        qualify_object_name(conn, ext_database_id, schema_param.schema, &entity_name)?;
     let query = format!("SELECT * FROM {}", entity_name);
-    let response = query_with_pagination(conn, ext_database_id, &query, start, length).await?;
+    let response = query_with_pagination(
+        conn,
+        &jwt_auth.claims,
+        ext_database_id,
+        &query,
+        start,
+        length,
+        pagination.cursor.as_deref(),
+    )
+    .await?;
     Ok(Json(response))
 }
 
 #[handler]
 pub async fn query_exact_whitelist_run(
     pool: poem::web::Data<&DbPool>,
-    _: crate::auth::middleware::JwtAuth,
+    jwt_auth: crate::auth::middleware::JwtAuth,
     Path(ext_database_query_id): Path<i64>,
 ) -> poem::Result<impl IntoResponse> {
     validate_id(ext_database_query_id)?;
@@ -1040,7 +1878,8 @@ pub async fn query_exact_whitelist_run(
     })?;
 
     let (ext_database_id, query_string) = get_whitelist_query(conn, ext_database_query_id)?;
-    let columns_info = run_and_extract_columns(conn, ext_database_id, &query_string).await?;
+    let columns_info =
+        run_and_extract_columns(conn, ext_database_id, jwt_auth.claims.id, &query_string).await?;
     Ok(Json(DataResponse {
         data: Value::Array(columns_info),
     }))
@@ -1049,7 +1888,7 @@ pub async fn query_exact_whitelist_run(
 #[handler]
 pub async fn query_exact_whitelist_list(
     pool: poem::web::Data<&DbPool>,
-    _: crate::auth::middleware::JwtAuth,
+    jwt_auth: crate::auth::middleware::JwtAuth,
     Path(ext_database_query_id): Path<i64>,
     Query(pagination): Query<Pagination>,
 ) -> poem::Result<impl IntoResponse> {
@@ -1064,7 +1903,15 @@ pub async fn query_exact_whitelist_list(
     })?;
 
     let (ext_database_id, query_string) = get_whitelist_query(conn, ext_database_query_id)?;
-    let response =
-        query_with_pagination(conn, ext_database_id, &query_string, start, length).await?;
+    let response = query_with_pagination(
+        conn,
+        &jwt_auth.claims,
+        ext_database_id,
+        &query_string,
+        start,
+        length,
+        pagination.cursor.as_deref(),
+    )
+    .await?;
     Ok(Json(response))
 }
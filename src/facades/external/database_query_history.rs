@@ -0,0 +1,442 @@
+use crate::auth::model::Claims;
+use crate::db::DbPool;
+use crate::facades::external::database_query::run_and_extract_columns;
+use crate::models::common::{DataResponse, PaginatedResponse};
+use crate::models::external::database::{
+    EntryQueryManualHistory, EntryQueryManualShare, QueryManual, QueryManualShare,
+};
+use crate::schema::{tbl_query_manual, tbl_query_manual_share};
+use crate::utils::common::{self, like_pattern, validate_id, validation_error_response};
+use chrono::Utc;
+use diesel::pg::Pg;
+use diesel::prelude::*;
+use poem::IntoResponse;
+use poem::web::Query;
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Json, Path},
+};
+use serde::Deserialize;
+use serde_json::json;
+use validator::Validate;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HistoryPagination {
+    pub start: Option<i64>,
+    pub length: Option<i64>,
+    pub search: Option<String>,
+    pub sort: Option<String>,
+    pub dir: Option<String>,
+    pub favourite_flag: Option<i16>,
+}
+
+fn visible_history_query<'a>(
+    claims: &Claims,
+    ext_database_id: i64,
+    pagination: &HistoryPagination,
+) -> tbl_query_manual::BoxedQuery<'a, Pg> {
+    let shared_ids =
+        tbl_query_manual_share::table
+            .filter(tbl_query_manual_share::is_del.eq(0))
+            .filter(tbl_query_manual_share::user_id.eq(claims.id).or(
+                tbl_query_manual_share::mt_role_id.eq_any(claims.role.clone().unwrap_or_default()),
+            ))
+            .select(tbl_query_manual_share::query_manual_id);
+
+    let mut query = tbl_query_manual::table
+        .filter(tbl_query_manual::ext_database_id.eq(ext_database_id))
+        .filter(
+            tbl_query_manual::created_by
+                .eq(claims.id)
+                .or(tbl_query_manual::id.eq_any(shared_ids)),
+        )
+        .into_boxed();
+
+    if let Some(ref term) = pagination.search {
+        let pattern = like_pattern(term);
+        query = query.filter(
+            tbl_query_manual::query
+                .ilike(pattern.clone())
+                .or(tbl_query_manual::title.ilike(pattern.clone()))
+                .or(tbl_query_manual::tags.ilike(pattern)),
+        );
+    }
+
+    if let Some(favourite_flag) = pagination.favourite_flag {
+        query = query.filter(tbl_query_manual::is_favourite.eq(favourite_flag));
+    }
+
+    query
+}
+
+pub fn get_visible_query(
+    conn: &mut PgConnection,
+    claims: &Claims,
+    query_manual_id: i64,
+) -> poem::Result<QueryManual> {
+    let query_manual = tbl_query_manual::table
+        .filter(tbl_query_manual::id.eq(query_manual_id))
+        .first::<QueryManual>(conn)
+        .map_err(|_| common::error_message(StatusCode::NOT_FOUND, "information.notFound"))?;
+
+    if query_manual.created_by == claims.id {
+        return Ok(query_manual);
+    }
+
+    let shared: i64 =
+        tbl_query_manual_share::table
+            .filter(tbl_query_manual_share::query_manual_id.eq(query_manual_id))
+            .filter(tbl_query_manual_share::is_del.eq(0))
+            .filter(tbl_query_manual_share::user_id.eq(claims.id).or(
+                tbl_query_manual_share::mt_role_id.eq_any(claims.role.clone().unwrap_or_default()),
+            ))
+            .count()
+            .get_result(conn)
+            .map_err(|e| {
+                eprintln!("Counting error: {}", e);
+                common::error_message(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "information.internalServerError",
+                )
+            })?;
+
+    if shared > 0 {
+        Ok(query_manual)
+    } else {
+        Err(common::error_message(
+            StatusCode::NOT_FOUND,
+            "information.notFound",
+        ))
+    }
+}
+
+pub fn check_owner(
+    conn: &mut PgConnection,
+    claims: &Claims,
+    query_manual_id: i64,
+) -> poem::Result<()> {
+    tbl_query_manual::table
+        .filter(tbl_query_manual::id.eq(query_manual_id))
+        .filter(tbl_query_manual::created_by.eq(claims.id))
+        .select(tbl_query_manual::id)
+        .first::<i64>(conn)
+        .map(|_| ())
+        .map_err(|_| common::error_message(StatusCode::NOT_FOUND, "information.notFound"))
+}
+
+#[handler]
+pub fn history_list(
+    pool: poem::web::Data<&DbPool>,
+    jwt_auth: crate::auth::middleware::JwtAuth,
+    Path(ext_database_id): Path<i64>,
+    Query(pagination): Query<HistoryPagination>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(ext_database_id)?;
+
+    let start = pagination.start.unwrap_or(0);
+    let length = pagination.length.unwrap_or(10).min(100);
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    let total: i64 = match visible_history_query(&jwt_auth.claims, ext_database_id, &pagination)
+        .count()
+        .get_result(conn)
+    {
+        Ok(count) => count,
+        Err(e) => {
+            eprintln!("Counting error: {}", e);
+            return Err(common::error_message(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "information.internalServerError",
+            ));
+        }
+    };
+
+    if total > 0 {
+        let mut query = visible_history_query(&jwt_auth.claims, ext_database_id, &pagination);
+
+        match (pagination.sort.as_deref(), pagination.dir.as_deref()) {
+            (Some("title"), Some("desc")) => query = query.order(tbl_query_manual::title.desc()),
+            (Some("title"), _) => query = query.order(tbl_query_manual::title.asc()),
+            (Some("createdDate"), Some("asc")) => {
+                query = query.order(tbl_query_manual::dt_created.asc())
+            }
+            _ => query = query.order(tbl_query_manual::dt_created.desc()),
+        }
+
+        let data = query
+            .offset(start)
+            .limit(length)
+            .load::<QueryManual>(conn)
+            .map_err(|e| {
+                eprintln!("Loading error: {}", e);
+                common::error_message(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "information.internalServerError",
+                )
+            })?;
+        Ok(Json(PaginatedResponse { total, data }))
+    } else {
+        Ok(Json(PaginatedResponse {
+            total: 0,
+            data: vec![],
+        }))
+    }
+}
+
+#[handler]
+pub fn history_update(
+    pool: poem::web::Data<&DbPool>,
+    jwt_auth: crate::auth::middleware::JwtAuth,
+    Path(query_manual_id): Path<i64>,
+    Json(mut entry_query_manual_history): Json<EntryQueryManualHistory>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(query_manual_id)?;
+
+    if let Err(e) = entry_query_manual_history.validate() {
+        return Err(validation_error_response(e));
+    }
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    entry_query_manual_history.version += 1;
+
+    let updated = diesel::update(
+        tbl_query_manual::table
+            .filter(tbl_query_manual::id.eq(query_manual_id))
+            .filter(tbl_query_manual::created_by.eq(jwt_auth.claims.id))
+            .filter(tbl_query_manual::version.eq(entry_query_manual_history.version - 1)),
+    )
+    .set((
+        &entry_query_manual_history,
+        tbl_query_manual::updated_by.eq(Some(jwt_auth.claims.id)),
+        tbl_query_manual::dt_updated.eq(Some(Utc::now().naive_utc())),
+    ))
+    .get_result::<QueryManual>(conn);
+
+    let updated = match updated {
+        Ok(updated) => updated,
+        // Baris milik user yang masih ada berarti versinya sudah berubah
+        Err(diesel::result::Error::NotFound) => {
+            check_owner(conn, &jwt_auth.claims, query_manual_id)?;
+            return Err(common::error_message(
+                StatusCode::CONFLICT,
+                "information.versionConflict",
+            ));
+        }
+        Err(e) => {
+            eprintln!("Updating error: {}", e);
+            return Err(common::error_message(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "information.internalServerError",
+            ));
+        }
+    };
+
+    Ok(Json(DataResponse { data: updated }))
+}
+
+#[handler]
+pub fn history_favourite(
+    pool: poem::web::Data<&DbPool>,
+    jwt_auth: crate::auth::middleware::JwtAuth,
+    Path((query_manual_id, favourite_flag)): Path<(i64, i16)>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(query_manual_id)?;
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    let updated = diesel::update(
+        tbl_query_manual::table
+            .filter(tbl_query_manual::id.eq(query_manual_id))
+            .filter(tbl_query_manual::created_by.eq(jwt_auth.claims.id)),
+    )
+    .set((
+        tbl_query_manual::is_favourite.eq(if favourite_flag == 1 { 1 } else { 0 }),
+        tbl_query_manual::updated_by.eq(Some(jwt_auth.claims.id)),
+        tbl_query_manual::dt_updated.eq(Some(Utc::now().naive_utc())),
+    ))
+    .get_result::<QueryManual>(conn)
+    .map_err(|_| common::error_message(StatusCode::NOT_FOUND, "information.notFound"))?;
+
+    Ok(Json(DataResponse { data: updated }))
+}
+
+#[handler]
+pub fn share_list(
+    pool: poem::web::Data<&DbPool>,
+    jwt_auth: crate::auth::middleware::JwtAuth,
+    Path(query_manual_id): Path<i64>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(query_manual_id)?;
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    check_owner(conn, &jwt_auth.claims, query_manual_id)?;
+
+    let data = tbl_query_manual_share::table
+        .filter(tbl_query_manual_share::query_manual_id.eq(query_manual_id))
+        .filter(tbl_query_manual_share::is_del.eq(0))
+        .order(tbl_query_manual_share::dt_created.asc())
+        .load::<QueryManualShare>(conn)
+        .map_err(|e| {
+            eprintln!("Loading error: {}", e);
+            common::error_message(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "information.internalServerError",
+            )
+        })?;
+
+    Ok(Json(DataResponse { data }))
+}
+
+#[handler]
+pub fn share_update(
+    pool: poem::web::Data<&DbPool>,
+    jwt_auth: crate::auth::middleware::JwtAuth,
+    Path(query_manual_id): Path<i64>,
+    Json(entry_query_manual_share): Json<EntryQueryManualShare>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(query_manual_id)?;
+    for user_id in &entry_query_manual_share.user_id_list {
+        validate_id(*user_id)?;
+    }
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    check_owner(conn, &jwt_auth.claims, query_manual_id)?;
+
+    let now = Utc::now().naive_utc();
+    let query_manual_share: Vec<QueryManualShare> = entry_query_manual_share
+        .user_id_list
+        .iter()
+        .map(|user_id| (Some(*user_id), None))
+        .chain(
+            entry_query_manual_share
+                .mt_role_id_list
+                .iter()
+                .map(|mt_role_id| (None, Some(*mt_role_id))),
+        )
+        .map(|(user_id, mt_role_id)| QueryManualShare {
+            id: common::generate_id(),
+            query_manual_id,
+            user_id,
+            mt_role_id,
+            is_del: 0,
+            created_by: jwt_auth.claims.id,
+            dt_created: now,
+            updated_by: None,
+            dt_updated: None,
+            version: 0,
+        })
+        .collect();
+
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        diesel::update(
+            tbl_query_manual_share::table
+                .filter(tbl_query_manual_share::query_manual_id.eq(query_manual_id))
+                .filter(tbl_query_manual_share::is_del.eq(0)),
+        )
+        .set((
+            tbl_query_manual_share::is_del.eq(1),
+            tbl_query_manual_share::updated_by.eq(Some(jwt_auth.claims.id)),
+            tbl_query_manual_share::dt_updated.eq(Some(now)),
+        ))
+        .execute(conn)?;
+
+        diesel::insert_into(tbl_query_manual_share::table)
+            .values(&query_manual_share)
+            .execute(conn)?;
+
+        Ok(())
+    })
+    .map_err(|e| {
+        eprintln!("Sharing error: {}", e);
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.internalServerError",
+        )
+    })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[handler]
+pub async fn history_rerun(
+    pool: poem::web::Data<&DbPool>,
+    jwt_auth: crate::auth::middleware::JwtAuth,
+    Path(query_manual_id): Path<i64>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(query_manual_id)?;
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    let query_manual = get_visible_query(conn, &jwt_auth.claims, query_manual_id)?;
+    let columns_info = run_and_extract_columns(
+        conn,
+        query_manual.ext_database_id,
+        jwt_auth.claims.id,
+        &query_manual.query,
+    )
+    .await?;
+
+    let inserted = diesel::insert_into(tbl_query_manual::table)
+        .values(QueryManual {
+            id: common::generate_id(),
+            ext_database_id: query_manual.ext_database_id,
+            query: query_manual.query,
+            title: None,
+            tags: None,
+            is_favourite: 0,
+            created_by: jwt_auth.claims.id,
+            dt_created: Utc::now().naive_utc(),
+            updated_by: None,
+            dt_updated: None,
+            version: 0,
+        })
+        .get_result::<QueryManual>(conn)
+        .map_err(|e| {
+            eprintln!("Inserting error: {}", e);
+            common::error_message(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "information.internalServerError",
+            )
+        })?;
+
+    Ok(Json(json!({
+        "id": inserted.id,
+        "header": columns_info
+    })))
+}
@@ -0,0 +1,423 @@
+use std::sync::LazyLock;
+use std::time::Instant;
+
+use diesel::prelude::*;
+use poem::web::{Json, Query};
+use poem::{IntoResponse, handler, http::StatusCode, web::Path};
+use regex::Regex;
+use serde_json::{Map, Value};
+
+use crate::database_pool::DatabasePool;
+use crate::db::DbPool;
+use crate::facades::external::database_execution::{ExecutionContext, record_execution};
+use crate::facades::external::database_mask::{MaskPolicy, load_mask_policy};
+use crate::facades::external::database_object::fetch_object_structure;
+use crate::facades::external::database_query::{get_external_pool, resolve_schema};
+use crate::models::common::DataResponse;
+use crate::models::external::database::SchemaParam;
+use crate::models::external::database_object::{ObjectColumn, ObjectStructure};
+use crate::models::external::database_row::{
+    EntryRowDelete, EntryRowInsert, EntryRowUpdate, RowEditResult,
+};
+use crate::schema::tbl_ext_database;
+use crate::utils::common::{self, validate_id};
+use crate::utils::database::{quote_identifier_mysql, quote_identifier_postgres};
+
+static TYPE_MODIFIER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\(\s*-?\d+(\s*,\s*-?\d+)?\s*\)").unwrap());
+
+enum RowEdit {
+    Insert(Map<String, Value>),
+    Update(Map<String, Value>, Map<String, Value>, Map<String, Value>),
+    Delete(Map<String, Value>, Map<String, Value>),
+}
+
+// Semua nilai dikirim sebagai bind parameter teks, PostgreSQL meng-cast ke tipe kolom
+struct RowStatement {
+    is_mysql: bool,
+    binds: Vec<Option<String>>,
+}
+
+impl RowStatement {
+    fn quote(&self, name: &str) -> String {
+        if self.is_mysql {
+            quote_identifier_mysql(name)
+        } else {
+            quote_identifier_postgres(name)
+        }
+    }
+
+    fn bind(&mut self, column: &ObjectColumn, value: &Value) -> String {
+        self.binds.push(match value {
+            Value::Null => None,
+            Value::String(s) => Some(s.clone()),
+            Value::Bool(b) if self.is_mysql => Some((if *b { "1" } else { "0" }).to_string()),
+            other => Some(other.to_string()),
+        });
+
+        // Nilai biner dikirim sebagai hex seperti saat dibaca
+        match self.is_mysql {
+            true if column.data_type.eq_ignore_ascii_case("json") => {
+                String::from("CAST(? AS JSON)")
+            }
+            true if is_binary_column(column, true) => String::from("UNHEX(?)"),
+            true => String::from("?"),
+            false if is_binary_column(column, false) => {
+                format!("decode(${}, 'hex')", self.binds.len())
+            }
+            false => format!(
+                "CAST(${} AS {})",
+                self.binds.len(),
+                base_type(&column.data_type)
+            ),
+        }
+    }
+
+    // Perbandingan aman terhadap NULL, json dibandingkan sebagai jsonb karena tidak punya operator =
+    fn matches(&mut self, column: &ObjectColumn, value: &Value) -> String {
+        let name = self.quote(&column.nm);
+        let placeholder = self.bind(column, value);
+        if self.is_mysql {
+            format!("{} <=> {}", name, placeholder)
+        } else if column.data_type == "json" {
+            format!(
+                "{}::JSONB IS NOT DISTINCT FROM {}::JSONB",
+                name, placeholder
+            )
+        } else {
+            format!("{} IS NOT DISTINCT FROM {}", name, placeholder)
+        }
+    }
+}
+
+// Cast eksplisit ke varchar(n) atau numeric(p,s) memotong/membulatkan diam-diam,
+// jadi typmod dibuang agar kelebihan panjang tetap menjadi error seperti assignment biasa
+pub fn base_type(data_type: &str) -> String {
+    TYPE_MODIFIER.replace_all(data_type, "").into_owned()
+}
+
+fn is_binary_column(column: &ObjectColumn, is_mysql: bool) -> bool {
+    let data_type = column.data_type.to_lowercase();
+    if is_mysql {
+        data_type.starts_with("binary")
+            || data_type.starts_with("varbinary")
+            || data_type.ends_with("blob")
+    } else {
+        data_type == "bytea"
+    }
+}
+
+fn quote_table(structure: &ObjectStructure, is_mysql: bool) -> String {
+    if is_mysql {
+        format!(
+            "{}.{}",
+            quote_identifier_mysql(&structure.schema),
+            quote_identifier_mysql(&structure.nm)
+        )
+    } else {
+        format!(
+            "{}.{}",
+            quote_identifier_postgres(&structure.schema),
+            quote_identifier_postgres(&structure.nm)
+        )
+    }
+}
+
+fn invalid(key: &str) -> poem::Error {
+    common::error_message(StatusCode::BAD_REQUEST, key)
+}
+
+fn find_column<'a>(structure: &'a ObjectStructure, name: &str) -> poem::Result<&'a ObjectColumn> {
+    structure
+        .columns
+        .iter()
+        .find(|col| col.nm == name)
+        .ok_or_else(|| invalid("row.invalidColumn"))
+}
+
+// Baris diidentifikasi dengan seluruh kolom primary key, nilai original dicek agar perubahan orang lain tidak tertimpa
+fn where_clause(
+    statement: &mut RowStatement,
+    structure: &ObjectStructure,
+    key: &Map<String, Value>,
+    original: &Map<String, Value>,
+    mask: &MaskPolicy,
+) -> poem::Result<String> {
+    let key_columns = structure
+        .primary_key
+        .as_ref()
+        .map(|pk| pk.columns.clone())
+        .filter(|columns| !columns.is_empty())
+        .ok_or_else(|| invalid("row.primaryKeyRequired"))?;
+    if key.len() != key_columns.len() || key_columns.iter().any(|col| !key.contains_key(col)) {
+        return Err(invalid("row.invalidKey"));
+    }
+
+    let mut conditions = Vec::new();
+    for name in &key_columns {
+        let column = find_column(structure, name)?;
+        conditions.push(statement.matches(column, &key[name]));
+    }
+
+    // Kolom yang dimasking hanya terlihat versi maskingnya sehingga tidak bisa dibandingkan
+    for (name, value) in original {
+        let column = find_column(structure, name)?;
+        if key.contains_key(name) || mask.is_masked(name) {
+            continue;
+        }
+        conditions.push(statement.matches(column, value));
+    }
+
+    Ok(conditions.join(" AND "))
+}
+
+fn build_row_statement(
+    edit: &RowEdit,
+    structure: &ObjectStructure,
+    is_mysql: bool,
+    mask: &MaskPolicy,
+) -> poem::Result<(String, Vec<Option<String>>)> {
+    let mut statement = RowStatement {
+        is_mysql,
+        binds: Vec::new(),
+    };
+    let table = quote_table(structure, is_mysql);
+
+    let query = match edit {
+        RowEdit::Insert(value) => {
+            if value.is_empty() {
+                return Err(invalid("row.valueRequired"));
+            }
+            let mut columns = Vec::new();
+            let mut placeholders = Vec::new();
+            for (name, value) in value {
+                let column = find_column(structure, name)?;
+                columns.push(statement.quote(&column.nm));
+                placeholders.push(statement.bind(column, value));
+            }
+            format!(
+                "INSERT INTO {} ({}) VALUES ({})",
+                table,
+                columns.join(", "),
+                placeholders.join(", ")
+            )
+        }
+        RowEdit::Update(key, original, value) => {
+            if value.is_empty() {
+                return Err(invalid("row.valueRequired"));
+            }
+            let mut assignments = Vec::new();
+            for (name, value) in value {
+                let column = find_column(structure, name)?;
+                let placeholder = statement.bind(column, value);
+                assignments.push(format!("{} = {}", statement.quote(&column.nm), placeholder));
+            }
+            let condition = where_clause(&mut statement, structure, key, original, mask)?;
+            format!(
+                "UPDATE {} SET {} WHERE {}",
+                table,
+                assignments.join(", "),
+                condition
+            )
+        }
+        RowEdit::Delete(key, original) => {
+            let condition = where_clause(&mut statement, structure, key, original, mask)?;
+            format!("DELETE FROM {} WHERE {}", table, condition)
+        }
+    };
+
+    Ok((query, statement.binds))
+}
+
+// Lebih dari satu baris terdampak berarti key tidak unik, transaksi dibatalkan
+async fn execute_row_statement(
+    ext_pool: &DatabasePool,
+    query: &str,
+    binds: &[Option<String>],
+) -> Result<u64, sqlx::Error> {
+    match ext_pool {
+        DatabasePool::Postgres(pg_pool) => {
+            let mut tx = pg_pool.begin().await?;
+            let mut statement = sqlx::query(query);
+            for value in binds {
+                statement = statement.bind(value);
+            }
+            let affected = statement.execute(&mut *tx).await?.rows_affected();
+            if affected <= 1 {
+                tx.commit().await?;
+            }
+            Ok(affected)
+        }
+        DatabasePool::MySql(my_pool) => {
+            let mut tx = my_pool.begin().await?;
+            let mut statement = sqlx::query(query);
+            for value in binds {
+                statement = statement.bind(value);
+            }
+            let affected = statement.execute(&mut *tx).await?.rows_affected();
+            if affected <= 1 {
+                tx.commit().await?;
+            }
+            Ok(affected)
+        }
+    }
+}
+
+pub fn ensure_not_locked(conn: &mut PgConnection, ext_database_id: i64) -> poem::Result<()> {
+    let is_lock = tbl_ext_database::table
+        .filter(tbl_ext_database::id.eq(ext_database_id))
+        .filter(tbl_ext_database::is_del.eq(0))
+        .select(tbl_ext_database::is_lock)
+        .first::<i16>(conn)
+        .map_err(|_| common::error_message(StatusCode::NOT_FOUND, "information.notFound"))?;
+
+    if is_lock == 1 {
+        return Err(common::error_message(
+            StatusCode::FORBIDDEN,
+            "row.databaseLocked",
+        ));
+    }
+    Ok(())
+}
+
+async fn edit_row(
+    pool: &DbPool,
+    roles: &[i16],
+    user_id: i64,
+    ext_database_id: i64,
+    table_name: &str,
+    schema: Option<String>,
+    edit: RowEdit,
+) -> poem::Result<RowEditResult> {
+    validate_id(ext_database_id)?;
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    ensure_not_locked(conn, ext_database_id)?;
+    let schema = resolve_schema(conn, ext_database_id, schema)?;
+    let (ext_pool, tunnel, _, _) = get_external_pool(conn, ext_database_id).await?;
+
+    let result = async {
+        let structure = fetch_object_structure(&ext_pool, schema.as_deref(), table_name).await?;
+        if structure.object_type != "table" {
+            return Err(invalid("row.invalidTable"));
+        }
+
+        let is_mysql = matches!(ext_pool, DatabasePool::MySql(_));
+        let mask = load_mask_policy(
+            conn,
+            ext_database_id,
+            roles,
+            &format!("SELECT * FROM {}", quote_table(&structure, is_mysql)),
+        )?;
+        let (query, binds) = build_row_statement(&edit, &structure, is_mysql, &mask)?;
+
+        let context = ExecutionContext::new(&ext_pool, ext_database_id, user_id, "row");
+        let dt_started = Instant::now();
+        let executed = execute_row_statement(&ext_pool, &query, &binds).await;
+        record_execution(
+            conn,
+            &context,
+            &query,
+            dt_started,
+            executed
+                .as_ref()
+                .map(|rows| Some(*rows))
+                .map_err(|e| e.to_string()),
+        );
+
+        let affected = executed.map_err(|e| {
+            eprintln!("Query error: {}", e);
+            match e.as_database_error() {
+                Some(db_error) => {
+                    common::error_message(StatusCode::BAD_REQUEST, db_error.message())
+                }
+                None => poem::Error::from_status(StatusCode::INTERNAL_SERVER_ERROR),
+            }
+        })?;
+
+        match affected {
+            0 => Err(common::error_message(
+                StatusCode::CONFLICT,
+                "row.changedOrDeleted",
+            )),
+            1 => Ok(RowEditResult { affected_row: 1 }),
+            _ => Err(invalid("row.keyNotUnique")),
+        }
+    }
+    .await;
+
+    if let Some(mut tunnel) = tunnel {
+        let _ = tunnel.kill().ok();
+    };
+
+    result
+}
+
+#[handler]
+pub async fn row_insert(
+    pool: poem::web::Data<&DbPool>,
+    jwt_auth: crate::auth::middleware::JwtAuth,
+    Path((ext_database_id, table_name)): Path<(i64, String)>,
+    Query(schema_param): Query<SchemaParam>,
+    Json(entry): Json<EntryRowInsert>,
+) -> poem::Result<impl IntoResponse> {
+    let data = edit_row(
+        &pool,
+        jwt_auth.claims.role.as_deref().unwrap_or_default(),
+        jwt_auth.claims.id,
+        ext_database_id,
+        &table_name,
+        schema_param.schema,
+        RowEdit::Insert(entry.value),
+    )
+    .await?;
+    Ok(Json(DataResponse { data }))
+}
+
+#[handler]
+pub async fn row_update(
+    pool: poem::web::Data<&DbPool>,
+    jwt_auth: crate::auth::middleware::JwtAuth,
+    Path((ext_database_id, table_name)): Path<(i64, String)>,
+    Query(schema_param): Query<SchemaParam>,
+    Json(entry): Json<EntryRowUpdate>,
+) -> poem::Result<impl IntoResponse> {
+    let data = edit_row(
+        &pool,
+        jwt_auth.claims.role.as_deref().unwrap_or_default(),
+        jwt_auth.claims.id,
+        ext_database_id,
+        &table_name,
+        schema_param.schema,
+        RowEdit::Update(entry.key, entry.original, entry.value),
+    )
+    .await?;
+    Ok(Json(DataResponse { data }))
+}
+
+#[handler]
+pub async fn row_delete(
+    pool: poem::web::Data<&DbPool>,
+    jwt_auth: crate::auth::middleware::JwtAuth,
+    Path((ext_database_id, table_name)): Path<(i64, String)>,
+    Query(schema_param): Query<SchemaParam>,
+    Json(entry): Json<EntryRowDelete>,
+) -> poem::Result<impl IntoResponse> {
+    let data = edit_row(
+        &pool,
+        jwt_auth.claims.role.as_deref().unwrap_or_default(),
+        jwt_auth.claims.id,
+        ext_database_id,
+        &table_name,
+        schema_param.schema,
+        RowEdit::Delete(entry.key, entry.original),
+    )
+    .await?;
+    Ok(Json(DataResponse { data }))
+}
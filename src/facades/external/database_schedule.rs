@@ -0,0 +1,907 @@
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use chrono::{NaiveDateTime, Utc};
+use cron::Schedule;
+use diesel::prelude::*;
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use poem::IntoResponse;
+use poem::web::Query;
+use poem::{
+    handler,
+    http::StatusCode,
+    web::{Json, Path},
+};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use sqlx::{Column, Row};
+use validator::Validate;
+
+use crate::database_pool::DatabasePool;
+use crate::db::DbPool;
+use crate::facades::external::database_execution::{ExecutionContext, record_execution};
+use crate::facades::external::database_mask::{load_mask_policy, user_roles};
+use crate::facades::external::database_query::{
+    column_headers, describe_columns_mysql, describe_columns_postgres, get_external_pool,
+    get_whitelist_query, is_mysql_database,
+};
+use crate::facades::external::server_command::{create_ssh_session, write_remote_file};
+use crate::models::common::{DataResponse, PaginatedResponse};
+use crate::models::external::database_schedule::{
+    EntryQuerySchedule, QuerySchedule, QueryScheduleRun,
+};
+use crate::schema::{
+    tbl_ext_database_query, tbl_ext_database_query_schedule, tbl_ext_database_query_schedule_run,
+};
+use crate::utils::common::{self, is_valid_directory_path, validate_id, validation_error_response};
+use crate::utils::database::{
+    apply_parameter, row_to_csv_line, rows_to_json_mysql, rows_to_json_postgres, rows_to_xlsx_bytes,
+};
+
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(30);
+
+// Batas isi file per run yang disimpan, file yang lebih besar tetap dikirim tapi tidak disimpan
+const SCHEDULE_FILE_MAX_BYTES: usize = 20 * 1024 * 1024;
+
+// Jumlah run terakhir per jadwal yang isi filenya dipertahankan
+const SCHEDULE_FILE_RETENTION: i64 = 10;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SchedulePagination {
+    pub start: Option<i64>,
+    pub length: Option<i64>,
+    pub external_database_query_id: Option<i64>,
+}
+
+struct RunResult {
+    row_count: i64,
+    file_name: String,
+    file_content: Option<Vec<u8>>,
+    message: Option<String>,
+}
+
+// Ekspresi 5 kolom (menit jam tanggal bulan hari) dilengkapi kolom detik agar cocok dengan format crate cron
+fn parse_cron(expression: &str) -> Option<Schedule> {
+    let expression = expression.trim();
+    let expression = if expression.split_whitespace().count() == 5 {
+        format!("0 {}", expression)
+    } else {
+        expression.to_string()
+    };
+    Schedule::from_str(&expression).ok()
+}
+
+fn next_run(expression: &str, after: NaiveDateTime) -> Option<NaiveDateTime> {
+    parse_cron(expression)?
+        .after(&after.and_utc())
+        .next()
+        .map(|dt| dt.naive_utc())
+}
+
+fn parse_parameter(parameter: Option<&str>) -> Option<Map<String, Value>> {
+    match parameter.filter(|p| !p.trim().is_empty()) {
+        Some(parameter) => serde_json::from_str(parameter).ok(),
+        None => Some(Map::new()),
+    }
+}
+
+fn email_list(email: Option<&str>) -> Vec<String> {
+    email
+        .unwrap_or_default()
+        .split([',', ';'])
+        .map(str::trim)
+        .filter(|address| !address.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn validate_schedule(entry: &EntryQuerySchedule) -> poem::Result<()> {
+    if parse_cron(&entry.cron_expression).is_none() {
+        return Err(common::error_message(
+            StatusCode::BAD_REQUEST,
+            "schedule.invalidCron",
+        ));
+    }
+    if parse_parameter(entry.parameter.as_deref()).is_none() {
+        return Err(common::error_message(
+            StatusCode::BAD_REQUEST,
+            "schedule.invalidParameter",
+        ));
+    }
+    if !matches!(entry.file_format.as_str(), "csv" | "xlsx") {
+        return Err(common::error_message(
+            StatusCode::BAD_REQUEST,
+            "schedule.invalidFileFormat",
+        ));
+    }
+    if let Some(ext_server_id) = entry.ext_server_id {
+        validate_id(ext_server_id)?;
+        if !entry
+            .directory
+            .as_deref()
+            .is_some_and(is_valid_directory_path)
+        {
+            return Err(common::error_message(
+                StatusCode::BAD_REQUEST,
+                "error.invalidDirectory",
+            ));
+        }
+    }
+    if email_list(entry.email.as_deref())
+        .iter()
+        .any(|address| address.parse::<Mailbox>().is_err())
+    {
+        return Err(common::error_message(
+            StatusCode::BAD_REQUEST,
+            "schedule.invalidEmail",
+        ));
+    }
+    Ok(())
+}
+
+fn get_schedule(conn: &mut PgConnection, schedule_id: i64) -> poem::Result<QuerySchedule> {
+    tbl_ext_database_query_schedule::table
+        .filter(tbl_ext_database_query_schedule::id.eq(schedule_id))
+        .filter(tbl_ext_database_query_schedule::is_del.eq(0))
+        .first::<QuerySchedule>(conn)
+        .map_err(|_| common::error_message(StatusCode::NOT_FOUND, "information.notFound"))
+}
+
+// Hasil kosong tetap punya header dari describe statement
+async fn fetch_schedule_rows(
+    ext_pool: &DatabasePool,
+    query: &str,
+) -> Result<(Vec<Value>, Vec<String>), sqlx::Error> {
+    match ext_pool {
+        DatabasePool::Postgres(pg_pool) => {
+            let rows = sqlx::query(query).fetch_all(pg_pool).await?;
+            let headers = match rows.first() {
+                Some(row) => row.columns().iter().map(|c| c.name().to_string()).collect(),
+                None => column_headers(&describe_columns_postgres(pg_pool, query).await),
+            };
+            Ok((rows_to_json_postgres(&rows), headers))
+        }
+        DatabasePool::MySql(my_pool) => {
+            let rows = sqlx::query(query).fetch_all(my_pool).await?;
+            let headers = match rows.first() {
+                Some(row) => row.columns().iter().map(|c| c.name().to_string()).collect(),
+                None => column_headers(&describe_columns_mysql(my_pool, query).await),
+            };
+            Ok((rows_to_json_mysql(&rows), headers))
+        }
+    }
+}
+
+fn rows_to_file(
+    file_format: &str,
+    rows: Vec<Value>,
+    headers: Vec<String>,
+) -> Result<Vec<u8>, String> {
+    match file_format {
+        "xlsx" => rows_to_xlsx_bytes(0, rows, headers)
+            .map_err(|_| String::from("schedule.fileGenerationFailed")),
+        _ => {
+            let mut csv = String::new();
+            if !headers.is_empty() {
+                csv.push_str(&headers.join(","));
+                csv.push('\n');
+            }
+            for row in rows.iter().filter_map(Value::as_object) {
+                csv.push_str(&row_to_csv_line(",", row, &headers));
+            }
+            Ok(csv.into_bytes())
+        }
+    }
+}
+
+async fn send_email(
+    recipients: &[String],
+    file_name: &str,
+    file_format: &str,
+    content: &[u8],
+) -> Result<(), String> {
+    let host = std::env::var("SMTP_HOST").map_err(|_| String::from("email.notConfigured"))?;
+    let from = std::env::var("SMTP_FROM")
+        .ok()
+        .and_then(|from| from.parse::<Mailbox>().ok())
+        .ok_or_else(|| String::from("email.notConfigured"))?;
+    let port = std::env::var("SMTP_PORT")
+        .ok()
+        .and_then(|port| port.parse().ok())
+        .unwrap_or(587);
+
+    let mut transport = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&host)
+        .map_err(|e| e.to_string())?
+        .port(port);
+    if let (Ok(username), Ok(password)) = (
+        std::env::var("SMTP_USERNAME"),
+        std::env::var("SMTP_PASSWORD"),
+    ) {
+        transport = transport.credentials(Credentials::new(username, password));
+    }
+
+    let mime = match file_format {
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        _ => "text/csv",
+    };
+    let mut builder = Message::builder().from(from).subject(file_name);
+    for recipient in recipients {
+        builder = builder.to(recipient.parse::<Mailbox>().map_err(|e| e.to_string())?);
+    }
+    let message = builder
+        .multipart(
+            MultiPart::mixed()
+                .singlepart(SinglePart::plain(format!(
+                    "Scheduled query result: {}",
+                    file_name
+                )))
+                .singlepart(Attachment::new(file_name.to_string()).body(
+                    content.to_vec(),
+                    ContentType::parse(mime).map_err(|e| e.to_string())?,
+                )),
+        )
+        .map_err(|e| e.to_string())?;
+
+    transport
+        .build()
+        .send(message)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+async fn execute_schedule(
+    conn: &mut PgConnection,
+    schedule: &QuerySchedule,
+) -> Result<RunResult, String> {
+    let (ext_database_id, query) = get_whitelist_query(conn, schedule.ext_database_query_id)
+        .map_err(|_| String::from("information.notFound"))?;
+    let parameter = parse_parameter(schedule.parameter.as_deref())
+        .ok_or_else(|| String::from("schedule.invalidParameter"))?;
+
+    // Placeholder diganti sebelum query diparse untuk masking
+    let is_mysql = is_mysql_database(conn, ext_database_id)
+        .map_err(|_| String::from("information.notFound"))?;
+    let query = apply_parameter(&query, &parameter, is_mysql)?;
+
+    // Jadwal berjalan tanpa sesi login, jadi masking mengikuti role pembuat jadwal
+    let roles = user_roles(conn, schedule.created_by);
+    let mask = load_mask_policy(conn, ext_database_id, &roles, &query)
+        .map_err(|_| String::from("information.internalServerError"))?;
+
+    let (ext_pool, tunnel, _, _) = get_external_pool(conn, ext_database_id)
+        .await
+        .map_err(|_| String::from("information.connectionFailed"))?;
+    let context =
+        ExecutionContext::new(&ext_pool, ext_database_id, schedule.created_by, "schedule");
+    let dt_started = Instant::now();
+    let result = fetch_schedule_rows(&ext_pool, &query).await;
+    record_execution(
+        conn,
+        &context,
+        &query,
+        dt_started,
+        result
+            .as_ref()
+            .map(|(rows, _)| Some(rows.len() as u64))
+            .map_err(|e| e.to_string()),
+    );
+
+    if let Some(mut tunnel) = tunnel {
+        let _ = tunnel.kill().ok();
+    };
+
+    let (mut rows, headers) = result.map_err(|e| format!("Query error: {}", e))?;
+    mask.mask_rows(&mut rows);
+    let row_count = rows.len() as i64;
+    let file_name = format!(
+        "schedule_{}_{}.{}",
+        schedule.id,
+        Utc::now().format("%Y%m%d%H%M%S"),
+        schedule.file_format
+    );
+    let file_content = rows_to_file(&schedule.file_format, rows, headers)?;
+
+    // Kegagalan pengiriman tidak menghapus hasil, cukup dicatat pada pesan run
+    let mut delivery_errors = Vec::new();
+    if let (Some(ext_server_id), Some(directory)) = (schedule.ext_server_id, &schedule.directory) {
+        let delivered =
+            create_ssh_session(conn, ext_server_id).and_then(|(session, server_type)| {
+                let directory = directory.trim_end_matches('/');
+                let directory = if server_type == 1 && !directory.starts_with('/') {
+                    format!("/{}", directory)
+                } else {
+                    directory.to_string()
+                };
+                write_remote_file(
+                    &session,
+                    server_type,
+                    &format!("{}/{}", directory, file_name),
+                    &file_content,
+                )
+            });
+        if delivered.is_err() {
+            delivery_errors.push(String::from("ssh.uploadFailed"));
+        }
+    }
+
+    let recipients = email_list(schedule.email.as_deref());
+    if !recipients.is_empty()
+        && let Err(e) = send_email(
+            &recipients,
+            &file_name,
+            &schedule.file_format,
+            &file_content,
+        )
+        .await
+    {
+        eprintln!("Email error: {}", e);
+        delivery_errors.push(e);
+    }
+
+    Ok(RunResult {
+        row_count,
+        file_name,
+        file_content: (file_content.len() <= SCHEDULE_FILE_MAX_BYTES).then_some(file_content),
+        message: (!delivery_errors.is_empty()).then(|| delivery_errors.join("; ")),
+    })
+}
+
+fn start_run(
+    conn: &mut PgConnection,
+    schedule: &QuerySchedule,
+    is_manual: i16,
+    user_id: i64,
+) -> QueryResult<QueryScheduleRun> {
+    let now = Utc::now().naive_utc();
+    let run = QueryScheduleRun {
+        id: common::generate_id(),
+        ext_database_query_schedule_id: schedule.id,
+        is_manual,
+        status: String::from("RUNNING"),
+        dt_started: now,
+        dt_finished: None,
+        duration: None,
+        row_count: None,
+        file_name: None,
+        message: None,
+        created_by: user_id,
+        dt_created: now,
+        updated_by: None,
+        dt_updated: None,
+        version: 0,
+    };
+
+    diesel::insert_into(tbl_ext_database_query_schedule_run::table)
+        .values(&run)
+        .execute(conn)?;
+    Ok(run)
+}
+
+async fn finish_run(pool: DbPool, schedule: QuerySchedule, run_id: i64) {
+    let started = Instant::now();
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(e) => {
+            eprintln!("Schedule connection error: {}", e);
+            return;
+        }
+    };
+
+    let result = execute_schedule(&mut conn, &schedule).await;
+    let duration = started.elapsed().as_millis() as i64;
+    let now = Utc::now().naive_utc();
+    let run = tbl_ext_database_query_schedule_run::table
+        .filter(tbl_ext_database_query_schedule_run::id.eq(run_id));
+
+    let updated = match result {
+        Ok(result) => diesel::update(run)
+            .set((
+                tbl_ext_database_query_schedule_run::status.eq(if result.message.is_none() {
+                    "SUCCESS"
+                } else {
+                    "FAILED"
+                }),
+                tbl_ext_database_query_schedule_run::dt_finished.eq(Some(now)),
+                tbl_ext_database_query_schedule_run::duration.eq(Some(duration)),
+                tbl_ext_database_query_schedule_run::row_count.eq(Some(result.row_count)),
+                tbl_ext_database_query_schedule_run::file_name.eq(Some(result.file_name)),
+                tbl_ext_database_query_schedule_run::file_content.eq(result.file_content),
+                tbl_ext_database_query_schedule_run::message.eq(result.message),
+                tbl_ext_database_query_schedule_run::dt_updated.eq(Some(now)),
+            ))
+            .execute(&mut conn),
+        Err(message) => diesel::update(run)
+            .set((
+                tbl_ext_database_query_schedule_run::status.eq("FAILED"),
+                tbl_ext_database_query_schedule_run::dt_finished.eq(Some(now)),
+                tbl_ext_database_query_schedule_run::duration.eq(Some(duration)),
+                tbl_ext_database_query_schedule_run::message.eq(Some(message)),
+                tbl_ext_database_query_schedule_run::dt_updated.eq(Some(now)),
+            ))
+            .execute(&mut conn),
+    };
+
+    if let Err(e) = updated {
+        eprintln!("Updating error: {}", e);
+    }
+
+    if let Err(e) = prune_run_files(&mut conn, schedule.id) {
+        eprintln!("Updating error: {}", e);
+    }
+}
+
+// Isi file run lama dikosongkan, riwayat run tetap disimpan
+fn prune_run_files(conn: &mut PgConnection, schedule_id: i64) -> QueryResult<usize> {
+    let retained = tbl_ext_database_query_schedule_run::table
+        .filter(tbl_ext_database_query_schedule_run::ext_database_query_schedule_id.eq(schedule_id))
+        .order(tbl_ext_database_query_schedule_run::dt_started.desc())
+        .limit(SCHEDULE_FILE_RETENTION)
+        .select(tbl_ext_database_query_schedule_run::id)
+        .load::<i64>(conn)?;
+
+    diesel::update(
+        tbl_ext_database_query_schedule_run::table
+            .filter(
+                tbl_ext_database_query_schedule_run::ext_database_query_schedule_id.eq(schedule_id),
+            )
+            .filter(tbl_ext_database_query_schedule_run::file_content.is_not_null())
+            .filter(tbl_ext_database_query_schedule_run::id.ne_all(retained)),
+    )
+    .set(tbl_ext_database_query_schedule_run::file_content.eq(None::<Vec<u8>>))
+    .execute(conn)
+}
+
+// Jadwal yang jatuh tempo diklaim dengan memajukan dt_next_run agar tidak dijalankan dua kali
+async fn run_due_schedules(pool: &DbPool) -> QueryResult<()> {
+    let mut conn = pool.get().map_err(|e| {
+        diesel::result::Error::QueryBuilderError(format!("Connection error: {}", e).into())
+    })?;
+    let now = Utc::now().naive_utc();
+
+    let due = tbl_ext_database_query_schedule::table
+        .filter(tbl_ext_database_query_schedule::is_del.eq(0))
+        .filter(tbl_ext_database_query_schedule::is_active.eq(1))
+        .filter(tbl_ext_database_query_schedule::dt_next_run.le(now))
+        .load::<QuerySchedule>(&mut conn)?;
+
+    for schedule in due {
+        let claimed = diesel::update(
+            tbl_ext_database_query_schedule::table
+                .filter(tbl_ext_database_query_schedule::id.eq(schedule.id))
+                .filter(tbl_ext_database_query_schedule::dt_next_run.eq(schedule.dt_next_run)),
+        )
+        .set((
+            tbl_ext_database_query_schedule::dt_next_run
+                .eq(next_run(&schedule.cron_expression, now)),
+            tbl_ext_database_query_schedule::dt_last_run.eq(Some(now)),
+        ))
+        .execute(&mut conn)?;
+
+        if claimed == 1 {
+            let run = start_run(&mut conn, &schedule, 0, schedule.created_by)?;
+            tokio::spawn(finish_run(pool.clone(), schedule, run.id));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn spawn_scheduler(pool: DbPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SCHEDULER_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = run_due_schedules(&pool).await {
+                eprintln!("Scheduler error: {}", e);
+            }
+        }
+    });
+}
+
+#[handler]
+pub fn schedule_list(
+    pool: poem::web::Data<&DbPool>,
+    _: crate::auth::middleware::JwtAuth,
+    Query(pagination): Query<SchedulePagination>,
+) -> poem::Result<impl IntoResponse> {
+    let start = pagination.start.unwrap_or(0);
+    let length = pagination.length.unwrap_or(10).min(100);
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    let filtered = || {
+        let mut query = tbl_ext_database_query_schedule::table
+            .filter(tbl_ext_database_query_schedule::is_del.eq(0))
+            .into_boxed();
+        if let Some(ext_database_query_id) = pagination.external_database_query_id {
+            query = query.filter(
+                tbl_ext_database_query_schedule::ext_database_query_id.eq(ext_database_query_id),
+            );
+        }
+        query
+    };
+
+    let total: i64 = filtered().count().get_result(conn).map_err(|e| {
+        eprintln!("Counting error: {}", e);
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.internalServerError",
+        )
+    })?;
+
+    let data = if total > 0 {
+        filtered()
+            .order(tbl_ext_database_query_schedule::dt_created.desc())
+            .offset(start)
+            .limit(length)
+            .load::<QuerySchedule>(conn)
+            .map_err(|e| {
+                eprintln!("Loading error: {}", e);
+                common::error_message(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "information.internalServerError",
+                )
+            })?
+    } else {
+        vec![]
+    };
+
+    Ok(Json(PaginatedResponse { total, data }))
+}
+
+#[handler]
+pub fn schedule_get(
+    pool: poem::web::Data<&DbPool>,
+    _: crate::auth::middleware::JwtAuth,
+    Path(schedule_id): Path<i64>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(schedule_id)?;
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    Ok(Json(DataResponse {
+        data: get_schedule(conn, schedule_id)?,
+    }))
+}
+
+#[handler]
+pub fn schedule_add(
+    pool: poem::web::Data<&DbPool>,
+    jwt_auth: crate::auth::middleware::JwtAuth,
+    Json(entry_schedule): Json<EntryQuerySchedule>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(entry_schedule.ext_database_query_id)?;
+
+    if let Err(e) = entry_schedule.validate() {
+        return Err(validation_error_response(e));
+    }
+    validate_schedule(&entry_schedule)?;
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    tbl_ext_database_query::table
+        .filter(tbl_ext_database_query::id.eq(entry_schedule.ext_database_query_id))
+        .filter(tbl_ext_database_query::is_del.eq(0))
+        .select(tbl_ext_database_query::id)
+        .first::<i64>(conn)
+        .map_err(|_| common::error_message(StatusCode::NOT_FOUND, "information.notFound"))?;
+
+    let now = Utc::now().naive_utc();
+    let schedule = QuerySchedule {
+        id: common::generate_id(),
+        ext_database_query_id: entry_schedule.ext_database_query_id,
+        dt_next_run: next_run(&entry_schedule.cron_expression, now),
+        cron_expression: entry_schedule.cron_expression,
+        parameter: entry_schedule.parameter,
+        file_format: entry_schedule.file_format,
+        ext_server_id: entry_schedule.ext_server_id,
+        directory: entry_schedule.directory,
+        email: entry_schedule.email,
+        is_active: entry_schedule.is_active,
+        dt_last_run: None,
+        is_del: 0,
+        created_by: jwt_auth.claims.id,
+        dt_created: now,
+        updated_by: None,
+        dt_updated: None,
+        version: 0,
+    };
+
+    let inserted = diesel::insert_into(tbl_ext_database_query_schedule::table)
+        .values(&schedule)
+        .get_result::<QuerySchedule>(conn)
+        .map_err(|e| {
+            eprintln!("Inserting error: {}", e);
+            common::error_message(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "information.internalServerError",
+            )
+        })?;
+
+    Ok((StatusCode::CREATED, Json(DataResponse { data: inserted })))
+}
+
+#[handler]
+pub fn schedule_update(
+    pool: poem::web::Data<&DbPool>,
+    jwt_auth: crate::auth::middleware::JwtAuth,
+    Path(schedule_id): Path<i64>,
+    Json(mut entry_schedule): Json<EntryQuerySchedule>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(schedule_id)?;
+    validate_id(entry_schedule.ext_database_query_id)?;
+
+    if let Err(e) = entry_schedule.validate() {
+        return Err(validation_error_response(e));
+    }
+    validate_schedule(&entry_schedule)?;
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    tbl_ext_database_query::table
+        .filter(tbl_ext_database_query::id.eq(entry_schedule.ext_database_query_id))
+        .filter(tbl_ext_database_query::is_del.eq(0))
+        .select(tbl_ext_database_query::id)
+        .first::<i64>(conn)
+        .map_err(|_| common::error_message(StatusCode::NOT_FOUND, "information.notFound"))?;
+
+    entry_schedule.version += 1;
+    let now = Utc::now().naive_utc();
+
+    let updated = diesel::update(
+        tbl_ext_database_query_schedule::table
+            .filter(tbl_ext_database_query_schedule::id.eq(schedule_id))
+            .filter(tbl_ext_database_query_schedule::is_del.eq(0))
+            .filter(tbl_ext_database_query_schedule::version.eq(entry_schedule.version - 1)),
+    )
+    .set((
+        tbl_ext_database_query_schedule::dt_next_run
+            .eq(next_run(&entry_schedule.cron_expression, now)),
+        &entry_schedule,
+        tbl_ext_database_query_schedule::updated_by.eq(Some(jwt_auth.claims.id)),
+        tbl_ext_database_query_schedule::dt_updated.eq(Some(now)),
+    ))
+    .get_result::<QuerySchedule>(conn)
+    .map_err(|e| {
+        eprintln!("Updating error: {}", e);
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.internalServerError",
+        )
+    })?;
+
+    Ok(Json(DataResponse { data: updated }))
+}
+
+#[handler]
+pub fn schedule_delete(
+    pool: poem::web::Data<&DbPool>,
+    jwt_auth: crate::auth::middleware::JwtAuth,
+    Path(schedule_id): Path<i64>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(schedule_id)?;
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    diesel::update(
+        tbl_ext_database_query_schedule::table
+            .filter(tbl_ext_database_query_schedule::id.eq(schedule_id)),
+    )
+    .set((
+        tbl_ext_database_query_schedule::is_del.eq(1),
+        tbl_ext_database_query_schedule::updated_by.eq(Some(jwt_auth.claims.id)),
+        tbl_ext_database_query_schedule::dt_updated.eq(Some(Utc::now().naive_utc())),
+    ))
+    .get_result::<QuerySchedule>(conn)
+    .map_err(|e| {
+        eprintln!("Soft Deleting error: {}", e);
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.internalServerError",
+        )
+    })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[handler]
+pub fn schedule_active(
+    pool: poem::web::Data<&DbPool>,
+    jwt_auth: crate::auth::middleware::JwtAuth,
+    Path((schedule_id, active_flag)): Path<(i64, i16)>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(schedule_id)?;
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    let schedule = get_schedule(conn, schedule_id)?;
+    let now = Utc::now().naive_utc();
+
+    // Saat dilanjutkan, jadwal berikutnya dihitung dari sekarang agar run yang terlewat tidak menumpuk
+    let updated = diesel::update(
+        tbl_ext_database_query_schedule::table
+            .filter(tbl_ext_database_query_schedule::id.eq(schedule_id)),
+    )
+    .set((
+        tbl_ext_database_query_schedule::is_active.eq(if active_flag == 1 { 1 } else { 0 }),
+        tbl_ext_database_query_schedule::dt_next_run.eq(next_run(&schedule.cron_expression, now)),
+        tbl_ext_database_query_schedule::updated_by.eq(Some(jwt_auth.claims.id)),
+        tbl_ext_database_query_schedule::dt_updated.eq(Some(now)),
+    ))
+    .get_result::<QuerySchedule>(conn)
+    .map_err(|_| common::error_message(StatusCode::NOT_FOUND, "information.notFound"))?;
+
+    Ok(Json(DataResponse { data: updated }))
+}
+
+#[handler]
+pub fn schedule_run(
+    pool: poem::web::Data<&DbPool>,
+    jwt_auth: crate::auth::middleware::JwtAuth,
+    Path(schedule_id): Path<i64>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(schedule_id)?;
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    let schedule = get_schedule(conn, schedule_id)?;
+    let run = start_run(conn, &schedule, 1, jwt_auth.claims.id).map_err(|e| {
+        eprintln!("Inserting error: {}", e);
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.internalServerError",
+        )
+    })?;
+
+    tokio::spawn(finish_run(pool.0.clone(), schedule, run.id));
+
+    Ok((StatusCode::ACCEPTED, Json(DataResponse { data: run })))
+}
+
+#[handler]
+pub fn schedule_run_list(
+    pool: poem::web::Data<&DbPool>,
+    _: crate::auth::middleware::JwtAuth,
+    Path(schedule_id): Path<i64>,
+    Query(pagination): Query<SchedulePagination>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(schedule_id)?;
+
+    let start = pagination.start.unwrap_or(0);
+    let length = pagination.length.unwrap_or(10).min(100);
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    let total: i64 = tbl_ext_database_query_schedule_run::table
+        .filter(tbl_ext_database_query_schedule_run::ext_database_query_schedule_id.eq(schedule_id))
+        .count()
+        .get_result(conn)
+        .map_err(|e| {
+            eprintln!("Counting error: {}", e);
+            common::error_message(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "information.internalServerError",
+            )
+        })?;
+
+    // Isi file tidak ikut dimuat pada daftar, unduh lewat endpoint file
+    let data = tbl_ext_database_query_schedule_run::table
+        .filter(tbl_ext_database_query_schedule_run::ext_database_query_schedule_id.eq(schedule_id))
+        .order(tbl_ext_database_query_schedule_run::dt_started.desc())
+        .offset(start)
+        .limit(length)
+        .select((
+            tbl_ext_database_query_schedule_run::id,
+            tbl_ext_database_query_schedule_run::ext_database_query_schedule_id,
+            tbl_ext_database_query_schedule_run::is_manual,
+            tbl_ext_database_query_schedule_run::status,
+            tbl_ext_database_query_schedule_run::dt_started,
+            tbl_ext_database_query_schedule_run::dt_finished,
+            tbl_ext_database_query_schedule_run::duration,
+            tbl_ext_database_query_schedule_run::row_count,
+            tbl_ext_database_query_schedule_run::file_name,
+            tbl_ext_database_query_schedule_run::message,
+            tbl_ext_database_query_schedule_run::created_by,
+            tbl_ext_database_query_schedule_run::dt_created,
+            tbl_ext_database_query_schedule_run::updated_by,
+            tbl_ext_database_query_schedule_run::dt_updated,
+            tbl_ext_database_query_schedule_run::version,
+        ))
+        .load::<QueryScheduleRun>(conn)
+        .map_err(|e| {
+            eprintln!("Loading error: {}", e);
+            common::error_message(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "information.internalServerError",
+            )
+        })?;
+
+    Ok(Json(PaginatedResponse { total, data }))
+}
+
+#[handler]
+pub fn schedule_run_file(
+    pool: poem::web::Data<&DbPool>,
+    _: crate::auth::middleware::JwtAuth,
+    Path(run_id): Path<i64>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(run_id)?;
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    let (file_name, file_content) = tbl_ext_database_query_schedule_run::table
+        .filter(tbl_ext_database_query_schedule_run::id.eq(run_id))
+        .select((
+            tbl_ext_database_query_schedule_run::file_name,
+            tbl_ext_database_query_schedule_run::file_content,
+        ))
+        .first::<(Option<String>, Option<Vec<u8>>)>(conn)
+        .ok()
+        .and_then(|(file_name, file_content)| file_name.zip(file_content))
+        .ok_or_else(|| common::error_message(StatusCode::NOT_FOUND, "information.notFound"))?;
+
+    let content_type = if file_name.ends_with(".xlsx") {
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+    } else {
+        "text/csv"
+    };
+
+    Ok(poem::Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", content_type)
+        .header(
+            "Content-Disposition",
+            format!("attachment; filename=\"{}\"", file_name),
+        )
+        .body(file_content))
+}
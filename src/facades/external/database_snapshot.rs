@@ -0,0 +1,259 @@
+use std::time::Instant;
+
+use chrono::{Duration, NaiveDateTime, Utc};
+use diesel::prelude::*;
+use poem::http::HeaderValue;
+use poem::web::Json;
+use poem::{IntoResponse, Response, handler, http::StatusCode, web::Path};
+use serde_json::{Map, Value};
+use sqlx::Row;
+use tokio_stream::{Stream, StreamExt};
+
+use crate::database_pool::DatabasePool;
+use crate::db::DbPool;
+use crate::facades::external::database_execution::{ExecutionContext, record_execution};
+use crate::facades::external::database_query_history::{check_owner, get_visible_query};
+use crate::models::common::DataResponse;
+use crate::models::external::database::QueryManualSnapshot;
+use crate::schema::tbl_query_manual_snapshot;
+use crate::utils::common::{self, validate_id};
+use crate::utils::database::{
+    extract_columns_info_mysql, extract_columns_info_postgres, row_to_json_mysql,
+    row_to_json_postgres,
+};
+
+const SNAPSHOT_MAX_BYTES: i64 = 10 * 1024 * 1024;
+const SNAPSHOT_TTL_MINUTES: i64 = 60;
+
+pub struct Snapshot {
+    pub rows: Vec<Value>,
+    pub headers: Vec<String>,
+    pub columns: Vec<Value>,
+    pub dt_captured: NaiveDateTime,
+}
+
+fn env_limit(name: &str, default: i64) -> i64 {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .filter(|value| *value > 0)
+        .unwrap_or(default)
+}
+
+// Baris dibaca bertahap dan berhenti begitu melewati batas ukuran, jadi hasil besar tidak pernah dimuat penuh
+async fn collect_rows<R, S>(
+    mut rows: S,
+    to_json: fn(&R) -> Map<String, Value>,
+    to_columns: fn(&[R]) -> Vec<Value>,
+    max_bytes: i64,
+) -> Result<Option<(Vec<Value>, Vec<String>, Vec<Value>, i64)>, sqlx::Error>
+where
+    R: Row,
+    S: Stream<Item = Result<R, sqlx::Error>> + Unpin,
+{
+    let mut data = Vec::new();
+    let mut headers = Vec::new();
+    let mut columns = Vec::new();
+    let mut byte_size = 0;
+
+    while let Some(row) = rows.next().await {
+        let row = row?;
+        if data.is_empty() {
+            columns = to_columns(std::slice::from_ref(&row));
+            headers = columns
+                .iter()
+                .filter_map(|column| column.get("name").and_then(Value::as_str))
+                .map(str::to_string)
+                .collect();
+        }
+
+        let value = Value::Object(to_json(&row));
+        byte_size += value.to_string().len() as i64;
+        if byte_size > max_bytes {
+            return Ok(None);
+        }
+        data.push(value);
+    }
+
+    Ok(Some((data, headers, columns, byte_size)))
+}
+
+pub async fn capture_snapshot(
+    conn: &mut PgConnection,
+    ext_pool: &DatabasePool,
+    context: &ExecutionContext,
+    query_manual_id: i64,
+    query: &str,
+    user_id: i64,
+) -> Result<NaiveDateTime, String> {
+    let max_bytes = env_limit("SNAPSHOT_MAX_BYTES", SNAPSHOT_MAX_BYTES);
+    let dt_started = Instant::now();
+    let collected = match ext_pool {
+        DatabasePool::Postgres(pg_pool) => {
+            collect_rows(
+                sqlx::query(query).fetch(pg_pool),
+                row_to_json_postgres,
+                extract_columns_info_postgres,
+                max_bytes,
+            )
+            .await
+        }
+        DatabasePool::MySql(my_pool) => {
+            collect_rows(
+                sqlx::query(query).fetch(my_pool),
+                row_to_json_mysql,
+                extract_columns_info_mysql,
+                max_bytes,
+            )
+            .await
+        }
+    }
+    .map_err(|e| format!("{}", e));
+    // Snapshot yang melebihi batas berhenti membaca lebih awal sehingga jumlah barisnya tidak diketahui
+    record_execution(
+        conn,
+        context,
+        query,
+        dt_started,
+        collected
+            .as_ref()
+            .map(|collected| collected.as_ref().map(|(rows, ..)| rows.len() as u64))
+            .map_err(String::clone),
+    );
+
+    let Some((rows, headers, columns, byte_size)) = collected? else {
+        return Err(String::from("snapshot.tooLarge"));
+    };
+
+    let now = Utc::now().naive_utc();
+    let snapshot = QueryManualSnapshot {
+        id: common::generate_id(),
+        query_manual_id,
+        header: Value::from(headers).to_string(),
+        column_info: Value::Array(columns).to_string(),
+        row_count: rows.len() as i64,
+        row_data: Value::Array(rows).to_string(),
+        byte_size,
+        dt_expired: now
+            + Duration::minutes(env_limit("SNAPSHOT_TTL_MINUTES", SNAPSHOT_TTL_MINUTES)),
+        created_by: user_id,
+        dt_created: now,
+        updated_by: None,
+        dt_updated: None,
+        version: 0,
+    };
+
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        // Snapshot kedaluwarsa ikut dibersihkan setiap kali ada snapshot baru
+        diesel::delete(
+            tbl_query_manual_snapshot::table.filter(
+                tbl_query_manual_snapshot::query_manual_id
+                    .eq(query_manual_id)
+                    .or(tbl_query_manual_snapshot::dt_expired.le(now)),
+            ),
+        )
+        .execute(conn)?;
+
+        diesel::insert_into(tbl_query_manual_snapshot::table)
+            .values(&snapshot)
+            .execute(conn)?;
+
+        Ok(())
+    })
+    .map_err(|e| {
+        eprintln!("Inserting error: {}", e);
+        String::from("information.internalServerError")
+    })?;
+
+    Ok(now)
+}
+
+pub fn get_snapshot(conn: &mut PgConnection, query_manual_id: i64) -> Option<Snapshot> {
+    let snapshot = tbl_query_manual_snapshot::table
+        .filter(tbl_query_manual_snapshot::query_manual_id.eq(query_manual_id))
+        .filter(tbl_query_manual_snapshot::dt_expired.gt(Utc::now().naive_utc()))
+        .order(tbl_query_manual_snapshot::dt_created.desc())
+        .first::<QueryManualSnapshot>(conn)
+        .ok()?;
+
+    Some(Snapshot {
+        rows: serde_json::from_str(&snapshot.row_data).ok()?,
+        headers: serde_json::from_str(&snapshot.header).ok()?,
+        columns: serde_json::from_str(&snapshot.column_info).ok()?,
+        dt_captured: snapshot.dt_created,
+    })
+}
+
+pub fn with_snapshot_header(
+    response: impl IntoResponse,
+    dt_captured: Option<NaiveDateTime>,
+) -> Response {
+    let mut response = response.into_response();
+    if let Some(dt_captured) = dt_captured
+        && let Ok(value) = HeaderValue::from_str(&dt_captured.and_utc().to_rfc3339())
+    {
+        response.headers_mut().insert("X-Snapshot-Date", value);
+    }
+    response
+}
+
+#[handler]
+pub fn snapshot_get(
+    pool: poem::web::Data<&DbPool>,
+    jwt_auth: crate::auth::middleware::JwtAuth,
+    Path(query_manual_id): Path<i64>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(query_manual_id)?;
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    get_visible_query(conn, &jwt_auth.claims, query_manual_id)?;
+
+    let snapshot = tbl_query_manual_snapshot::table
+        .filter(tbl_query_manual_snapshot::query_manual_id.eq(query_manual_id))
+        .filter(tbl_query_manual_snapshot::dt_expired.gt(Utc::now().naive_utc()))
+        .order(tbl_query_manual_snapshot::dt_created.desc())
+        .first::<QueryManualSnapshot>(conn)
+        .map_err(|_| common::error_message(StatusCode::NOT_FOUND, "information.notFound"))?;
+
+    Ok(Json(DataResponse { data: snapshot }))
+}
+
+#[handler]
+pub fn snapshot_delete(
+    pool: poem::web::Data<&DbPool>,
+    jwt_auth: crate::auth::middleware::JwtAuth,
+    Path(query_manual_id): Path<i64>,
+) -> poem::Result<impl IntoResponse> {
+    validate_id(query_manual_id)?;
+
+    let conn = &mut pool.get().map_err(|_| {
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.connectionFailed",
+        )
+    })?;
+
+    // Hanya pemilik query yang boleh menghapus snapshot
+    check_owner(conn, &jwt_auth.claims, query_manual_id)?;
+
+    diesel::delete(
+        tbl_query_manual_snapshot::table
+            .filter(tbl_query_manual_snapshot::query_manual_id.eq(query_manual_id)),
+    )
+    .execute(conn)
+    .map_err(|e| {
+        eprintln!("Deleting error: {}", e);
+        common::error_message(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "information.internalServerError",
+        )
+    })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
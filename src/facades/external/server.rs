@@ -2,6 +2,7 @@ use crate::models::common::{DataResponse, PaginatedResponse};
 use crate::models::external::server::{EntryExternalServer, ExternalServer};
 use crate::schema::tbl_ext_server;
 use crate::utils::common::{self, validate_id, validation_error_response};
+use crate::utils::crypto::seal_credential;
 use crate::{db::DbPool, models::common::Pagination};
 use chrono::Utc;
 use diesel::prelude::*;
@@ -14,6 +15,14 @@ use poem::{
 };
 use validator::Validate;
 
+// String kosong disimpan sebagai NULL, selain itu dienkripsi sebelum disimpan
+fn seal_optional_credential(value: Option<&str>) -> poem::Result<Option<String>> {
+    value
+        .filter(|value| !value.is_empty())
+        .map(seal_credential)
+        .transpose()
+}
+
 #[handler]
 pub fn list(
     pool: poem::web::Data<&DbPool>,
@@ -113,6 +122,9 @@ pub fn add(
         return Err(validation_error_response(e));
     }
 
+    let password = seal_optional_credential(entry_ext_server.password.as_deref())?;
+    let private_key = seal_optional_credential(entry_ext_server.private_key.as_deref())?;
+
     let ext_server = ExternalServer {
         id: common::generate_id(),
         cd: entry_ext_server.cd,
@@ -121,8 +133,8 @@ pub fn add(
         ip: entry_ext_server.ip,
         port: entry_ext_server.port,
         username: entry_ext_server.username,
-        password: entry_ext_server.password,
-        private_key: entry_ext_server.private_key,
+        password,
+        private_key,
         is_lock: 1,
         is_del: 0,
         created_by: jwt_auth.claims.id,
@@ -174,25 +186,55 @@ pub fn update(
     })?;
 
     entry_ext_server.version = entry_ext_server.version + 1;
+    let password = entry_ext_server
+        .password
+        .as_deref()
+        .map(|password| seal_optional_credential(Some(password)))
+        .transpose()?;
+    let private_key = entry_ext_server
+        .private_key
+        .as_deref()
+        .map(|private_key| seal_optional_credential(Some(private_key)))
+        .transpose()?;
 
-    let updated = diesel::update(
-        tbl_ext_server::table
-            .filter(tbl_ext_server::id.eq(ext_server_id))
-            .filter(tbl_ext_server::version.eq(&entry_ext_server.version - 1)),
-    )
-    .set((
-        &entry_ext_server,
-        tbl_ext_server::updated_by.eq(Some(jwt_auth.claims.id)),
-        tbl_ext_server::dt_updated.eq(Some(Utc::now().naive_utc())),
-    ))
-    .get_result::<ExternalServer>(conn)
-    .map_err(|e| {
-        eprintln!("Updating error: {}", e);
-        common::error_message(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            "information.internalServerError",
-        )
-    })?;
+    let updated = conn
+        .transaction::<_, diesel::result::Error, _>(|conn| {
+            let mut updated = diesel::update(
+                tbl_ext_server::table
+                    .filter(tbl_ext_server::id.eq(ext_server_id))
+                    .filter(tbl_ext_server::version.eq(&entry_ext_server.version - 1)),
+            )
+            .set((
+                &entry_ext_server,
+                tbl_ext_server::updated_by.eq(Some(jwt_auth.claims.id)),
+                tbl_ext_server::dt_updated.eq(Some(Utc::now().naive_utc())),
+            ))
+            .get_result::<ExternalServer>(conn)?;
+
+            if let Some(password) = password {
+                updated = diesel::update(
+                    tbl_ext_server::table.filter(tbl_ext_server::id.eq(ext_server_id)),
+                )
+                .set(tbl_ext_server::password.eq(password))
+                .get_result::<ExternalServer>(conn)?;
+            }
+            if let Some(private_key) = private_key {
+                updated = diesel::update(
+                    tbl_ext_server::table.filter(tbl_ext_server::id.eq(ext_server_id)),
+                )
+                .set(tbl_ext_server::private_key.eq(private_key))
+                .get_result::<ExternalServer>(conn)?;
+            }
+
+            Ok(updated)
+        })
+        .map_err(|e| {
+            eprintln!("Updating error: {}", e);
+            common::error_message(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "information.internalServerError",
+            )
+        })?;
 
     Ok(Json(DataResponse { data: updated }))
 }
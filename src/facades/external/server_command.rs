@@ -14,6 +14,7 @@ use crate::schema::tbl_ext_server;
 use crate::utils::common::{
     self, generate_copy_name, is_valid_directory_path, is_valid_filename, validate_id,
 };
+use crate::utils::crypto::reveal_credential;
 use base64::{Engine, engine::general_purpose};
 use chrono::Utc;
 use diesel::prelude::*;
@@ -54,7 +55,7 @@ struct FileData {
     pub directory: String,
 }
 
-fn create_ssh_session(
+pub fn create_ssh_session(
     conn: &mut PgConnection,
     ext_server_id: i64,
 ) -> Result<(Session, i16), poem::Error> {
@@ -78,6 +79,8 @@ fn create_ssh_session(
         ))
         .first::<(i16, String, i16, String, Option<String>, Option<String>)>(conn)
         .map_err(|_| common::error_message(StatusCode::NOT_FOUND, "information.notFound"))?;
+    let password = password.as_deref().map(reveal_credential).transpose()?;
+    let private_key = private_key.as_deref().map(reveal_credential).transpose()?;
 
     let tcp = TcpStream::connect(format!("{}:{}", ip, port))
         .map_err(|_| common::error_message(StatusCode::BAD_REQUEST, "ssh.connectionFailed"))?;
@@ -152,6 +155,8 @@ pub fn start_ssh_tunnel(
         ))
         .first::<(String, i16, String, Option<String>, Option<String>)>(conn)
         .map_err(|_| common::error_message(StatusCode::NOT_FOUND, "information.notFound"))?;
+    let password = password.as_deref().map(reveal_credential).transpose()?;
+    let private_key = private_key.as_deref().map(reveal_credential).transpose()?;
 
     let listener = std::net::TcpListener::bind("127.0.0.1:0").map_err(|e| {
         eprintln!("Failed to bind local port: {}", e);
@@ -253,6 +258,49 @@ fn run_ssh_command(session: &Session, command: &str) -> Result<String, poem::Err
     Ok(output)
 }
 
+pub fn write_remote_file(
+    session: &Session,
+    mt_server_type_id: i16,
+    path: &str,
+    content: &[u8],
+) -> Result<(), poem::Error> {
+    match mt_server_type_id {
+        1 => {
+            let mut remote_file = session
+                .scp_send(
+                    std::path::Path::new(path),
+                    0o644,
+                    content.len() as u64,
+                    None,
+                )
+                .map_err(|_| {
+                    common::error_message(StatusCode::INTERNAL_SERVER_ERROR, "ssh.uploadFailed")
+                })?;
+
+            remote_file.write_all(content).map_err(|_| {
+                common::error_message(StatusCode::INTERNAL_SERVER_ERROR, "ssh.writeFailed")
+            })?;
+        }
+        2 => {
+            let base64_str = general_purpose::STANDARD.encode(content);
+            let command = format!(
+                "$data = \"{}\"; [IO.File]::WriteAllBytes(\"{}\", [Convert]::FromBase64String($data))",
+                base64_str, path
+            );
+
+            run_ssh_command(session, &command)?;
+        }
+        _ => {
+            return Err(common::error_message(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "ssh.unknownServerType",
+            ));
+        }
+    };
+
+    Ok(())
+}
+
 pub fn run_ssh_stream_command(
     session: &Session,
     command: &str,
@@ -1226,46 +1274,12 @@ pub async fn upload_files(
             if exists {
                 new_name = generate_copy_name(&new_name);
             } else {
-                match mt_server_type_id {
-                    1 => {
-                        let mut remote_file = session
-                            .scp_send(
-                                std::path::Path::new(&format!("{}/{}", dir_path, new_name)),
-                                0o644,
-                                content.len() as u64,
-                                None,
-                            )
-                            .map_err(|_| {
-                                common::error_message(
-                                    StatusCode::INTERNAL_SERVER_ERROR,
-                                    "ssh.uploadFailed",
-                                )
-                            })?;
-
-                        remote_file.write_all(&content).map_err(|_| {
-                            common::error_message(
-                                StatusCode::INTERNAL_SERVER_ERROR,
-                                "ssh.writeFailed",
-                            )
-                        })?;
-                    }
-                    2 => {
-                        let base64_str = general_purpose::STANDARD.encode(content);
-                        let command = format!(
-                            "$data = \"{}\"; [IO.File]::WriteAllBytes(\"{}\", [Convert]::FromBase64String($data))",
-                            base64_str,
-                            &format!("{}/{}", dir_path, new_name)
-                        );
-
-                        run_ssh_command(&session, &command)?;
-                    }
-                    _ => {
-                        return Err(common::error_message(
-                            StatusCode::INTERNAL_SERVER_ERROR,
-                            "ssh.unknownServerType",
-                        ));
-                    }
-                };
+                write_remote_file(
+                    &session,
+                    mt_server_type_id,
+                    &format!("{}/{}", dir_path, new_name),
+                    &content,
+                )?;
                 break;
             }
         }
@@ -53,6 +53,7 @@ async fn main() -> Result<()> {
     let database_url = env::var("DATABASE_URL")
         .map_err(|_| anyhow::anyhow!("DATABASE_URL is not set in environment"))?;
     let pool = db::init_pool(&database_url)?;
+    facades::external::database_schedule::spawn_scheduler(pool.clone());
 
     let port: u16 = env::var("PORT")
         .unwrap_or_else(|_| "3000".to_string())
@@ -66,7 +67,7 @@ async fn main() -> Result<()> {
         // .allow_origin("http://localhost:5173")
         // .allow_methods([Method::GET, Method::POST])
         // .allow_headers(["Authorization", "Content-Type"])
-        .expose_headers(["Content-Disposition"]);
+        .expose_headers(["Content-Disposition", "X-Snapshot-Date"]);
 
     let app = Route::new()
         .at("", get(hello))
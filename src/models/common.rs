@@ -1,4 +1,16 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, Serializer};
+
+// Kredensial hanya dilaporkan statusnya, nilai aslinya tidak pernah dikirim ke klien
+pub fn credential_status<S: Serializer>(value: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(if value.is_empty() { "not set" } else { "set" })
+}
+
+pub fn optional_credential_status<S: Serializer>(
+    value: &Option<String>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    credential_status(value.as_deref().unwrap_or_default(), serializer)
+}
 
 #[derive(Deserialize)]
 pub struct Pagination {
@@ -7,6 +19,7 @@ pub struct Pagination {
     pub search: Option<String>,
     pub sort: Option<String>,
     pub dir: Option<String>,
+    pub cursor: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -15,16 +28,27 @@ pub struct PaginatedResponse<T> {
     pub data: Vec<T>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct EstimatedPaginatedResponse<T> {
+    pub total: i64,
+    #[serde(rename = "estimatedFlag")]
+    pub is_estimated: i16,
+    pub data: Vec<T>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct LoadedMoreResponse<T> {
     pub loaded: i64,
     pub data: Vec<T>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 #[serde(untagged)]
 pub enum PaginatedLoadedMoreResponse<T> {
     Paginated(PaginatedResponse<T>),
+    Estimated(EstimatedPaginatedResponse<T>),
     LoadedMore(LoadedMoreResponse<T>),
 }
 
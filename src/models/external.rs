@@ -1,3 +1,18 @@
 pub mod api;
+pub mod connection_test;
 pub mod database;
+pub mod database_chart;
+pub mod database_compare;
+pub mod database_diff;
+pub mod database_dump;
+pub mod database_execution;
+pub mod database_explain;
+pub mod database_format;
+pub mod database_import;
+pub mod database_mask;
+pub mod database_metadata;
+pub mod database_notify;
+pub mod database_object;
+pub mod database_row;
+pub mod database_schedule;
 pub mod server;
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+pub struct ConnectionTestParam {
+    // Id data tersimpan, dipakai untuk mengisi kredensial yang tidak dikirim ulang
+    pub id: Option<i64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionStep {
+    pub name: String,
+    pub status: String,
+    pub duration: u128,
+    pub message: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionTestResult {
+    #[serde(rename = "successFlag")]
+    pub is_success: i16,
+    pub server_version: Option<String>,
+    pub duration: u128,
+    #[serde(rename = "stepList")]
+    pub steps: Vec<ConnectionStep>,
+}
@@ -19,13 +19,19 @@ pub struct ExternalDatabase {
     pub ip: String,
     pub port: i16,
     pub username: String,
+    #[serde(
+        rename = "passwordStatus",
+        serialize_with = "crate::models::common::credential_status"
+    )]
     pub password: String,
     #[serde(rename = "databaseName")]
     pub db_name: String,
+    pub default_schema: Option<String>,
     // #[serde(rename = "databaseConnection")]
     // pub db_connection: String,
     #[serde(rename = "usePageFlag")]
     pub is_use_page: i16,
+    pub count_estimate_threshold: Option<i64>,
     #[serde(rename = "lockFlag")]
     pub is_lock: i16,
     #[serde(rename = "deletedFlag")]
@@ -62,16 +68,22 @@ pub struct EntryExternalDatabase {
     pub port: i16,
     #[validate(length(min = 1, message = "Username must be filled"))]
     pub username: String,
-    #[validate(length(min = 1, message = "Password must be filled"))]
-    pub password: String,
+    // Kosong atau tidak dikirim saat update berarti password lama dipertahankan,
+    // saat tambah data dicek di handler
+    #[diesel(skip_update)]
+    pub password: Option<String>,
     #[serde(rename = "databaseName")]
     #[validate(length(min = 1, message = "DB Name must be filled"))]
     pub db_name: String,
+    #[validate(length(max = 100, message = "Default schema must not exceed 100 characters"))]
+    pub default_schema: Option<String>,
     // #[serde(rename = "databaseConnection")]
     // #[validate(length(min = 1, message = "Database connection must be filled"))]
     // pub db_connection: String,
     #[serde(rename = "usePageFlag")]
     pub is_use_page: i16,
+    #[validate(range(min = 1, message = "Count estimate threshold must be positive"))]
+    pub count_estimate_threshold: Option<i64>,
     #[serde(rename = "lockFlag")]
     pub is_lock: i16,
     #[serde(default)]
@@ -107,6 +119,10 @@ pub struct QueryManual {
     #[serde(rename = "externalDatabaseId")]
     pub ext_database_id: i64,
     pub query: String,
+    pub title: Option<String>,
+    pub tags: Option<String>,
+    #[serde(rename = "favouriteFlag")]
+    pub is_favourite: i16,
     pub created_by: i64,
     #[serde(rename = "createdDate")]
     pub dt_created: NaiveDateTime,
@@ -120,4 +136,80 @@ pub struct QueryManual {
 #[serde(rename_all = "camelCase")]
 pub struct EntryQueryManual {
     pub query: String,
+    #[serde(default, rename = "snapshotFlag")]
+    pub is_snapshot: i16,
+}
+
+#[derive(Serialize, Deserialize, Validate, AsChangeset)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[diesel(table_name = crate::schema::tbl_query_manual)]
+#[diesel(treat_none_as_null = true)]
+pub struct EntryQueryManualHistory {
+    #[validate(length(max = 200, message = "Title must not exceed 200 characters"))]
+    pub title: Option<String>,
+    #[validate(length(max = 500, message = "Tags must not exceed 500 characters"))]
+    pub tags: Option<String>,
+    #[serde(default)]
+    pub version: i16,
+}
+
+#[derive(Insertable, Queryable, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[diesel(table_name = crate::schema::tbl_query_manual_share)]
+pub struct QueryManualShare {
+    pub id: i64,
+    #[serde(rename = "queryManualId")]
+    pub query_manual_id: i64,
+    pub user_id: Option<i64>,
+    #[serde(rename = "roleId")]
+    pub mt_role_id: Option<i16>,
+    #[serde(rename = "deletedFlag")]
+    pub is_del: i16,
+    pub created_by: i64,
+    #[serde(rename = "createdDate")]
+    pub dt_created: NaiveDateTime,
+    pub updated_by: Option<i64>,
+    #[serde(rename = "updatedDate")]
+    pub dt_updated: Option<NaiveDateTime>,
+    pub version: i16,
+}
+
+#[derive(Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct EntryQueryManualShare {
+    #[serde(default)]
+    pub user_id_list: Vec<i64>,
+    #[serde(rename = "roleIdList", default)]
+    pub mt_role_id_list: Vec<i16>,
+}
+
+#[derive(Insertable, Queryable, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[diesel(table_name = crate::schema::tbl_query_manual_snapshot)]
+pub struct QueryManualSnapshot {
+    pub id: i64,
+    #[serde(rename = "queryManualId")]
+    pub query_manual_id: i64,
+    #[serde(skip_serializing)]
+    pub header: String,
+    #[serde(skip_serializing)]
+    pub column_info: String,
+    #[serde(skip_serializing)]
+    pub row_data: String,
+    pub row_count: i64,
+    pub byte_size: i64,
+    #[serde(rename = "expiredDate")]
+    pub dt_expired: NaiveDateTime,
+    pub created_by: i64,
+    #[serde(rename = "capturedDate")]
+    pub dt_created: NaiveDateTime,
+    pub updated_by: Option<i64>,
+    #[serde(rename = "updatedDate")]
+    pub dt_updated: Option<NaiveDateTime>,
+    pub version: i16,
+}
+
+#[derive(Deserialize)]
+pub struct SchemaParam {
+    pub schema: Option<String>,
 }
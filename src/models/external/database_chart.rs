@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use validator::Validate;
+use validator_derive::Validate;
+
+#[derive(Deserialize, Serialize, Validate)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ChartMeasure {
+    #[validate(length(max = 200, message = "Column must not exceed 200 characters"))]
+    pub column: Option<String>,
+    #[validate(length(min = 1, message = "Aggregation must be filled"))]
+    pub aggregation: String,
+}
+
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct EntryChart {
+    #[serde(default, rename = "dimensionList")]
+    #[validate(length(max = 3, message = "At most 3 dimensions are allowed"))]
+    pub dimensions: Vec<String>,
+    #[serde(rename = "measureList")]
+    #[validate(
+        length(min = 1, max = 10, message = "Between 1 and 10 measures are required"),
+        nested
+    )]
+    pub measures: Vec<ChartMeasure>,
+    #[validate(length(max = 200, message = "Pivot column must not exceed 200 characters"))]
+    pub pivot_column: Option<String>,
+    #[validate(range(min = 1, max = 1000, message = "Top N must be between 1 and 1000"))]
+    pub top_n: Option<i64>,
+    // Nilai placeholder {{nama}} pada query tersimpan
+    #[serde(default)]
+    pub parameter: Map<String, Value>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartSeries {
+    #[serde(rename = "name")]
+    pub nm: String,
+    pub measure: String,
+    pub pivot_value: Option<Value>,
+    #[serde(rename = "dataList")]
+    pub data: Vec<Value>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChartResult {
+    #[serde(rename = "dimensionList")]
+    pub dimensions: Vec<String>,
+    #[serde(rename = "categoryList")]
+    pub categories: Vec<Vec<Value>>,
+    #[serde(rename = "seriesList")]
+    pub series: Vec<ChartSeries>,
+    #[serde(rename = "pushedDownFlag")]
+    pub is_pushed_down: i16,
+}
@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use validator_derive::Validate;
+
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct EntryDataCompare {
+    #[validate(length(max = 100, message = "Source schema must not exceed 100 characters"))]
+    pub source_schema: Option<String>,
+    #[validate(length(max = 200, message = "Source table must not exceed 200 characters"))]
+    pub source_table: Option<String>,
+    pub source_query: Option<String>,
+    #[validate(length(max = 100, message = "Target schema must not exceed 100 characters"))]
+    pub target_schema: Option<String>,
+    #[validate(length(max = 200, message = "Target table must not exceed 200 characters"))]
+    pub target_table: Option<String>,
+    pub target_query: Option<String>,
+    #[serde(rename = "keyColumnList")]
+    #[validate(length(min = 1, message = "At least one key column is required"))]
+    pub key_columns: Vec<String>,
+    #[serde(default, rename = "reconcileFlag")]
+    pub is_reconcile: i16,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnDifference {
+    #[serde(rename = "name")]
+    pub nm: String,
+    pub source_value: Value,
+    pub target_value: Value,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RowDifference {
+    pub key: Map<String, Value>,
+    #[serde(rename = "columnList")]
+    pub columns: Vec<ColumnDifference>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataCompareResult {
+    pub source_row: usize,
+    pub target_row: usize,
+    pub matched_row: usize,
+    pub only_in_source_row: usize,
+    pub only_in_target_row: usize,
+    pub different_row: usize,
+    #[serde(rename = "onlyInSourceList")]
+    pub only_in_source: Vec<Map<String, Value>>,
+    #[serde(rename = "onlyInTargetList")]
+    pub only_in_target: Vec<Map<String, Value>>,
+    #[serde(rename = "differentList")]
+    pub different: Vec<RowDifference>,
+    pub script: Option<String>,
+}
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaDiffParam {
+    pub schema: Option<String>,
+    pub target_schema: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnDiff {
+    #[serde(rename = "name")]
+    pub nm: String,
+    pub status: String,
+    pub source_data_type: Option<String>,
+    pub target_data_type: Option<String>,
+    pub source_nullable: Option<bool>,
+    pub target_nullable: Option<bool>,
+    pub source_default_value: Option<String>,
+    pub target_default_value: Option<String>,
+    pub source_identity_generation: Option<String>,
+    pub target_identity_generation: Option<String>,
+    pub source_generated: Option<bool>,
+    pub target_generated: Option<bool>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefinitionDiff {
+    #[serde(rename = "name")]
+    pub nm: String,
+    pub status: String,
+    pub source_definition: Option<String>,
+    pub target_definition: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectDiff {
+    #[serde(rename = "name")]
+    pub nm: String,
+    pub object_type: String,
+    pub status: String,
+    #[serde(rename = "columnList")]
+    pub columns: Vec<ColumnDiff>,
+    #[serde(rename = "constraintList")]
+    pub constraints: Vec<DefinitionDiff>,
+    #[serde(rename = "indexList")]
+    pub indexes: Vec<DefinitionDiff>,
+    pub source_definition: Option<String>,
+    pub target_definition: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaDiff {
+    pub source_schema: String,
+    pub target_schema: String,
+    #[serde(rename = "objectList")]
+    pub objects: Vec<ObjectDiff>,
+    pub script: String,
+}
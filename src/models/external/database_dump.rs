@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use validator_derive::Validate;
+
+#[derive(Deserialize, Serialize, Validate)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct DumpTable {
+    #[serde(rename = "name")]
+    #[validate(length(
+        min = 1,
+        max = 200,
+        message = "Table name must be between 1 and 200 characters"
+    ))]
+    pub nm: String,
+    pub filter: Option<String>,
+}
+
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct EntryDatabaseDump {
+    #[validate(length(max = 100, message = "Schema must not exceed 100 characters"))]
+    pub schema: Option<String>,
+    #[serde(rename = "tableList")]
+    #[validate(length(min = 1, message = "At least one table is required"), nested)]
+    pub tables: Vec<DumpTable>,
+    #[serde(default, rename = "schemaOnlyFlag")]
+    pub is_schema_only: i16,
+    #[serde(default, rename = "dataOnlyFlag")]
+    pub is_data_only: i16,
+    #[validate(range(
+        min = 1,
+        max = 1000,
+        message = "Number of lines per action must be between 1 and 1000"
+    ))]
+    pub number_line_per_action: Option<i16>,
+}
@@ -0,0 +1,51 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::prelude::{Insertable, Queryable};
+use serde::{Deserialize, Serialize};
+
+#[derive(Insertable, Queryable, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[diesel(table_name = crate::schema::tbl_ext_database_execution)]
+pub struct DatabaseExecution {
+    pub id: i64,
+    #[serde(rename = "externalDatabaseId")]
+    pub ext_database_id: i64,
+    pub source: String,
+    pub query: String,
+    pub statement_kind: String,
+    pub duration_ms: i64,
+    pub affected_row: Option<i64>,
+    pub error_message: Option<String>,
+    pub created_by: i64,
+    #[serde(rename = "createdDate")]
+    pub dt_created: NaiveDateTime,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionPagination {
+    pub start: Option<i64>,
+    pub length: Option<i64>,
+    pub search: Option<String>,
+    pub sort: Option<String>,
+    pub dir: Option<String>,
+    pub source: Option<String>,
+    pub statement_kind: Option<String>,
+    pub created_by: Option<i64>,
+    pub error_flag: Option<i16>,
+    #[serde(rename = "startDate")]
+    pub dt_start: Option<NaiveDate>,
+    #[serde(rename = "endDate")]
+    pub dt_end: Option<NaiveDate>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlowestExecution {
+    pub query: String,
+    pub statement_kind: String,
+    pub execution_count: i64,
+    pub average_duration_ms: f64,
+    pub max_duration_ms: i64,
+    #[serde(rename = "lastExecutedDate")]
+    pub dt_last_executed: NaiveDateTime,
+}
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use validator_derive::Validate;
+
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct EntryQueryExplain {
+    #[validate(length(min = 1, message = "Query is required"))]
+    pub query: String,
+    #[serde(default, rename = "analyzeFlag")]
+    pub is_analyze: i16,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanNode {
+    pub node_type: String,
+    pub relation_name: Option<String>,
+    pub startup_cost: Option<f64>,
+    pub total_cost: Option<f64>,
+    pub plan_rows: Option<f64>,
+    pub actual_startup_time: Option<f64>,
+    pub actual_total_time: Option<f64>,
+    pub actual_rows: Option<f64>,
+    pub actual_loops: Option<f64>,
+    pub detail: Map<String, Value>,
+    #[serde(rename = "childList")]
+    pub children: Vec<PlanNode>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryPlan {
+    pub database_type: String,
+    #[serde(rename = "analyzeFlag")]
+    pub is_analyze: i16,
+    pub planning_time: Option<f64>,
+    pub execution_time: Option<f64>,
+    pub plan: PlanNode,
+    pub raw: Value,
+}
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+use validator_derive::Validate;
+
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct EntryQueryFormat {
+    #[validate(length(min = 1, message = "Query must be filled"))]
+    pub query: String,
+    #[validate(range(min = 1, max = 2, message = "Type must be filled"))]
+    pub mt_database_type_id: i16,
+    pub keyword_case: Option<String>,
+    #[validate(range(min = 1, max = 8, message = "Indent size must be between 1 and 8"))]
+    pub indent_size: Option<i16>,
+    #[serde(rename = "useTabFlag")]
+    pub is_use_tab: Option<i16>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryFormatResult {
+    pub query: String,
+    #[serde(rename = "formattedFlag")]
+    pub is_formatted: i16,
+}
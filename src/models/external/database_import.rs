@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ImportColumnMapping {
+    pub file_column: String,
+    pub table_column: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportPreview {
+    #[serde(rename = "headerList")]
+    pub headers: Vec<String>,
+    #[serde(rename = "columnTypeList")]
+    pub column_types: Vec<String>,
+    #[serde(rename = "rowList")]
+    pub rows: Vec<Vec<Option<String>>>,
+    pub total_row: usize,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportRowError {
+    pub row_number: usize,
+    #[serde(rename = "columnName")]
+    pub column_nm: Option<String>,
+    pub message: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportResult {
+    pub total_row: usize,
+    pub inserted_row: usize,
+    pub failed_row: usize,
+    #[serde(rename = "rollbackFlag")]
+    pub is_rollback: i16,
+    #[serde(rename = "errorList")]
+    pub errors: Vec<ImportRowError>,
+}
@@ -0,0 +1,66 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::{AsChangeset, Insertable, Queryable};
+use serde::{Deserialize, Serialize};
+use validator_derive::Validate;
+
+#[derive(Insertable, Queryable, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[diesel(table_name = crate::schema::tbl_ext_database_mask)]
+pub struct DatabaseMask {
+    pub id: i64,
+    #[serde(rename = "externalDatabaseId")]
+    pub ext_database_id: i64,
+    pub table_name: Option<String>,
+    pub column_name: Option<String>,
+    pub column_pattern: Option<String>,
+    pub strategy: String,
+    pub visible_prefix: i16,
+    pub visible_suffix: i16,
+    pub mask_format: Option<String>,
+    #[serde(rename = "roleId")]
+    pub mt_role_id: Option<i16>,
+    #[serde(rename = "deletedFlag")]
+    pub is_del: i16,
+    pub created_by: i64,
+    #[serde(rename = "createdDate")]
+    pub dt_created: NaiveDateTime,
+    pub updated_by: Option<i64>,
+    #[serde(rename = "updatedDate")]
+    pub dt_updated: Option<NaiveDateTime>,
+    pub version: i16,
+}
+
+#[derive(Serialize, Deserialize, Validate, AsChangeset)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[diesel(table_name = crate::schema::tbl_ext_database_mask)]
+#[diesel(treat_none_as_null = true)]
+pub struct EntryDatabaseMask {
+    #[validate(length(max = 255, message = "Table name must not exceed 255 characters"))]
+    pub table_name: Option<String>,
+    #[validate(length(max = 255, message = "Column name must not exceed 255 characters"))]
+    pub column_name: Option<String>,
+    #[validate(length(max = 255, message = "Column pattern must not exceed 255 characters"))]
+    pub column_pattern: Option<String>,
+    #[validate(length(min = 1, max = 10, message = "Strategy must be filled"))]
+    pub strategy: String,
+    #[serde(default)]
+    #[validate(range(
+        min = 0,
+        max = 100,
+        message = "Visible prefix must be between 0 and 100"
+    ))]
+    pub visible_prefix: i16,
+    #[serde(default)]
+    #[validate(range(
+        min = 0,
+        max = 100,
+        message = "Visible suffix must be between 0 and 100"
+    ))]
+    pub visible_suffix: i16,
+    #[validate(length(max = 255, message = "Mask format must not exceed 255 characters"))]
+    pub mask_format: Option<String>,
+    #[serde(rename = "roleId")]
+    pub mt_role_id: Option<i16>,
+    #[serde(default)]
+    pub version: i16,
+}
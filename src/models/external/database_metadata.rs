@@ -0,0 +1,53 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+pub struct MetadataParam {
+    #[serde(rename = "refreshFlag")]
+    pub is_refresh: Option<i16>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataColumn {
+    #[serde(rename = "name")]
+    pub nm: String,
+    pub data_type: String,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataObject {
+    pub schema: String,
+    #[serde(rename = "name")]
+    pub nm: String,
+    pub object_type: String,
+    #[serde(rename = "columnList")]
+    pub columns: Vec<MetadataColumn>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataFunction {
+    pub schema: String,
+    #[serde(rename = "name")]
+    pub nm: String,
+    pub object_type: String,
+    pub signature: String,
+    pub return_type: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseMetadata {
+    #[serde(rename = "schemaList")]
+    pub schemas: Vec<String>,
+    #[serde(rename = "objectList")]
+    pub objects: Vec<MetadataObject>,
+    #[serde(rename = "functionList")]
+    pub functions: Vec<MetadataFunction>,
+    #[serde(rename = "keywordList")]
+    pub keywords: Vec<String>,
+    #[serde(rename = "cachedDate")]
+    pub dt_cached: NaiveDateTime,
+}
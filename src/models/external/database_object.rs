@@ -0,0 +1,82 @@
+use serde::Serialize;
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectColumn {
+    pub position: i64,
+    #[serde(rename = "name")]
+    pub nm: String,
+    pub data_type: String,
+    pub nullable: bool,
+    pub default_value: Option<String>,
+    pub comment: Option<String>,
+    // ALWAYS atau BY DEFAULT untuk kolom identity
+    pub identity_generation: Option<String>,
+    // Kolom generated menyimpan ekspresinya pada default_value
+    pub is_generated: bool,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectConstraint {
+    #[serde(rename = "name")]
+    pub nm: String,
+    pub constraint_type: String,
+    #[serde(rename = "columnList")]
+    pub columns: Vec<String>,
+    pub referenced_table: Option<String>,
+    #[serde(rename = "referencedColumnList")]
+    pub referenced_columns: Vec<String>,
+    pub on_update: Option<String>,
+    pub on_delete: Option<String>,
+    pub definition: String,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectIndex {
+    #[serde(rename = "name")]
+    pub nm: String,
+    #[serde(rename = "columnList")]
+    pub columns: Vec<String>,
+    #[serde(rename = "uniqueFlag")]
+    pub is_unique: i16,
+    #[serde(rename = "primaryFlag")]
+    pub is_primary: i16,
+    pub index_type: Option<String>,
+    pub definition: String,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectTrigger {
+    #[serde(rename = "name")]
+    pub nm: String,
+    pub timing: String,
+    pub event: String,
+    pub definition: String,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectStructure {
+    #[serde(rename = "name")]
+    pub nm: String,
+    pub schema: String,
+    pub object_type: String,
+    pub comment: Option<String>,
+    #[serde(rename = "columnList")]
+    pub columns: Vec<ObjectColumn>,
+    pub primary_key: Option<ObjectConstraint>,
+    #[serde(rename = "foreignKeyList")]
+    pub foreign_keys: Vec<ObjectConstraint>,
+    #[serde(rename = "uniqueKeyList")]
+    pub unique_keys: Vec<ObjectConstraint>,
+    #[serde(rename = "checkConstraintList")]
+    pub check_constraints: Vec<ObjectConstraint>,
+    #[serde(rename = "indexList")]
+    pub indexes: Vec<ObjectIndex>,
+    #[serde(rename = "triggerList")]
+    pub triggers: Vec<ObjectTrigger>,
+    pub ddl: String,
+}
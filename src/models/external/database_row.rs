@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct EntryRowInsert {
+    pub value: Map<String, Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct EntryRowUpdate {
+    pub key: Map<String, Value>,
+    // Nilai baris saat dibaca, update dibatalkan bila sudah berubah
+    #[serde(default)]
+    pub original: Map<String, Value>,
+    pub value: Map<String, Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct EntryRowDelete {
+    pub key: Map<String, Value>,
+    #[serde(default)]
+    pub original: Map<String, Value>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RowEditResult {
+    pub affected_row: u64,
+}
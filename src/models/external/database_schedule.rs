@@ -0,0 +1,90 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::{AsChangeset, Insertable, Queryable};
+use serde::{Deserialize, Serialize};
+use validator_derive::Validate;
+
+#[derive(Insertable, Queryable, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[diesel(table_name = crate::schema::tbl_ext_database_query_schedule)]
+pub struct QuerySchedule {
+    pub id: i64,
+    #[serde(rename = "externalDatabaseQueryId")]
+    pub ext_database_query_id: i64,
+    pub cron_expression: String,
+    pub parameter: Option<String>,
+    pub file_format: String,
+    #[serde(rename = "externalServerId")]
+    pub ext_server_id: Option<i64>,
+    pub directory: Option<String>,
+    pub email: Option<String>,
+    #[serde(rename = "activeFlag")]
+    pub is_active: i16,
+    #[serde(rename = "nextRunDate")]
+    pub dt_next_run: Option<NaiveDateTime>,
+    #[serde(rename = "lastRunDate")]
+    pub dt_last_run: Option<NaiveDateTime>,
+    #[serde(rename = "deletedFlag")]
+    pub is_del: i16,
+    pub created_by: i64,
+    #[serde(rename = "createdDate")]
+    pub dt_created: NaiveDateTime,
+    pub updated_by: Option<i64>,
+    #[serde(rename = "updatedDate")]
+    pub dt_updated: Option<NaiveDateTime>,
+    pub version: i16,
+}
+
+#[derive(Serialize, Deserialize, Validate, AsChangeset)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+#[diesel(table_name = crate::schema::tbl_ext_database_query_schedule)]
+#[diesel(treat_none_as_null = true)]
+pub struct EntryQuerySchedule {
+    #[serde(rename = "externalDatabaseQueryId")]
+    pub ext_database_query_id: i64,
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "Cron expression must be between 1 and 100 characters"
+    ))]
+    pub cron_expression: String,
+    pub parameter: Option<String>,
+    #[validate(length(min = 1, max = 4, message = "File format must be filled"))]
+    pub file_format: String,
+    #[serde(rename = "externalServerId")]
+    pub ext_server_id: Option<i64>,
+    #[validate(length(max = 500, message = "Directory must not exceed 500 characters"))]
+    pub directory: Option<String>,
+    #[validate(length(max = 1000, message = "Email must not exceed 1000 characters"))]
+    pub email: Option<String>,
+    #[serde(rename = "activeFlag")]
+    pub is_active: i16,
+    #[serde(default)]
+    pub version: i16,
+}
+
+#[derive(Insertable, Queryable, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[diesel(table_name = crate::schema::tbl_ext_database_query_schedule_run)]
+pub struct QueryScheduleRun {
+    pub id: i64,
+    #[serde(rename = "externalDatabaseQueryScheduleId")]
+    pub ext_database_query_schedule_id: i64,
+    #[serde(rename = "manualFlag")]
+    pub is_manual: i16,
+    pub status: String,
+    #[serde(rename = "startedDate")]
+    pub dt_started: NaiveDateTime,
+    #[serde(rename = "finishedDate")]
+    pub dt_finished: Option<NaiveDateTime>,
+    pub duration: Option<i64>,
+    pub row_count: Option<i64>,
+    pub file_name: Option<String>,
+    pub message: Option<String>,
+    pub created_by: i64,
+    #[serde(rename = "createdDate")]
+    pub dt_created: NaiveDateTime,
+    pub updated_by: Option<i64>,
+    #[serde(rename = "updatedDate")]
+    pub dt_updated: Option<NaiveDateTime>,
+    pub version: i16,
+}
@@ -17,7 +17,15 @@ pub struct ExternalServer {
     pub ip: String,
     pub port: i16,
     pub username: String,
+    #[serde(
+        rename = "passwordStatus",
+        serialize_with = "crate::models::common::optional_credential_status"
+    )]
     pub password: Option<String>,
+    #[serde(
+        rename = "privateKeyStatus",
+        serialize_with = "crate::models::common::optional_credential_status"
+    )]
     pub private_key: Option<String>,
     #[serde(rename = "lockFlag")]
     pub is_lock: i16,
@@ -53,7 +61,10 @@ pub struct EntryExternalServer {
     pub port: i16,
     #[validate(length(min = 1, message = "Username must be filled"))]
     pub username: String,
+    // Null saat update berarti nilai lama dipertahankan, string kosong menghapusnya
+    #[diesel(skip_update)]
     pub password: Option<String>,
+    #[diesel(skip_update)]
     pub private_key: Option<String>,
     #[serde(default)]
     pub version: i16,
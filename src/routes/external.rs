@@ -1,7 +1,25 @@
 use crate::facades::external::api;
 use crate::facades::external::api_req;
+use crate::facades::external::connection_test;
+use crate::facades::external::credential;
 use crate::facades::external::database;
+use crate::facades::external::database_chart;
+use crate::facades::external::database_compare;
+use crate::facades::external::database_diff;
+use crate::facades::external::database_dump;
+use crate::facades::external::database_execution;
+use crate::facades::external::database_explain;
+use crate::facades::external::database_format;
+use crate::facades::external::database_import;
+use crate::facades::external::database_mask;
+use crate::facades::external::database_metadata;
+use crate::facades::external::database_notify;
+use crate::facades::external::database_object;
 use crate::facades::external::database_query;
+use crate::facades::external::database_query_history;
+use crate::facades::external::database_row;
+use crate::facades::external::database_schedule;
+use crate::facades::external::database_snapshot;
 use crate::facades::external::server;
 use crate::facades::external::server_command;
 
@@ -9,6 +27,15 @@ use poem::{Route, get, post, put};
 
 pub fn routes() -> Route {
     Route::new()
+        .at("/credential-rotate.json", post(credential::credential_rotate))
+        .at(
+            "/database-query-format.json",
+            post(database_format::query_format),
+        )
+        .at(
+            "/database-connect-test.json",
+            post(connection_test::database_test),
+        )
         .at("/database.json", get(database::list).post(database::add))
         .at(
             "/:id/database.json",
@@ -17,6 +44,42 @@ pub fn routes() -> Route {
                 .delete(database::delete),
         )
         .at("/:id/database-connect.json", get(database_query::connect))
+        .at(
+            "/:id/database-mask-list.json",
+            get(database_mask::mask_list).post(database_mask::mask_add),
+        )
+        .at(
+            "/:id/database-mask.json",
+            get(database_mask::mask_get)
+                .put(database_mask::mask_update)
+                .delete(database_mask::mask_delete),
+        )
+        .at(
+            "/:id/database-metadata.json",
+            get(database_metadata::metadata_get),
+        )
+        .at(
+            "/:id/database-notify.json",
+            get(database_notify::notify_listen),
+        )
+        .at("/:id/database-dump.json", post(database_dump::database_dump))
+        .at(
+            "/:id/database-execution-list.json",
+            get(database_execution::execution_list),
+        )
+        .at(
+            "/:id/database-execution-slowest.json",
+            get(database_execution::execution_slowest),
+        )
+        .at(
+            "/:id/database-import-preview.json",
+            post(database_import::import_preview),
+        )
+        .at("/:id/database-import.json", post(database_import::import_run))
+        .at(
+            "/:id/database-query-schema-list.json",
+            get(database_query::query_schema_list),
+        )
         .at(
             "/:id/database-query-object-list.json",
             get(database_query::query_object_list),
@@ -29,6 +92,10 @@ pub fn routes() -> Route {
             "/:id/database-query-manual-run.json",
             post(database_query::query_manual_run),
         )
+        .at(
+            "/:id/database-query-explain.json",
+            post(database_explain::query_explain),
+        )
         .at(
             "/:id/database-query-manual-list.json",
             get(database_query::query_manual_list),
@@ -37,6 +104,30 @@ pub fn routes() -> Route {
             "/:id/database-query-manual-all-list.json",
             get(database_query::query_manual_all_list),
         )
+        .at(
+            "/:id/database-query-history.json",
+            get(database_query_history::history_list),
+        )
+        .at(
+            "/:id/database-query-manual-history.json",
+            put(database_query_history::history_update),
+        )
+        .at(
+            "/:id/:favourite_flag/database-query-manual-favourite.json",
+            put(database_query_history::history_favourite),
+        )
+        .at(
+            "/:id/database-query-manual-share.json",
+            get(database_query_history::share_list).post(database_query_history::share_update),
+        )
+        .at(
+            "/:id/database-query-manual-snapshot.json",
+            get(database_snapshot::snapshot_get).delete(database_snapshot::snapshot_delete),
+        )
+        .at(
+            "/:id/database-query-manual-rerun.json",
+            post(database_query_history::history_rerun),
+        )
         .at(
             "/:id/:include_column_name_flag/:number_line_per_action/database-query-manual-sql-insert.json",
             get(database_query::query_manual_sql_insert),
@@ -49,6 +140,14 @@ pub fn routes() -> Route {
             "/:id/:first_amount_combined/database-query-manual.xlsx",
             get(database_query::query_manual_xlsx),
         )
+        .at(
+            "/:id/database-query-manual.parquet",
+            get(database_query::query_manual_parquet),
+        )
+        .at(
+            "/:id/database-query-manual.arrow",
+            get(database_query::query_manual_arrow),
+        )
         .at(
             "/:id/:header_flag/:delimiter/database-query-manual-csv.json",
             get(database_query::query_manual_csv),
@@ -61,6 +160,10 @@ pub fn routes() -> Route {
             "/:id/database-query-manual-xml.json",
             get(database_query::query_manual_xml),
         )
+        .at(
+            "/:id/database-query-manual-chart.json",
+            post(database_chart::chart_query_manual),
+        )
         .at(
             "/:id/:name/database-query-exact-object-run.json",
             post(database_query::query_exact_object_run),
@@ -69,6 +172,24 @@ pub fn routes() -> Route {
             "/:id/:name/database-query-exact-object-list.json",
             get(database_query::query_exact_object_list),
         )
+        .at(
+            "/:id/:name/database-row.json",
+            post(database_row::row_insert)
+                .put(database_row::row_update)
+                .delete(database_row::row_delete),
+        )
+        .at(
+            "/:id/:name/database-query-object-structure.json",
+            get(database_object::query_object_structure),
+        )
+        .at(
+            "/:id/:target_id/database-schema-diff.json",
+            get(database_diff::schema_diff),
+        )
+        .at(
+            "/:id/:target_id/database-data-compare.json",
+            post(database_compare::data_compare),
+        )
         .at(
             "/:id/database-query-exact-whitelist-run.json",
             post(database_query::query_exact_whitelist_run),
@@ -77,6 +198,32 @@ pub fn routes() -> Route {
             "/:id/database-query-exact-whitelist-list.json",
             get(database_query::query_exact_whitelist_list),
         )
+        .at(
+            "/:id/database-query-whitelist-chart.json",
+            post(database_chart::chart_query_whitelist),
+        )
+        .at(
+            "/database-query-schedule.json",
+            get(database_schedule::schedule_list).post(database_schedule::schedule_add),
+        )
+        .at(
+            "/:id/database-query-schedule.json",
+            get(database_schedule::schedule_get)
+                .put(database_schedule::schedule_update)
+                .delete(database_schedule::schedule_delete),
+        )
+        .at(
+            "/:id/:active_flag/database-query-schedule-active.json",
+            put(database_schedule::schedule_active),
+        )
+        .at(
+            "/:id/database-query-schedule-run.json",
+            get(database_schedule::schedule_run_list).post(database_schedule::schedule_run),
+        )
+        .at(
+            "/:id/database-query-schedule-run-file.json",
+            get(database_schedule::schedule_run_file),
+        )
         .at("/server.json", get(server::list).post(server::add))
         .at(
             "/:id/server.json",
@@ -85,6 +232,10 @@ pub fn routes() -> Route {
                 .delete(server::delete),
         )
         .at("/:id/server-connect.json", get(server_command::connect))
+        .at(
+            "/server-connect-test.json",
+            post(connection_test::server_test),
+        )
         .at("/:id/server-directory.json", get(server_command::directory_list))
         .at("/:id/server-entity.json", 
         post(server_command::add_folder)
@@ -135,8 +135,10 @@ table! {
         username -> Varchar,
         password -> Varchar,
         db_name -> Varchar,
+        default_schema -> Nullable<Varchar>,
         // db_connection -> Varchar,
         is_use_page -> SmallInt,
+        count_estimate_threshold -> Nullable<BigInt>,
         is_lock -> SmallInt,
         is_del -> SmallInt,
         created_by -> BigInt,
@@ -162,11 +164,127 @@ table! {
     }
 }
 
+table! {
+    tbl_ext_database_mask (id) {
+        id -> BigInt,
+        ext_database_id -> BigInt,
+        table_name -> Nullable<Varchar>,
+        column_name -> Nullable<Varchar>,
+        column_pattern -> Nullable<Varchar>,
+        strategy -> Varchar,
+        visible_prefix -> SmallInt,
+        visible_suffix -> SmallInt,
+        mask_format -> Nullable<Varchar>,
+        mt_role_id -> Nullable<SmallInt>,
+        is_del -> SmallInt,
+        created_by -> BigInt,
+        dt_created -> Timestamp,
+        updated_by -> Nullable<BigInt>,
+        dt_updated -> Nullable<Timestamp>,
+        version -> SmallInt,
+    }
+}
+
+table! {
+    tbl_ext_database_execution (id) {
+        id -> BigInt,
+        ext_database_id -> BigInt,
+        source -> Varchar,
+        query -> Varchar,
+        statement_kind -> Varchar,
+        duration_ms -> BigInt,
+        affected_row -> Nullable<BigInt>,
+        error_message -> Nullable<Varchar>,
+        created_by -> BigInt,
+        dt_created -> Timestamp,
+    }
+}
+
+table! {
+    tbl_ext_database_query_schedule (id) {
+        id -> BigInt,
+        ext_database_query_id -> BigInt,
+        cron_expression -> Varchar,
+        parameter -> Nullable<Varchar>,
+        file_format -> Varchar,
+        ext_server_id -> Nullable<BigInt>,
+        directory -> Nullable<Varchar>,
+        email -> Nullable<Varchar>,
+        is_active -> SmallInt,
+        dt_next_run -> Nullable<Timestamp>,
+        dt_last_run -> Nullable<Timestamp>,
+        is_del -> SmallInt,
+        created_by -> BigInt,
+        dt_created -> Timestamp,
+        updated_by -> Nullable<BigInt>,
+        dt_updated -> Nullable<Timestamp>,
+        version -> SmallInt,
+    }
+}
+
+table! {
+    tbl_ext_database_query_schedule_run (id) {
+        id -> BigInt,
+        ext_database_query_schedule_id -> BigInt,
+        is_manual -> SmallInt,
+        status -> Varchar,
+        dt_started -> Timestamp,
+        dt_finished -> Nullable<Timestamp>,
+        duration -> Nullable<BigInt>,
+        row_count -> Nullable<BigInt>,
+        file_name -> Nullable<Varchar>,
+        file_content -> Nullable<Binary>,
+        message -> Nullable<Varchar>,
+        created_by -> BigInt,
+        dt_created -> Timestamp,
+        updated_by -> Nullable<BigInt>,
+        dt_updated -> Nullable<Timestamp>,
+        version -> SmallInt,
+    }
+}
+
 table! {
     tbl_query_manual (id) {
         id -> BigInt,
         ext_database_id -> BigInt,
         query -> Varchar,
+        title -> Nullable<Varchar>,
+        tags -> Nullable<Varchar>,
+        is_favourite -> SmallInt,
+        created_by -> BigInt,
+        dt_created -> Timestamp,
+        updated_by -> Nullable<BigInt>,
+        dt_updated -> Nullable<Timestamp>,
+        version -> SmallInt,
+    }
+}
+
+table! {
+    tbl_query_manual_share (id) {
+        id -> BigInt,
+        query_manual_id -> BigInt,
+        user_id -> Nullable<BigInt>,
+        mt_role_id -> Nullable<SmallInt>,
+        is_del -> SmallInt,
+        created_by -> BigInt,
+        dt_created -> Timestamp,
+        updated_by -> Nullable<BigInt>,
+        dt_updated -> Nullable<Timestamp>,
+        version -> SmallInt,
+    }
+}
+allow_tables_to_appear_in_same_query!(tbl_query_manual, tbl_query_manual_share);
+
+table! {
+    tbl_query_manual_snapshot (id) {
+        id -> BigInt,
+        query_manual_id -> BigInt,
+        header -> Varchar,
+        column_info -> Varchar,
+        row_data -> Varchar,
+        row_count -> BigInt,
+        byte_size -> BigInt,
+        dt_expired -> Timestamp,
         created_by -> BigInt,
         dt_created -> Timestamp,
         updated_by -> Nullable<BigInt>,
@@ -1,2 +1,5 @@
 pub mod common;
+pub mod crypto;
 pub mod database;
+pub mod import;
+pub mod sql_format;
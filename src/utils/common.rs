@@ -110,6 +110,16 @@ pub fn parse_pagination(pagination: &Pagination) -> (i64, i64) {
     (start, length)
 }
 
+// Pola pencarian ilike dengan wildcard dari input di-escape agar dicocokkan apa adanya
+pub fn like_pattern(term: &str) -> String {
+    format!(
+        "%{}%",
+        term.replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    )
+}
+
 pub fn encode_special_chars(input: &str) -> String {
     input
         .chars()
@@ -0,0 +1,123 @@
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::{Engine, engine::general_purpose};
+use poem::http::StatusCode;
+use sha2::{Digest, Sha256};
+
+use crate::utils::common;
+
+const CIPHER_PREFIX: &str = "enc:v1";
+const NONCE_SIZE: usize = 12;
+
+// Master key berupa 32 byte base64, key lama dipisah koma dan hanya dipakai untuk dekripsi
+fn parse_key(value: &str) -> Option<[u8; 32]> {
+    general_purpose::STANDARD
+        .decode(value.trim())
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+}
+
+fn current_key() -> Option<[u8; 32]> {
+    std::env::var("CREDENTIAL_MASTER_KEY")
+        .ok()
+        .and_then(|value| parse_key(&value))
+}
+
+fn known_keys() -> Vec<[u8; 32]> {
+    let previous = std::env::var("CREDENTIAL_MASTER_KEY_PREVIOUS").unwrap_or_default();
+    current_key()
+        .into_iter()
+        .chain(previous.split(',').filter_map(parse_key))
+        .collect()
+}
+
+fn key_id(key: &[u8; 32]) -> String {
+    Sha256::digest(key)
+        .iter()
+        .take(4)
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn seal(key: &[u8], plain: &[u8]) -> Result<String, String> {
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let mut sealed = nonce.to_vec();
+    sealed.extend(
+        cipher
+            .encrypt(&nonce, plain)
+            .map_err(|_| String::from("credential.encryptFailed"))?,
+    );
+    Ok(general_purpose::STANDARD.encode(sealed))
+}
+
+fn open(key: &[u8], sealed: &str) -> Result<Vec<u8>, String> {
+    let sealed = general_purpose::STANDARD
+        .decode(sealed)
+        .map_err(|_| String::from("credential.decryptFailed"))?;
+    if sealed.len() <= NONCE_SIZE {
+        return Err(String::from("credential.decryptFailed"));
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_SIZE);
+    Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key))
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| String::from("credential.decryptFailed"))
+}
+
+// Envelope: setiap nilai punya data key acak sendiri, data key itu yang dibungkus master key
+pub fn encrypt_credential(plain: &str) -> Result<String, String> {
+    let master_key = current_key().ok_or_else(|| String::from("credential.keyNotConfigured"))?;
+    let data_key = Aes256Gcm::generate_key(&mut OsRng);
+
+    Ok(format!(
+        "{}:{}:{}:{}",
+        CIPHER_PREFIX,
+        key_id(&master_key),
+        seal(&master_key, &data_key)?,
+        seal(&data_key, plain.as_bytes())?
+    ))
+}
+
+// Nilai tanpa prefix dianggap data lama yang belum terenkripsi
+pub fn decrypt_credential(stored: &str) -> Result<String, String> {
+    let Some(rest) = stored
+        .strip_prefix(CIPHER_PREFIX)
+        .and_then(|rest| rest.strip_prefix(':'))
+    else {
+        return Ok(stored.to_string());
+    };
+
+    let [stored_key_id, wrapped_key, ciphertext] = rest.split(':').collect::<Vec<_>>()[..] else {
+        return Err(String::from("credential.decryptFailed"));
+    };
+    let master_key = known_keys()
+        .into_iter()
+        .find(|key| key_id(key) == stored_key_id)
+        .ok_or_else(|| String::from("credential.keyNotFound"))?;
+
+    let data_key = open(&master_key, wrapped_key)?;
+    if data_key.len() != 32 {
+        return Err(String::from("credential.decryptFailed"));
+    }
+    String::from_utf8(open(&data_key, ciphertext)?)
+        .map_err(|_| String::from("credential.decryptFailed"))
+}
+
+pub fn is_current_credential(stored: &str) -> bool {
+    current_key()
+        .is_some_and(|key| stored.starts_with(&format!("{}:{}:", CIPHER_PREFIX, key_id(&key))))
+}
+
+pub fn reveal_credential(stored: &str) -> poem::Result<String> {
+    decrypt_credential(stored).map_err(|e| {
+        eprintln!("Credential error: {}", e);
+        common::error_message(StatusCode::INTERNAL_SERVER_ERROR, &e)
+    })
+}
+
+pub fn seal_credential(plain: &str) -> poem::Result<String> {
+    encrypt_credential(plain).map_err(|e| {
+        eprintln!("Credential error: {}", e);
+        common::error_message(StatusCode::INTERNAL_SERVER_ERROR, &e)
+    })
+}
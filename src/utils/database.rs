@@ -1,8 +1,35 @@
+use arrow::array::{
+    ArrayRef, BooleanArray, Date32Array, Decimal128Array, Float32Array, Float64Array, Int16Array,
+    Int32Array, Int64Array, StringArray, TimestampMicrosecondArray, UInt64Array,
+};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::ipc::writer::FileWriter;
+use arrow::record_batch::{RecordBatch, RecordBatchOptions};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
 use poem::http::StatusCode;
 use regex::Regex;
 use serde_json::{Map, Number, Value};
-use sqlx::{Column, Row, TypeInfo, mysql::MySqlRow, postgres::PgRow};
+use sqlparser::ast::{
+    Delete, Expr, FromTable, GroupByExpr, ObjectName, Query, SelectItem, SetExpr, Statement,
+    TableFactor, TableObject, TableWithJoins, Visit, Visitor,
+};
+use sqlparser::dialect::{Dialect, MySqlDialect, PostgreSqlDialect};
+use sqlparser::parser::Parser;
+use sqlx::error::BoxDynError;
+use sqlx::mysql::MySqlColumn;
+use sqlx::postgres::types::{Oid, PgInterval, PgMoney, PgRange, PgTimeTz};
+use sqlx::postgres::{PgColumn, PgTypeInfo, PgTypeKind, PgValueFormat, PgValueRef};
+use sqlx::types::ipnetwork::IpNetwork;
+use sqlx::types::mac_address::MacAddress;
+use sqlx::types::{BigDecimal, BitVec, Json, Uuid};
+use sqlx::{Column, Postgres, Row, TypeInfo, ValueRef, mysql::MySqlRow, postgres::PgRow};
 use std::fmt::Write;
+use std::ops::Bound;
+use std::ops::ControlFlow;
+use std::sync::{Arc, LazyLock};
 use umya_spreadsheet::Style;
 use umya_spreadsheet::structs::Fill;
 use umya_spreadsheet::structs::Font;
@@ -23,12 +50,15 @@ pub fn split_manual_query(input: &str) -> Vec<String> {
         LineComment,
         BlockComment,
         BeginEndBlock,
+        DollarQuote,
     }
 
     use State::*;
 
     let mut state = Normal;
     let mut begin_end_level = 0;
+    let mut dollar_tag = String::new();
+    let mut dollar_start = 0;
     let mut chars = input.chars().peekable();
 
     while let Some(c) = chars.next() {
@@ -63,22 +93,45 @@ pub fn split_manual_query(input: &str) -> Vec<String> {
                     }
                 }
                 'B' | 'b' => {
-                    let mut peek_str = String::from(c);
-                    for _ in 0..4 {
-                        if let Some(&ch) = chars.peek() {
-                            peek_str.push(ch);
-                            chars.next();
-                        } else {
-                            break;
-                        }
-                    }
+                    // Karakter berikutnya hanya dikonsumsi bila memang BEGIN, agar ';' setelah huruf b tetap memisah
+                    let peek_str: String =
+                        std::iter::once(c).chain(chars.clone().take(4)).collect();
 
                     if peek_str.to_uppercase() == "BEGIN" {
                         begin_end_level += 1;
                         state = BeginEndBlock;
+                        current.push_str(&peek_str);
+                        for _ in 0..4 {
+                            chars.next();
+                        }
+                    } else {
+                        current.push(c);
                     }
+                }
+                // Dollar quote PostgreSQL ($$ atau $tag$), isinya tidak dipisah walau ada ';'
+                '$' if !current
+                    .chars()
+                    .last()
+                    .is_some_and(|p| p.is_alphanumeric() || p == '_' || p == '$') =>
+                {
+                    let tag: String = chars
+                        .clone()
+                        .take_while(|ch| ch.is_alphanumeric() || *ch == '_')
+                        .collect();
+                    let is_tag = !tag.starts_with(|ch: char| ch.is_ascii_digit())
+                        && chars.clone().nth(tag.chars().count()) == Some('$');
 
-                    current.push_str(&peek_str);
+                    current.push(c);
+                    if is_tag {
+                        for _ in 0..=tag.chars().count() {
+                            chars.next();
+                        }
+                        current.push_str(&tag);
+                        current.push('$');
+                        dollar_tag = format!("${}$", tag);
+                        dollar_start = current.len();
+                        state = DollarQuote;
+                    }
                 }
                 ';' => {
                     if begin_end_level == 0 {
@@ -131,6 +184,14 @@ pub fn split_manual_query(input: &str) -> Vec<String> {
                     }
                 }
             }
+            DollarQuote => {
+                current.push(c);
+                if current.len() >= dollar_start + dollar_tag.len()
+                    && current.ends_with(&dollar_tag)
+                {
+                    state = Normal;
+                }
+            }
             BeginEndBlock => {
                 current.push(c);
 
@@ -181,208 +242,916 @@ pub fn split_manual_query(input: &str) -> Vec<String> {
     results
 }
 
-pub fn extract_query_parts(flat_query: &str) -> Option<(String, String)> {
-    // let query_pattern = r"(?is)(SELECT)\s+.*?\s+FROM\s+(\S+)
-    //                     |(INSERT)\s+INTO\s+(\S+)
-    //                     |(UPDATE)\s+(\S+)\s+SET
-    //                     |(DELETE)\s+FROM\s+(\S+)
-    //                     |((?:CREATE OR REPLACE|CREATE|REPLACE|ALTER|DROP)\s+(?:FUNCTION|TABLE|VIEW|PROCEDURE))\s+(\S+)";
-    let query_pattern = r"(?is)(SELECT)\s+.*?\s+FROM\s+(\S+)|(INSERT)\s+INTO\s+(\S+)|(UPDATE)\s+(\S+)\s+SET|(DELETE)\s+FROM\s+(\S+)|(CREATE|REPLACE|ALTER|DROP)\s+(\S+)";
+pub fn is_only_comment(query: &str) -> bool {
+    let re = Regex::new(r"(?s)^\s*((--[^\n]*\n?)|(/\*[\s\S]*?\*/))*\s*$").unwrap();
+    re.is_match(query)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SqlStatementKind {
+    Select,
+    Insert,
+    Update,
+    Delete,
+    Create,
+    Alter,
+    Drop,
+    Other,
+}
+
+impl SqlStatementKind {
+    pub fn action(&self) -> &'static str {
+        match self {
+            SqlStatementKind::Select => "select",
+            SqlStatementKind::Insert => "insert",
+            SqlStatementKind::Update => "update",
+            SqlStatementKind::Delete => "delete",
+            SqlStatementKind::Create => "create",
+            SqlStatementKind::Alter => "alter",
+            SqlStatementKind::Drop => "drop",
+            SqlStatementKind::Other => "other",
+        }
+    }
+}
+
+pub struct SqlStatementInfo {
+    pub kind: SqlStatementKind,
+    pub name: String,
+    pub tables: Vec<String>,
+    // UPDATE/DELETE tanpa WHERE pada statement utamanya
+    pub is_unbounded: bool,
+}
+
+pub fn sql_dialect(is_mysql: bool) -> Box<dyn Dialect + Send + Sync> {
+    if is_mysql {
+        Box::new(MySqlDialect {})
+    } else {
+        Box::new(PostgreSqlDialect {})
+    }
+}
+
+#[derive(Default)]
+struct RelationCollector {
+    tables: Vec<String>,
+    cte_names: Vec<String>,
+}
 
-    let re = Regex::new(query_pattern).unwrap();
+impl Visitor for RelationCollector {
+    type Break = ();
 
-    if let Some(caps) = re.captures(flat_query) {
-        for i in (2..=10).rev().step_by(2) {
-            if let (Some(name_match), Some(action_match)) = (caps.get(i), caps.get(i - 1)) {
-                let name = name_match.as_str().to_lowercase();
-                let action = action_match.as_str().to_lowercase();
-                return Some((name, action));
+    fn pre_visit_query(&mut self, query: &Query) -> ControlFlow<Self::Break> {
+        if let Some(with) = &query.with {
+            for cte in &with.cte_tables {
+                self.cte_names.push(cte.alias.name.value.to_lowercase());
             }
         }
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_relation(&mut self, relation: &ObjectName) -> ControlFlow<Self::Break> {
+        let name = relation.to_string();
+        if !self.tables.contains(&name) {
+            self.tables.push(name);
+        }
+        ControlFlow::Continue(())
     }
-    None
 }
 
-pub fn is_sql_type(query: &str, keyword: &str) -> bool {
-    // Contoh: keyword = "DROP|CREATE|ALTER"
-    let pattern = format!(
-        r"(?si)^\s*((--[^\n]*\n?)|(/\*[\s\S]*?\*/))*\s*{}(\s|\(|$)",
-        keyword
-    );
-    Regex::new(&pattern).unwrap().is_match(query)
+fn table_with_joins_name(tables: &[TableWithJoins]) -> Vec<String> {
+    match tables.first().map(|table| &table.relation) {
+        Some(TableFactor::Table { name, .. }) => vec![name.to_string()],
+        _ => Vec::new(),
+    }
 }
 
-pub fn is_only_comment(query: &str) -> bool {
-    let re = Regex::new(r"(?s)^\s*((--[^\n]*\n?)|(/\*[\s\S]*?\*/))*\s*$").unwrap();
-    re.is_match(query)
+fn delete_target_name(delete: &Delete) -> Vec<String> {
+    if !delete.tables.is_empty() {
+        return delete.tables.iter().map(|name| name.to_string()).collect();
+    }
+    match &delete.from {
+        FromTable::WithFromKeyword(tables) | FromTable::WithoutKeyword(tables) => {
+            table_with_joins_name(tables)
+        }
+    }
 }
 
-pub fn convert_to_count_query(raw_query: &str) -> Option<String> {
-    let lower = raw_query.to_lowercase();
+// Mengumpulkan INSERT/UPDATE/DELETE di dalam query, termasuk CTE dan subquery
+#[derive(Default)]
+struct DmlCollector {
+    statements: Vec<Statement>,
+}
 
-    // Cari posisi kata "from" pertama
-    let from_pos = lower.find(" from ")?; // spasi penting agar tidak salah match
+impl Visitor for DmlCollector {
+    type Break = ();
 
-    // Ambil bagian FROM ke akhir
-    let from_clause = &raw_query[from_pos..];
+    fn pre_visit_statement(&mut self, statement: &Statement) -> ControlFlow<Self::Break> {
+        if matches!(
+            statement,
+            Statement::Insert(_) | Statement::Update { .. } | Statement::Delete(_)
+        ) {
+            self.statements.push(statement.clone());
+        }
+        ControlFlow::Continue(())
+    }
+}
 
-    // Buang ORDER BY, LIMIT, OFFSET jika ada
-    let clauses_to_remove = [" order by ", " limit ", " offset ", " fetch "];
-    let mut clean_clause = from_clause.to_string();
-    for clause in clauses_to_remove {
-        if let Some(pos) = clean_clause.to_lowercase().find(clause) {
-            clean_clause = clean_clause[..pos].trim_end().to_string();
+// Menentukan jenis statement beserta objek utamanya, query yang memuat INSERT/UPDATE/DELETE
+// (misalnya WITH d AS (DELETE ...) SELECT ...) diperlakukan sebagai DML
+fn statement_kind(statement: &Statement) -> (SqlStatementKind, Vec<String>, bool) {
+    match statement {
+        Statement::Query(query) => {
+            let mut collector = DmlCollector::default();
+            let _ = query.visit(&mut collector);
+            let mut inner = collector.statements.iter().map(statement_kind);
+            match inner.next() {
+                Some((kind, objects, is_unbounded)) => {
+                    let is_unbounded = is_unbounded || inner.any(|(_, _, unbounded)| unbounded);
+                    (kind, objects, is_unbounded)
+                }
+                None => (SqlStatementKind::Select, Vec::new(), false),
+            }
         }
+        Statement::Insert(insert) => (
+            SqlStatementKind::Insert,
+            match &insert.table {
+                TableObject::TableName(name) => vec![name.to_string()],
+                _ => Vec::new(),
+            },
+            false,
+        ),
+        Statement::Update {
+            table, selection, ..
+        } => (
+            SqlStatementKind::Update,
+            table_with_joins_name(std::slice::from_ref(table)),
+            selection.is_none(),
+        ),
+        Statement::Delete(delete) => (
+            SqlStatementKind::Delete,
+            delete_target_name(delete),
+            delete.selection.is_none(),
+        ),
+        Statement::CreateTable(create) => (
+            SqlStatementKind::Create,
+            vec![create.name.to_string()],
+            false,
+        ),
+        Statement::CreateView { name, .. } | Statement::CreateProcedure { name, .. } => {
+            (SqlStatementKind::Create, vec![name.to_string()], false)
+        }
+        Statement::CreateIndex(create) => (
+            SqlStatementKind::Create,
+            vec![create.table_name.to_string()],
+            false,
+        ),
+        Statement::CreateFunction(create) => (
+            SqlStatementKind::Create,
+            vec![create.name.to_string()],
+            false,
+        ),
+        Statement::CreateTrigger(create) => (
+            SqlStatementKind::Create,
+            vec![create.name.to_string()],
+            false,
+        ),
+        Statement::AlterTable { name, .. } | Statement::AlterView { name, .. } => {
+            (SqlStatementKind::Alter, vec![name.to_string()], false)
+        }
+        Statement::Drop { names, .. } => (
+            SqlStatementKind::Drop,
+            names.iter().map(|name| name.to_string()).collect(),
+            false,
+        ),
+        Statement::DropFunction { func_desc, .. }
+        | Statement::DropProcedure {
+            proc_desc: func_desc,
+            ..
+        } => (
+            SqlStatementKind::Drop,
+            func_desc.iter().map(|desc| desc.name.to_string()).collect(),
+            false,
+        ),
+        Statement::DropTrigger(drop) => (
+            SqlStatementKind::Drop,
+            vec![drop.trigger_name.to_string()],
+            false,
+        ),
+        _ => (SqlStatementKind::Other, Vec::new(), false),
     }
+}
 
-    // Susun ulang
-    let result = format!("SELECT COUNT(*){}", clean_clause);
-    Some(result)
+// Body routine (BEGIN ... END) belum tentu dikenali parser, jadi DDL tetap dikenali dari keyword
+fn parse_ddl_fallback(query: &str) -> Option<SqlStatementInfo> {
+    let re = Regex::new(
+        r"(?is)^\s*((--[^\n]*\n?)|(/\*[\s\S]*?\*/))*\s*(CREATE|ALTER|DROP)\s+(OR\s+REPLACE\s+)?(DEFINER\s*=\s*\S+\s+)?(TABLE|VIEW|FUNCTION|PROCEDURE|TRIGGER|INDEX|EVENT)\s+(IF\s+(NOT\s+)?EXISTS\s+)?([^\s(]+)",
+    )
+    .unwrap();
+    let caps = re.captures(query)?;
+    let kind = match caps[4].to_uppercase().as_str() {
+        "CREATE" => SqlStatementKind::Create,
+        "ALTER" => SqlStatementKind::Alter,
+        _ => SqlStatementKind::Drop,
+    };
+    let name = caps[10].to_string();
+
+    Some(SqlStatementInfo {
+        kind,
+        tables: vec![name.clone()],
+        name,
+        is_unbounded: false,
+    })
 }
 
-pub fn rows_to_json_postgres(rows: &[PgRow]) -> Vec<Value> {
-    let mut results = Vec::new();
+pub fn parse_statement_info(
+    query: &str,
+    dialect: &dyn Dialect,
+) -> Result<SqlStatementInfo, String> {
+    let statements = match Parser::parse_sql(dialect, query) {
+        Ok(statements) => statements,
+        Err(e) => return parse_ddl_fallback(query).ok_or_else(|| e.to_string()),
+    };
+
+    let [statement] = statements.as_slice() else {
+        return Err(String::from("Expected exactly one statement"));
+    };
+
+    let (kind, objects, is_unbounded) = statement_kind(statement);
+
+    let mut collector = RelationCollector {
+        tables: objects,
+        ..Default::default()
+    };
+    let _ = statement.visit(&mut collector);
+    let tables: Vec<String> = collector
+        .tables
+        .into_iter()
+        .filter(|table| !collector.cte_names.contains(&table.to_lowercase()))
+        .collect();
+
+    Ok(SqlStatementInfo {
+        kind,
+        name: tables.first().cloned().unwrap_or_default(),
+        tables,
+        is_unbounded,
+    })
+}
 
-    for row in rows {
-        let mut map = Map::new();
+// Kata kunci reserved PostgreSQL, termasuk yang hanya boleh dipakai sebagai nama fungsi atau tipe
+const POSTGRES_RESERVED_WORDS: &str = "all analyse analyze and any array as asc asymmetric authorization binary both case cast check collate collation column concurrently constraint create cross current_catalog current_date current_role current_schema current_time current_timestamp current_user default deferrable desc distinct do else end except false fetch for foreign freeze from full grant group having ilike in initially inner intersect into is isnull join lateral leading left like limit localtime localtimestamp natural not notnull null offset on only or order outer overlaps placing primary references returning right select session_user similar some symmetric system_user table tablesample then to trailing true union unique user using variadic verbose when where window with";
 
-        for (i, column) in row.columns().iter().enumerate() {
-            let name = column.name();
-            let type_name = column.type_info().name().to_uppercase();
-
-            let value = match type_name.as_str() {
-                "INT2" => row
-                    .try_get::<i16, _>(i)
-                    .map(|v| Value::Number((v as i64).into())),
-                "INT4" => row
-                    .try_get::<i32, _>(i)
-                    .map(|v| Value::Number((v as i64).into())),
-                "INT8" => row
-                    .try_get::<i64, _>(i)
-                    .map(|v| Value::Number(Number::from(v))),
-                "FLOAT4" | "FLOAT8" => row.try_get::<f64, _>(i).map(|v| {
-                    Number::from_f64(v)
-                        .map(Value::Number)
-                        .unwrap_or(Value::Null)
-                }),
-                "NUMERIC" | "DECIMAL" => row.try_get::<String, _>(i).map(Value::String),
-                "BOOL" => row.try_get::<bool, _>(i).map(Value::Bool),
-                "DATE" | "TIMESTAMP" => row.try_get::<String, _>(i).map(Value::String),
-                "OID" => row
-                    .try_get::<i64, _>(i)
-                    .map(|v| Value::Number(Number::from(v)))
-                    .or_else(|_| {
-                        row.try_get::<i32, _>(i)
-                            .map(|v| Value::Number((v as i64).into()))
-                    }),
-                _ => {
-                    // Coba ambil sebagai String dulu
-                    row.try_get::<String, _>(i)
-                        .map(Value::String)
-                        .or_else(|_| row.try_get::<bool, _>(i).map(Value::Bool))
-                        .or_else(|_| {
-                            row.try_get::<f64, _>(i).map(|v| {
-                                Number::from_f64(v)
-                                    .map(Value::Number)
-                                    .unwrap_or(Value::Null)
-                            })
-                        })
+static SIMPLE_IDENTIFIER_POSTGRES: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[a-z_][a-z0-9_$]*$").unwrap());
+
+pub fn quote_identifier_postgres(name: &str) -> String {
+    if SIMPLE_IDENTIFIER_POSTGRES.is_match(name)
+        && !POSTGRES_RESERVED_WORDS.split(' ').any(|word| word == name)
+    {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+}
+
+pub fn quote_identifier_mysql(name: &str) -> String {
+    format!("`{}`", name.replace('`', "``"))
+}
+
+pub fn quote_literal_postgres(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+pub fn quote_literal_mysql(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "''"))
+}
+
+pub fn value_to_sql_literal(value: &Value, is_mysql: bool) -> String {
+    match value {
+        Value::Null => "NULL".to_string(),
+        Value::Bool(b) if is_mysql => (if *b { "1" } else { "0" }).to_string(),
+        Value::Bool(b) => b.to_string().to_uppercase(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) if is_mysql => quote_literal_mysql(s),
+        Value::String(s) => quote_literal_postgres(s),
+        other if is_mysql => quote_literal_mysql(&other.to_string()),
+        other => quote_literal_postgres(&other.to_string()),
+    }
+}
+
+// Kurung kurawal ganda supaya tidak bentrok dengan quantifier regex atau escape ODBC seperti {fn now()}
+static PARAMETER_PLACEHOLDER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{(\w+)\}\}").unwrap());
+
+// Placeholder {{nama}} diganti literal SQL yang sudah di-escape sesuai dialek dalam satu kali lintasan,
+// sehingga nilai yang memuat {{nama}} tidak ikut diganti lagi
+pub fn apply_parameter(
+    query: &str,
+    parameter: &Map<String, Value>,
+    is_mysql: bool,
+) -> Result<String, String> {
+    let mut is_unknown = false;
+    let query = PARAMETER_PLACEHOLDER.replace_all(query, |caps: &regex::Captures| match parameter
+        .get(&caps[1])
+    {
+        Some(value) => value_to_sql_literal(value, is_mysql),
+        None => {
+            is_unknown = true;
+            caps[0].to_string()
+        }
+    });
+
+    if is_unknown {
+        return Err(String::from("query.unknownParameter"));
+    }
+    Ok(query.into_owned())
+}
+
+// ORDER BY/LIMIT/OFFSET/FETCH teratas dibuang lewat parser agar query bisa dipakai sebagai derived table
+pub fn strip_query_ordering(raw_query: &str, dialect: &dyn Dialect) -> String {
+    if let Ok(mut statements) = Parser::parse_sql(dialect, raw_query)
+        && let [Statement::Query(query)] = statements.as_mut_slice()
+    {
+        query.order_by = None;
+        query.limit_clause = None;
+        query.fetch = None;
+        return query.to_string();
+    }
+    raw_query.trim().trim_end_matches(';').to_string()
+}
+
+pub fn convert_to_count_query(raw_query: &str, dialect: &dyn Dialect) -> String {
+    format!(
+        "SELECT COUNT(*) FROM ({}) count_rows",
+        strip_query_ordering(raw_query, dialect)
+    )
+}
+
+pub struct KeysetRelation {
+    pub name: String,
+    pub name_parts: Vec<String>,
+    pub projection: Option<Vec<String>>,
+}
+
+// Keyset hanya aman untuk SELECT sederhana dari satu tabel tanpa urutan, agregasi maupun DISTINCT
+pub fn keyset_relation(raw_query: &str, dialect: &dyn Dialect) -> Option<KeysetRelation> {
+    let statements = Parser::parse_sql(dialect, raw_query).ok()?;
+    let [Statement::Query(query)] = statements.as_slice() else {
+        return None;
+    };
+    if query.with.is_some()
+        || query.order_by.is_some()
+        || query.limit_clause.is_some()
+        || query.fetch.is_some()
+    {
+        return None;
+    }
+    let SetExpr::Select(select) = query.body.as_ref() else {
+        return None;
+    };
+    let is_grouped = match &select.group_by {
+        GroupByExpr::All(_) => true,
+        GroupByExpr::Expressions(exprs, _) => !exprs.is_empty(),
+    };
+    if select.distinct.is_some() || is_grouped || select.having.is_some() {
+        return None;
+    }
+    let [table] = select.from.as_slice() else {
+        return None;
+    };
+    let TableFactor::Table {
+        name, args: None, ..
+    } = &table.relation
+    else {
+        return None;
+    };
+    if !table.joins.is_empty() {
+        return None;
+    }
+
+    let mut projection = Some(Vec::new());
+    for item in &select.projection {
+        match item {
+            SelectItem::Wildcard(_) | SelectItem::QualifiedWildcard(_, _) => projection = None,
+            SelectItem::UnnamedExpr(Expr::Identifier(ident)) => {
+                if let Some(columns) = projection.as_mut() {
+                    columns.push(ident.value.clone());
+                }
+            }
+            SelectItem::UnnamedExpr(Expr::CompoundIdentifier(idents)) => {
+                if let Some(columns) = projection.as_mut()
+                    && let Some(ident) = idents.last()
+                {
+                    columns.push(ident.value.clone());
                 }
             }
-            .unwrap_or(Value::Null);
+            _ => {}
+        }
+    }
+
+    Some(KeysetRelation {
+        name: name.to_string(),
+        name_parts: name
+            .0
+            .iter()
+            .filter_map(|part| part.as_ident().map(|ident| ident.value.clone()))
+            .collect(),
+        projection,
+    })
+}
+
+pub fn rows_to_json_postgres(rows: &[PgRow]) -> Vec<Value> {
+    rows.iter()
+        .map(|row| Value::Object(row_to_json_postgres(row)))
+        .collect()
+}
+
+fn float_value(v: f64) -> Value {
+    // NaN dan Infinity tidak bisa direpresentasikan sebagai angka JSON
+    Number::from_f64(v)
+        .map(Value::Number)
+        .unwrap_or_else(|| Value::String(v.to_string()))
+}
+
+fn bytes_to_hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        let _ = write!(&mut hex, "{:02x}", byte);
+    }
+    hex
+}
+
+// Nilai yang tidak dikenali tetap dikembalikan, sebagai teks jika terbaca atau hex jika biner
+fn raw_bytes_value(bytes: &[u8]) -> Value {
+    match std::str::from_utf8(bytes) {
+        Ok(text) if !text.chars().any(|c| c.is_control() && !c.is_whitespace()) => {
+            Value::String(text.to_string())
+        }
+        _ => Value::String(bytes_to_hex(bytes)),
+    }
+}
+
+fn interval_to_string(interval: PgInterval) -> String {
+    let mut result = String::from("P");
+    let (years, months) = (interval.months / 12, interval.months % 12);
+    if years != 0 {
+        let _ = write!(&mut result, "{}Y", years);
+    }
+    if months != 0 {
+        let _ = write!(&mut result, "{}M", months);
+    }
+    if interval.days != 0 {
+        let _ = write!(&mut result, "{}D", interval.days);
+    }
 
-            map.insert(name.to_string(), value);
+    let sign = if interval.microseconds < 0 { "-" } else { "" };
+    let micros = interval.microseconds.unsigned_abs();
+    let (hours, minutes) = (micros / 3_600_000_000, micros / 60_000_000 % 60);
+    let (seconds, fraction) = (micros / 1_000_000 % 60, micros % 1_000_000);
+    if micros != 0 {
+        result.push('T');
+        if hours != 0 {
+            let _ = write!(&mut result, "{}{}H", sign, hours);
+        }
+        if minutes != 0 {
+            let _ = write!(&mut result, "{}{}M", sign, minutes);
         }
+        if seconds != 0 || fraction != 0 {
+            let fraction = format!("{:06}", fraction);
+            let fraction = fraction.trim_end_matches('0');
+            if fraction.is_empty() {
+                let _ = write!(&mut result, "{}{}S", sign, seconds);
+            } else {
+                let _ = write!(&mut result, "{}{}.{}S", sign, seconds, fraction);
+            }
+        }
+    }
 
-        results.push(Value::Object(map));
+    if result == "P" {
+        result.push_str("T0S");
     }
+    result
+}
 
-    results
+fn range_to_string<T>(range: PgRange<T>, format: impl Fn(&T) -> String) -> String {
+    let lower = match &range.start {
+        Bound::Included(v) => format!("[{}", format(v)),
+        Bound::Excluded(v) => format!("({}", format(v)),
+        Bound::Unbounded => String::from("("),
+    };
+    let upper = match &range.end {
+        Bound::Included(v) => format!("{}]", format(v)),
+        Bound::Excluded(v) => format!("{})", format(v)),
+        Bound::Unbounded => String::from(")"),
+    };
+    format!("{},{}", lower, upper)
 }
 
-pub fn rows_to_json_mysql(rows: &[MySqlRow]) -> Vec<Value> {
-    let mut results = Vec::new();
+fn network_to_string(network: IpNetwork) -> String {
+    if network.prefix() == IpNetwork::from(network.ip()).prefix() {
+        network.ip().to_string()
+    } else {
+        network.to_string()
+    }
+}
 
-    for row in rows {
-        let mut map = Map::new();
+// sqlx belum punya decoder macaddr8, format biner berisi 8 byte alamat
+struct MacAddress8(String);
+
+impl sqlx::Type<Postgres> for MacAddress8 {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("macaddr8")
+    }
+}
 
-        for (i, column) in row.columns().iter().enumerate() {
-            let name = column.name();
-            let type_name = column.type_info().name().to_uppercase();
-
-            let value = match type_name.as_str() {
-                "SMALLINT" => row
-                    .try_get::<i16, _>(i)
-                    .map(|v| Value::Number((v as i64).into())),
-                "INT" | "INTEGER" => row
-                    .try_get::<i32, _>(i)
-                    .map(|v| Value::Number((v as i64).into())),
-                "BIGINT" => row
-                    .try_get::<i64, _>(i)
-                    .map(|v| Value::Number(Number::from(v))),
-                "FLOAT" | "DOUBLE" => row.try_get::<f64, _>(i).map(|v| {
-                    Number::from_f64(v)
-                        .map(Value::Number)
-                        .unwrap_or(Value::Null)
-                }),
-                "DECIMAL" => row.try_get::<String, _>(i).map(Value::String),
-                "BOOLEAN" | "TINYINT(1)" => row.try_get::<bool, _>(i).map(Value::Bool),
-                "DATE" | "DATETIME" | "TIMESTAMP" => row.try_get::<String, _>(i).map(Value::String),
-                _ => row.try_get::<String, _>(i).map(Value::String),
+impl sqlx::Decode<'_, Postgres> for MacAddress8 {
+    fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+        match value.format() {
+            PgValueFormat::Binary => {
+                let bytes = value.as_bytes()?;
+                if bytes.len() != 8 {
+                    return Err("invalid macaddr8 length".into());
+                }
+                Ok(MacAddress8(
+                    bytes
+                        .iter()
+                        .map(|byte| format!("{:02x}", byte))
+                        .collect::<Vec<_>>()
+                        .join(":"),
+                ))
             }
-            .unwrap_or(Value::Null);
+            PgValueFormat::Text => Ok(MacAddress8(value.as_str()?.to_string())),
+        }
+    }
+}
+
+fn postgres_typed<T>(
+    row: &PgRow,
+    i: usize,
+    is_array: bool,
+    convert: impl Fn(T) -> Value,
+) -> Result<Value, sqlx::Error>
+where
+    T: for<'a> sqlx::Decode<'a, Postgres> + sqlx::Type<Postgres>,
+{
+    if is_array {
+        row.try_get_unchecked::<Vec<Option<T>>, _>(i).map(|items| {
+            Value::Array(
+                items
+                    .into_iter()
+                    .map(|item| item.map(&convert).unwrap_or(Value::Null))
+                    .collect(),
+            )
+        })
+    } else {
+        row.try_get_unchecked::<T, _>(i).map(convert)
+    }
+}
 
-            map.insert(name.to_string(), value);
+fn postgres_value(row: &PgRow, i: usize, type_info: &PgTypeInfo) -> Result<Value, sqlx::Error> {
+    let (type_info, is_array) = match type_info.kind() {
+        PgTypeKind::Array(element) => (element, true),
+        _ => (type_info, false),
+    };
+    // Domain dibaca sesuai tipe dasarnya
+    let type_info = match type_info.kind() {
+        PgTypeKind::Domain(base) => base,
+        _ => type_info,
+    };
+    if matches!(type_info.kind(), PgTypeKind::Enum(_)) {
+        return postgres_typed(row, i, is_array, Value::String);
+    }
+
+    match type_info.name().to_uppercase().as_str() {
+        "INT2" => postgres_typed(row, i, is_array, |v: i16| Value::from(v)),
+        "INT4" => postgres_typed(row, i, is_array, |v: i32| Value::from(v)),
+        "INT8" => postgres_typed(row, i, is_array, |v: i64| Value::from(v)),
+        "OID" => postgres_typed(row, i, is_array, |v: Oid| Value::from(v.0)),
+        "FLOAT4" => postgres_typed(row, i, is_array, |v: f32| float_value(v as f64)),
+        "FLOAT8" => postgres_typed(row, i, is_array, float_value),
+        "NUMERIC" => postgres_typed(row, i, is_array, |v: BigDecimal| {
+            Value::String(v.to_string())
+        }),
+        "MONEY" => postgres_typed(row, i, is_array, |v: PgMoney| {
+            Value::String(v.to_bigdecimal(2).to_string())
+        }),
+        "BOOL" => postgres_typed(row, i, is_array, Value::Bool),
+        "\"CHAR\"" => postgres_typed(row, i, is_array, |v: i8| {
+            Value::String((v as u8 as char).to_string())
+        }),
+        "TEXT" | "VARCHAR" | "BPCHAR" | "NAME" | "CITEXT" | "UNKNOWN" | "XML" => {
+            postgres_typed(row, i, is_array, Value::String)
         }
+        "DATE" => postgres_typed(row, i, is_array, |v: NaiveDate| {
+            Value::String(v.to_string())
+        }),
+        "TIME" => postgres_typed(row, i, is_array, |v: NaiveTime| {
+            Value::String(v.to_string())
+        }),
+        "TIMETZ" => postgres_typed(row, i, is_array, |v: PgTimeTz<NaiveTime, FixedOffset>| {
+            Value::String(format!("{}{}", v.time, v.offset))
+        }),
+        "TIMESTAMP" => postgres_typed(row, i, is_array, |v: NaiveDateTime| {
+            Value::String(v.to_string())
+        }),
+        "TIMESTAMPTZ" => postgres_typed(row, i, is_array, |v: DateTime<Utc>| {
+            Value::String(v.to_rfc3339())
+        }),
+        "INTERVAL" => postgres_typed(row, i, is_array, |v: PgInterval| {
+            Value::String(interval_to_string(v))
+        }),
+        "UUID" => postgres_typed(row, i, is_array, |v: Uuid| Value::String(v.to_string())),
+        "JSON" | "JSONB" => postgres_typed(row, i, is_array, |v: Json<Value>| v.0),
+        "BYTEA" => postgres_typed(row, i, is_array, |v: Vec<u8>| {
+            Value::String(bytes_to_hex(&v))
+        }),
+        "INET" | "CIDR" => postgres_typed(row, i, is_array, |v: IpNetwork| {
+            Value::String(network_to_string(v))
+        }),
+        "MACADDR" => postgres_typed(row, i, is_array, |v: MacAddress| {
+            Value::String(v.to_string())
+        }),
+        "MACADDR8" => postgres_typed(row, i, is_array, |v: MacAddress8| Value::String(v.0)),
+        "BIT" | "VARBIT" => postgres_typed(row, i, is_array, |v: BitVec| {
+            Value::String(v.iter().map(|bit| if bit { '1' } else { '0' }).collect())
+        }),
+        "INT4RANGE" => postgres_typed(row, i, is_array, |v: PgRange<i32>| {
+            Value::String(range_to_string(v, i32::to_string))
+        }),
+        "INT8RANGE" => postgres_typed(row, i, is_array, |v: PgRange<i64>| {
+            Value::String(range_to_string(v, i64::to_string))
+        }),
+        "NUMRANGE" => postgres_typed(row, i, is_array, |v: PgRange<BigDecimal>| {
+            Value::String(range_to_string(v, BigDecimal::to_string))
+        }),
+        "DATERANGE" => postgres_typed(row, i, is_array, |v: PgRange<NaiveDate>| {
+            Value::String(range_to_string(v, NaiveDate::to_string))
+        }),
+        "TSRANGE" => postgres_typed(row, i, is_array, |v: PgRange<NaiveDateTime>| {
+            Value::String(range_to_string(v, NaiveDateTime::to_string))
+        }),
+        "TSTZRANGE" => postgres_typed(row, i, is_array, |v: PgRange<DateTime<Utc>>| {
+            Value::String(range_to_string(v, DateTime::to_rfc3339))
+        }),
+        _ => Err(sqlx::Error::Decode(
+            format!("unsupported type {}", type_info.name()).into(),
+        )),
+    }
+}
 
-        results.push(Value::Object(map));
+pub fn logical_type_postgres(type_info: &PgTypeInfo) -> &'static str {
+    match type_info.kind() {
+        PgTypeKind::Array(_) => return "array",
+        PgTypeKind::Enum(_) => return "enum",
+        PgTypeKind::Range(_) => return "range",
+        PgTypeKind::Domain(base) => return logical_type_postgres(base),
+        _ => {}
     }
 
-    results
+    match type_info.name().to_uppercase().as_str() {
+        "INT2" | "INT4" | "INT8" | "OID" => "integer",
+        "FLOAT4" | "FLOAT8" => "float",
+        "NUMERIC" | "MONEY" => "decimal",
+        "BOOL" => "boolean",
+        "\"CHAR\"" | "TEXT" | "VARCHAR" | "BPCHAR" | "NAME" | "CITEXT" | "UNKNOWN" | "XML" => {
+            "text"
+        }
+        "DATE" => "date",
+        "TIME" | "TIMETZ" => "time",
+        "TIMESTAMP" => "timestamp",
+        "TIMESTAMPTZ" => "timestamptz",
+        "INTERVAL" => "interval",
+        "UUID" => "uuid",
+        "JSON" | "JSONB" => "json",
+        "BYTEA" => "binary",
+        "INET" | "CIDR" | "MACADDR" | "MACADDR8" => "network",
+        "BIT" | "VARBIT" => "bit",
+        _ => "other",
+    }
+}
+
+pub fn row_to_json_postgres(row: &PgRow) -> Map<String, Value> {
+    let mut map = Map::new();
+
+    for (i, column) in row.columns().iter().enumerate() {
+        let value = match row.try_get_raw(i) {
+            Ok(raw) if raw.is_null() => Value::Null,
+            // Byte wire format tipe yang tidak dikenali bukan nilai yang bermakna, jadi dikosongkan
+            Ok(_) => postgres_value(row, i, column.type_info()).unwrap_or(Value::Null),
+            Err(_) => Value::Null,
+        };
+
+        map.insert(column.name().to_string(), value);
+    }
+
+    map
+}
+
+pub fn rows_to_json_mysql(rows: &[MySqlRow]) -> Vec<Value> {
+    rows.iter()
+        .map(|row| Value::Object(row_to_json_mysql(row)))
+        .collect()
+}
+
+fn mysql_value(row: &MySqlRow, i: usize, type_name: &str) -> Result<Value, sqlx::Error> {
+    match type_name {
+        "BOOLEAN" => row.try_get_unchecked::<bool, _>(i).map(Value::Bool),
+        "TINYINT" | "SMALLINT" | "MEDIUMINT" | "INT" | "BIGINT" => {
+            row.try_get_unchecked::<i64, _>(i).map(Value::from)
+        }
+        "TINYINT UNSIGNED" | "SMALLINT UNSIGNED" | "MEDIUMINT UNSIGNED" | "INT UNSIGNED"
+        | "BIGINT UNSIGNED" | "YEAR" | "BIT" => row.try_get_unchecked::<u64, _>(i).map(Value::from),
+        "FLOAT" => row
+            .try_get_unchecked::<f32, _>(i)
+            .map(|v| float_value(v as f64)),
+        "DOUBLE" => row.try_get_unchecked::<f64, _>(i).map(float_value),
+        "DECIMAL" => row
+            .try_get_unchecked::<BigDecimal, _>(i)
+            .map(|v| Value::String(v.to_string())),
+        "DATE" => row
+            .try_get_unchecked::<NaiveDate, _>(i)
+            .map(|v| Value::String(v.to_string())),
+        "TIME" => row
+            .try_get_unchecked::<NaiveTime, _>(i)
+            .map(|v| Value::String(v.to_string())),
+        "DATETIME" | "TIMESTAMP" => row
+            .try_get_unchecked::<NaiveDateTime, _>(i)
+            .map(|v| Value::String(v.to_string())),
+        "JSON" => row.try_get_unchecked::<Json<Value>, _>(i).map(|v| v.0),
+        "CHAR" | "VARCHAR" | "TINYTEXT" | "TEXT" | "MEDIUMTEXT" | "LONGTEXT" | "ENUM" | "SET" => {
+            row.try_get_unchecked::<String, _>(i).map(Value::String)
+        }
+        "BINARY" | "VARBINARY" | "TINYBLOB" | "BLOB" | "MEDIUMBLOB" | "LONGBLOB" | "GEOMETRY" => {
+            row.try_get_unchecked::<Vec<u8>, _>(i)
+                .map(|v| Value::String(bytes_to_hex(&v)))
+        }
+        "NULL" => Ok(Value::Null),
+        _ => Err(sqlx::Error::Decode(
+            format!("unsupported type {}", type_name).into(),
+        )),
+    }
+}
+
+pub fn logical_type_mysql(type_name: &str) -> &'static str {
+    match type_name.to_uppercase().as_str() {
+        "BOOLEAN" => "boolean",
+        "TINYINT" | "SMALLINT" | "MEDIUMINT" | "INT" | "BIGINT" | "TINYINT UNSIGNED"
+        | "SMALLINT UNSIGNED" | "MEDIUMINT UNSIGNED" | "INT UNSIGNED" | "BIGINT UNSIGNED"
+        | "YEAR" => "integer",
+        "BIT" => "bit",
+        "FLOAT" | "DOUBLE" => "float",
+        "DECIMAL" => "decimal",
+        "DATE" => "date",
+        "TIME" => "time",
+        "DATETIME" | "TIMESTAMP" => "timestamp",
+        "JSON" => "json",
+        "CHAR" | "VARCHAR" | "TINYTEXT" | "TEXT" | "MEDIUMTEXT" | "LONGTEXT" | "SET" => "text",
+        "ENUM" => "enum",
+        "BINARY" | "VARBINARY" | "TINYBLOB" | "BLOB" | "MEDIUMBLOB" | "LONGBLOB" => "binary",
+        "GEOMETRY" => "geometry",
+        _ => "other",
+    }
+}
+
+pub fn row_to_json_mysql(row: &MySqlRow) -> Map<String, Value> {
+    let mut map = Map::new();
+
+    for (i, column) in row.columns().iter().enumerate() {
+        let type_name = column.type_info().name().to_uppercase();
+
+        let value = match row.try_get_raw(i) {
+            Ok(raw) if raw.is_null() => Value::Null,
+            // Tanggal nol atau TIME di atas 24 jam gagal di-decode, nilainya diambil apa adanya
+            Ok(_) => mysql_value(row, i, &type_name).unwrap_or_else(|_| {
+                row.try_get_unchecked::<Vec<u8>, _>(i)
+                    .map(|v| raw_bytes_value(&v))
+                    .unwrap_or(Value::Null)
+            }),
+            Err(_) => Value::Null,
+        };
+
+        map.insert(column.name().to_string(), value);
+    }
+
+    map
 }
 
 pub fn extract_columns_info_postgres(rows: &[PgRow]) -> Vec<Value> {
+    rows.first()
+        .map(|row| columns_info_postgres(row.columns()))
+        .unwrap_or_default()
+}
+
+// Dipakai juga untuk hasil describe sehingga hasil kosong tetap punya daftar kolom
+pub fn columns_info_postgres(columns: &[PgColumn]) -> Vec<Value> {
     let mut columns_info = Vec::new();
 
-    if let Some(row) = rows.get(0) {
-        for column in row.columns() {
-            let mut map = Map::new();
-            map.insert("name".to_string(), Value::String(column.name().to_string()));
-            map.insert(
-                "type".to_string(),
-                Value::String(column.type_info().name().to_string()),
-            );
-            columns_info.push(Value::Object(map));
-        }
+    for column in columns {
+        let mut map = Map::new();
+        map.insert("name".to_string(), Value::String(column.name().to_string()));
+        map.insert(
+            "type".to_string(),
+            Value::String(column.type_info().name().to_string()),
+        );
+        map.insert(
+            "logicalType".to_string(),
+            Value::String(logical_type_postgres(column.type_info()).to_string()),
+        );
+        columns_info.push(Value::Object(map));
     }
 
     columns_info
 }
 
 pub fn extract_columns_info_mysql(rows: &[MySqlRow]) -> Vec<Value> {
+    rows.first()
+        .map(|row| columns_info_mysql(row.columns()))
+        .unwrap_or_default()
+}
+
+pub fn columns_info_mysql(columns: &[MySqlColumn]) -> Vec<Value> {
     let mut columns_info = Vec::new();
 
-    if let Some(row) = rows.get(0) {
-        for column in row.columns() {
-            let mut map = Map::new();
-            map.insert("name".to_string(), Value::String(column.name().to_string()));
-            map.insert(
-                "type".to_string(),
-                Value::String(column.type_info().name().to_string()),
-            );
-            columns_info.push(Value::Object(map));
-        }
+    for column in columns {
+        let mut map = Map::new();
+        map.insert("name".to_string(), Value::String(column.name().to_string()));
+        map.insert(
+            "type".to_string(),
+            Value::String(column.type_info().name().to_string()),
+        );
+        map.insert(
+            "logicalType".to_string(),
+            Value::String(logical_type_mysql(column.type_info().name()).to_string()),
+        );
+        columns_info.push(Value::Object(map));
     }
 
     columns_info
 }
 
+fn column_types<'a>(columns: &'a [Value], name: &str) -> (&'a str, &'a str) {
+    columns
+        .iter()
+        .find(|column| column.get("name").and_then(Value::as_str) == Some(name))
+        .map(|column| {
+            (
+                column.get("type").and_then(Value::as_str).unwrap_or(""),
+                column
+                    .get("logicalType")
+                    .and_then(Value::as_str)
+                    .unwrap_or(""),
+            )
+        })
+        .unwrap_or(("", ""))
+}
+
+// Literal SQL mengikuti logicalType kolom agar nilai biner, bit, array dan JSON tidak hilang
+fn value_to_sql_text(value: &Value, type_name: &str, logical_type: &str) -> String {
+    match (value, logical_type) {
+        (Value::Null, _) => "NULL".to_string(),
+        // Nilai JSON selalu dikutip utuh agar boolean, angka dan string tetap menjadi dokumen JSON
+        (v, "json") => {
+            let literal = format!("'{}'", v.to_string().replace('\'', "''"));
+            if type_name.eq_ignore_ascii_case("JSONB") {
+                format!("{}::jsonb", literal)
+            } else {
+                literal
+            }
+        }
+        (Value::Bool(b), _) => b.to_string(),
+        (Value::Number(n), _) => n.to_string(),
+        (Value::String(s), "binary") if type_name.eq_ignore_ascii_case("BYTEA") => {
+            format!("'\\x{}'::bytea", s)
+        }
+        (Value::String(s), "binary" | "geometry") => format!("X'{}'", s),
+        (Value::String(s), "bit") => format!("B'{}'", s),
+        (Value::String(s), _) => format!("'{}'", s.replace('\'', "''")),
+        (Value::Array(items), "array") => {
+            let element_type = type_name.trim_end_matches("[]");
+            let element_logical_type = if element_type.eq_ignore_ascii_case("BYTEA") {
+                "binary"
+            } else {
+                ""
+            };
+            format!(
+                "ARRAY[{}]::{}",
+                items
+                    .iter()
+                    .map(|item| value_to_sql_text(item, element_type, element_logical_type))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                type_name
+            )
+        }
+        (other, _) => format!("'{}'", other.to_string().replace('\'', "''")),
+    }
+}
+
 pub fn rows_to_insert_query_string(
     table_name: &str,
     include_column_name_flag: i16,
     number_line_per_action: i16,
     rows: Vec<Value>,
     headers: Vec<String>,
+    columns: &[Value],
 ) -> String {
     let mut result = String::new();
     let mut batch = Vec::new();
@@ -398,16 +1167,9 @@ pub fn rows_to_insert_query_string(
 
         for header in &headers {
             let value = obj.get(header).unwrap_or(&Value::Null);
+            let (type_name, logical_type) = column_types(columns, header);
 
-            let value_str = match value {
-                Value::Null => "NULL".to_string(),
-                Value::Bool(b) => b.to_string(),
-                Value::Number(n) => n.to_string(),
-                Value::String(s) => format!("'{}'", s.replace('\'', "''")),
-                _ => "NULL".to_string(), // fallback untuk array, object, dsb
-            };
-
-            values.push(value_str);
+            values.push(value_to_sql_text(value, type_name, logical_type));
         }
 
         batch.push(values.join(", "));
@@ -452,6 +1214,7 @@ pub fn rows_to_update_query_string(
     first_amount_conditioned: i16,
     rows: Vec<Value>,
     headers: Vec<String>,
+    columns: &[Value],
 ) -> String {
     let mut result = String::new();
 
@@ -465,13 +1228,12 @@ pub fn rows_to_update_query_string(
         let mut where_clauses = Vec::new();
 
         for (i, name) in headers.iter().enumerate() {
-            let value = match obj.get(name).unwrap_or(&Value::Null) {
-                Value::Null => "NULL".to_string(),
-                Value::Bool(b) => b.to_string(),
-                Value::Number(n) => n.to_string(),
-                Value::String(s) => format!("'{}'", s.replace('\'', "''")),
-                _ => "NULL".to_string(), // default fallback
-            };
+            let (type_name, logical_type) = column_types(columns, name);
+            let value = value_to_sql_text(
+                obj.get(name).unwrap_or(&Value::Null),
+                type_name,
+                logical_type,
+            );
 
             if (i as i16) < first_amount_conditioned {
                 where_clauses.push(format!("{} = {}", name, value));
@@ -528,12 +1290,235 @@ pub fn rows_to_update_query_string(
     result
 }
 
+fn arrow_data_type(type_name: &str, rows: &[Value], name: &str) -> DataType {
+    match type_name.to_uppercase().as_str() {
+        "INT2" | "SMALLINT" | "TINYINT" | "TINYINT UNSIGNED" => DataType::Int16,
+        "INT4" | "INT" | "INTEGER" | "MEDIUMINT" | "SMALLINT UNSIGNED" | "MEDIUMINT UNSIGNED" => {
+            DataType::Int32
+        }
+        "INT8" | "BIGINT" | "OID" | "INT UNSIGNED" => DataType::Int64,
+        "BIGINT UNSIGNED" => DataType::UInt64,
+        "FLOAT4" | "FLOAT" => DataType::Float32,
+        "FLOAT8" | "DOUBLE" => DataType::Float64,
+        "NUMERIC" | "DECIMAL" => decimal_data_type(rows, name),
+        "BOOL" | "BOOLEAN" | "TINYINT(1)" => DataType::Boolean,
+        "DATE" => DataType::Date32,
+        "TIMESTAMP" | "DATETIME" => DataType::Timestamp(TimeUnit::Microsecond, None),
+        "TIMESTAMPTZ" => DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+        _ => DataType::Utf8,
+    }
+}
+
+// Skala diambil dari nilai terbesar, kolom jatuh ke teks jika tidak muat di Decimal128
+fn decimal_data_type(rows: &[Value], name: &str) -> DataType {
+    let mut scale = 0usize;
+    let mut digits = 0usize;
+
+    for row in rows {
+        if let Some(Value::String(value)) = row.get(name) {
+            let unsigned = value.trim_start_matches('-');
+            let (whole, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+            if !whole
+                .chars()
+                .chain(fraction.chars())
+                .all(|c| c.is_ascii_digit())
+            {
+                return DataType::Utf8;
+            }
+            scale = scale.max(fraction.len());
+            digits = digits.max(whole.trim_start_matches('0').len());
+        }
+    }
+
+    if digits + scale > 38 {
+        DataType::Utf8
+    } else {
+        DataType::Decimal128(38, scale as i8)
+    }
+}
+
+fn parse_decimal(value: &str, scale: usize) -> Option<i128> {
+    let (sign, unsigned) = match value.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, value),
+    };
+    let (whole, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    let digits = format!("{}{:0<width$}", whole, fraction, width = scale);
+
+    digits.parse::<i128>().ok().map(|v| v * sign)
+}
+
+fn parse_timestamp_micros(value: &str) -> Option<i64> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|v| v.timestamp_micros())
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f")
+                .map(|v| v.and_utc().timestamp_micros())
+        })
+        .ok()
+}
+
+fn value_to_i64(value: &Value) -> Option<i64> {
+    match value {
+        Value::Number(n) => n.as_i64(),
+        Value::String(s) => s.parse().ok(),
+        Value::Bool(b) => Some(*b as i64),
+        _ => None,
+    }
+}
+
+fn value_to_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn value_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+fn column_to_array(data_type: &DataType, rows: &[Value], name: &str) -> ArrayRef {
+    let values: Vec<&Value> = rows
+        .iter()
+        .map(|row| row.get(name).unwrap_or(&Value::Null))
+        .collect();
+
+    match data_type {
+        DataType::Int16 => Arc::new(Int16Array::from_iter(
+            values
+                .iter()
+                .map(|v| value_to_i64(v).and_then(|v| i16::try_from(v).ok())),
+        )),
+        DataType::Int32 => Arc::new(Int32Array::from_iter(
+            values
+                .iter()
+                .map(|v| value_to_i64(v).and_then(|v| i32::try_from(v).ok())),
+        )),
+        DataType::Int64 => Arc::new(Int64Array::from_iter(
+            values.iter().map(|v| value_to_i64(v)),
+        )),
+        DataType::UInt64 => Arc::new(UInt64Array::from_iter(values.iter().map(|v| match v {
+            Value::Number(n) => n.as_u64(),
+            Value::String(s) => s.parse().ok(),
+            _ => None,
+        }))),
+        DataType::Float32 => Arc::new(Float32Array::from_iter(
+            values.iter().map(|v| value_to_f64(v).map(|v| v as f32)),
+        )),
+        DataType::Float64 => Arc::new(Float64Array::from_iter(
+            values.iter().map(|v| value_to_f64(v)),
+        )),
+        DataType::Decimal128(precision, scale) => Arc::new(
+            Decimal128Array::from_iter(values.iter().map(|v| match v {
+                Value::String(s) => parse_decimal(s, *scale as usize),
+                Value::Number(n) => parse_decimal(&n.to_string(), *scale as usize),
+                _ => None,
+            }))
+            .with_precision_and_scale(*precision, *scale)
+            .unwrap_or_else(|_| Decimal128Array::new_null(values.len())),
+        ),
+        DataType::Boolean => Arc::new(BooleanArray::from_iter(values.iter().map(|v| match v {
+            Value::Bool(b) => Some(*b),
+            Value::Number(n) => n.as_i64().map(|v| v != 0),
+            Value::String(s) => s.parse().ok(),
+            _ => None,
+        }))),
+        DataType::Date32 => {
+            let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap_or_default();
+            Arc::new(Date32Array::from_iter(values.iter().map(|v| {
+                match v {
+                    Value::String(s) => NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                        .ok()
+                        .map(|d| (d - epoch).num_days() as i32),
+                    _ => None,
+                }
+            })))
+        }
+        DataType::Timestamp(_, timezone) => Arc::new(
+            TimestampMicrosecondArray::from_iter(values.iter().map(|v| match v {
+                Value::String(s) => parse_timestamp_micros(s),
+                _ => None,
+            }))
+            .with_timezone_opt(timezone.clone()),
+        ),
+        _ => Arc::new(StringArray::from_iter(
+            values.iter().map(|v| value_to_string(v)),
+        )),
+    }
+}
+
+pub fn rows_to_record_batch(rows: &[Value], columns: &[Value]) -> poem::Result<RecordBatch> {
+    let mut fields = Vec::new();
+    let mut arrays = Vec::new();
+
+    for column in columns {
+        let name = column.get("name").and_then(Value::as_str).unwrap_or("");
+        let type_name = column.get("type").and_then(Value::as_str).unwrap_or("");
+        let data_type = arrow_data_type(type_name, rows, name);
+
+        arrays.push(column_to_array(&data_type, rows, name));
+        fields.push(Field::new(name, data_type, true));
+    }
+
+    let options = RecordBatchOptions::new().with_row_count(Some(rows.len()));
+    RecordBatch::try_new_with_options(Arc::new(Schema::new(fields)), arrays, &options).map_err(
+        |e| {
+            eprintln!("Arrow error: {}", e);
+            common::error_message(StatusCode::INTERNAL_SERVER_ERROR, "arrow.writeFailed")
+        },
+    )
+}
+
+pub fn rows_to_parquet_bytes(rows: &[Value], columns: &[Value]) -> poem::Result<Vec<u8>> {
+    let batch = rows_to_record_batch(rows, columns)?;
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+
+    let write_failed = |e: parquet::errors::ParquetError| {
+        eprintln!("Parquet error: {}", e);
+        common::error_message(StatusCode::INTERNAL_SERVER_ERROR, "parquet.writeFailed")
+    };
+
+    let mut buffer = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut buffer, batch.schema(), Some(properties))
+        .map_err(write_failed)?;
+    writer.write(&batch).map_err(write_failed)?;
+    writer.close().map_err(write_failed)?;
+
+    Ok(buffer)
+}
+
+pub fn rows_to_arrow_bytes(rows: &[Value], columns: &[Value]) -> poem::Result<Vec<u8>> {
+    let batch = rows_to_record_batch(rows, columns)?;
+
+    let mut buffer = Vec::new();
+    FileWriter::try_new(&mut buffer, &batch.schema())
+        .and_then(|mut writer| {
+            writer.write(&batch)?;
+            writer.finish()
+        })
+        .map_err(|e| {
+            eprintln!("Arrow error: {}", e);
+            common::error_message(StatusCode::INTERNAL_SERVER_ERROR, "arrow.writeFailed")
+        })?;
+
+    Ok(buffer)
+}
+
 pub fn rows_to_xlsx_bytes(
     first_amount_combined: i16,
     rows: Vec<Value>,
     headers: Vec<String>,
 ) -> poem::Result<Vec<u8>> {
-    if rows.is_empty() {
+    // Hasil kosong tetap ditulis sebagai file berisi header saja
+    if rows.is_empty() && headers.is_empty() {
         return Ok(vec![]);
     }
 
@@ -599,7 +1584,7 @@ pub fn rows_to_xlsx_bytes(
                 Value::Bool(b) => b.to_string(),
                 Value::Number(n) => n.to_string(),
                 Value::String(s) => s.clone(),
-                _ => "".to_string(),
+                other => other.to_string(),
             };
             row_data.push(cell_value);
         }
@@ -640,119 +1625,66 @@ pub fn rows_to_xlsx_bytes(
     Ok(buffer)
 }
 
-pub fn rows_to_csv_string(
-    header_flag: i16,
-    delimiter: &str,
-    rows: Vec<Value>,
-    headers: Vec<String>,
-) -> String {
-    let mut result = String::new();
-
-    if rows.is_empty() {
-        return result;
-    }
-
-    // Tulis header jika diminta
-    if header_flag == 1 {
-        result.push_str(&headers.join(delimiter));
-        result.push('\n');
-    }
-
-    for row in rows {
-        let obj = match row.as_object() {
-            Some(obj) => obj,
-            None => continue, // skip jika bukan objek
+pub fn row_to_csv_line(delimiter: &str, obj: &Map<String, Value>, headers: &[String]) -> String {
+    let mut values = vec![];
+
+    for col in headers {
+        let raw_val = obj.get(col).unwrap_or(&Value::Null);
+        let value = match raw_val {
+            Value::Null => "".to_string(),
+            Value::Bool(b) => b.to_string(),
+            Value::Number(n) => n.to_string(),
+            Value::String(s) => s.to_string(),
+            other => other.to_string(), // array/object ditulis sebagai JSON
         };
 
-        let mut values = vec![];
-
-        for col in &headers {
-            let raw_val = obj.get(col).unwrap_or(&Value::Null);
-            let value = match raw_val {
-                Value::Null => "".to_string(),
-                Value::Bool(b) => b.to_string(),
-                Value::Number(n) => n.to_string(),
-                Value::String(s) => s.to_string(),
-                _ => "".to_string(), // fallback
+        // Bungkus string jika mengandung karakter khusus
+        let formatted_value =
+            if value.contains(delimiter) || value.contains('"') || value.contains('\n') {
+                format!("\"{}\"", value.replace('"', "\"\""))
+            } else {
+                value
             };
 
-            // Bungkus string jika mengandung karakter khusus
-            let formatted_value =
-                if value.contains(delimiter) || value.contains('"') || value.contains('\n') {
-                    format!("\"{}\"", value.replace('"', "\"\""))
-                } else {
-                    value
-                };
-
-            values.push(formatted_value);
-        }
-
-        result.push_str(&values.join(delimiter));
-        result.push('\n');
+        values.push(formatted_value);
     }
 
-    result
+    let mut line = values.join(delimiter);
+    line.push('\n');
+    line
 }
 
-pub fn rows_to_json_string(rows: Vec<Value>, headers: Vec<String>) -> String {
-    let mut standardized_rows = Vec::new();
-
-    for row in rows {
-        let obj = match row.as_object() {
-            Some(o) => o,
-            None => continue, // skip jika bukan objek
-        };
-
-        let mut new_obj = serde_json::Map::new();
-
-        for header in &headers {
-            let val = obj.get(header).cloned().unwrap_or(Value::Null);
-            new_obj.insert(header.clone(), val);
-        }
-
-        standardized_rows.push(Value::Object(new_obj));
-    }
-
-    serde_json::to_string_pretty(&Value::Array(standardized_rows))
-        .unwrap_or_else(|_| "[]".to_string())
-}
+pub fn row_to_xml_element(
+    table_name: &str,
+    map: &Map<String, Value>,
+    headers: &[String],
+) -> String {
+    let mut result = format!("  <{}>\n", table_name);
 
-pub fn rows_to_xml_string(table_name: &str, rows: Vec<Value>, headers: Vec<String>) -> String {
-    let mut result = String::new();
-    result.push_str("<List>\n");
+    for col_name in headers {
+        let value = map.get(col_name).unwrap_or(&Value::Null);
 
-    for row in rows {
-        result.push_str(&format!("  <{}>\n", table_name));
-
-        if let Value::Object(map) = row {
-            for col_name in &headers {
-                let value = map.get(col_name).unwrap_or(&Value::Null);
-
-                let value_str = match value {
-                    Value::Null => "".to_string(),
-                    Value::Bool(b) => b.to_string(),
-                    Value::Number(n) => n.to_string(),
-                    Value::String(s) => s.to_string(),
-                    _ => value.to_string(), // fallback untuk array/object
-                };
-
-                let escaped_value = value_str
-                    .replace('&', "&amp;")
-                    .replace('<', "&lt;")
-                    .replace('>', "&gt;")
-                    .replace('"', "&quot;")
-                    .replace('\'', "&apos;");
-
-                result.push_str(&format!(
-                    "    <{}>{}</{}>\n",
-                    col_name, escaped_value, col_name
-                ));
-            }
-        }
+        let value_str = match value {
+            Value::Null => "".to_string(),
+            Value::Bool(b) => b.to_string(),
+            Value::Number(n) => n.to_string(),
+            Value::String(s) => s.to_string(),
+            _ => value.to_string(), // fallback untuk array/object
+        };
 
-        result.push_str(&format!("  </{}>\n", table_name));
+        let escaped_value = value_str
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&apos;");
+
+        result.push_str(&format!(
+            "    <{}>{}</{}>\n",
+            col_name, escaped_value, col_name
+        ));
     }
 
-    result.push_str("</List>\n");
+    result.push_str(&format!("  </{}>\n", table_name));
     result
 }
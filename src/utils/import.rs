@@ -0,0 +1,266 @@
+use std::io::Cursor;
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime};
+use poem::http::StatusCode;
+
+use crate::utils::common;
+
+const TIMESTAMP_FORMATS: [&str; 4] = [
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+    "%d/%m/%Y %H:%M:%S",
+];
+
+const DATE_FORMATS: [&str; 3] = ["%Y-%m-%d", "%d/%m/%Y", "%Y/%m/%d"];
+
+pub type ImportRecord = Vec<Option<String>>;
+
+pub fn parse_import_file(
+    file_name: &str,
+    data: &[u8],
+    delimiter: char,
+    header_flag: i16,
+) -> poem::Result<(Vec<String>, Vec<ImportRecord>)> {
+    let mut records = if file_name.to_lowercase().ends_with(".xlsx") {
+        parse_xlsx(data)?
+    } else {
+        let text = std::str::from_utf8(data).map_err(|_| {
+            common::error_message(StatusCode::BAD_REQUEST, "import.invalidEncoding")
+        })?;
+        parse_csv(text.trim_start_matches('\u{feff}'), delimiter)
+    };
+
+    // Baris kosong di akhir file tidak ikut diimpor
+    while records
+        .last()
+        .is_some_and(|row| row.iter().all(|v| v.is_none()))
+    {
+        records.pop();
+    }
+
+    let width = records.iter().map(|row| row.len()).max().unwrap_or(0);
+    for row in records.iter_mut() {
+        row.resize(width, None);
+    }
+
+    let headers = if header_flag == 1 && !records.is_empty() {
+        records
+            .remove(0)
+            .into_iter()
+            .enumerate()
+            .map(|(i, v)| v.unwrap_or_else(|| format!("column{}", i + 1)))
+            .collect()
+    } else {
+        (1..=width).map(|i| format!("column{}", i)).collect()
+    };
+
+    Ok((headers, records))
+}
+
+fn parse_csv(text: &str, delimiter: char) -> Vec<ImportRecord> {
+    let mut records = Vec::new();
+    let mut row: ImportRecord = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+
+    let push_field = |row: &mut ImportRecord, field: &mut String, quoted: bool| {
+        if field.is_empty() && !quoted {
+            row.push(None);
+        } else {
+            row.push(Some(std::mem::take(field)));
+        }
+    };
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+        } else if c == delimiter {
+            push_field(&mut row, &mut field, quoted);
+            quoted = false;
+        } else {
+            match c {
+                '"' if field.is_empty() => {
+                    in_quotes = true;
+                    quoted = true;
+                }
+                '\r' if chars.peek() == Some(&'\n') => {}
+                '\n' | '\r' => {
+                    push_field(&mut row, &mut field, quoted);
+                    quoted = false;
+                    records.push(std::mem::take(&mut row));
+                }
+                _ => field.push(c),
+            }
+        }
+    }
+
+    if !field.is_empty() || quoted || !row.is_empty() {
+        push_field(&mut row, &mut field, quoted);
+        records.push(row);
+    }
+
+    records
+}
+
+fn parse_xlsx(data: &[u8]) -> poem::Result<Vec<ImportRecord>> {
+    let book = umya_spreadsheet::reader::xlsx::read_reader(Cursor::new(data), true)
+        .map_err(|_| common::error_message(StatusCode::BAD_REQUEST, "import.invalidFile"))?;
+    let sheet = book
+        .get_sheet(&0)
+        .ok_or_else(|| common::error_message(StatusCode::BAD_REQUEST, "import.invalidFile"))?;
+
+    let (max_col, max_row) = sheet.get_highest_column_and_row();
+    let mut records = Vec::new();
+
+    for row in 1..=max_row {
+        let record = (1..=max_col)
+            .map(|col| {
+                let value = sheet.get_value((col, row));
+                if value.is_empty() { None } else { Some(value) }
+            })
+            .collect();
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+pub fn infer_column_type<'a>(values: impl Iterator<Item = Option<&'a str>>) -> &'static str {
+    let mut inferred: Option<&'static str> = None;
+
+    for value in values.flatten() {
+        let value = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+
+        let current = if value.parse::<i64>().is_ok() {
+            "integer"
+        } else if value.parse::<f64>().is_ok() {
+            "decimal"
+        } else if parse_bool(value).is_some() {
+            "boolean"
+        } else if parse_date(value).is_some() {
+            "date"
+        } else if parse_timestamp(value).is_some() {
+            "timestamp"
+        } else {
+            return "text";
+        };
+
+        inferred = match (inferred, current) {
+            (None, current) => Some(current),
+            (Some(previous), current) if previous == current => Some(previous),
+            (Some("integer"), "decimal") | (Some("decimal"), "integer") => Some("decimal"),
+            (Some("date"), "timestamp") | (Some("timestamp"), "date") => Some("timestamp"),
+            _ => return "text",
+        };
+    }
+
+    inferred.unwrap_or("text")
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "t" | "yes" | "y" => Some(true),
+        "false" | "f" | "no" | "n" => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_excel_serial(value: &str) -> Option<NaiveDateTime> {
+    let serial = value.parse::<f64>().ok().filter(|v| *v > 0.0)?;
+    let epoch = NaiveDate::from_ymd_opt(1899, 12, 30)?.and_hms_opt(0, 0, 0)?;
+
+    epoch.checked_add_signed(Duration::milliseconds(
+        (serial * 86_400_000.0).round() as i64
+    ))
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+}
+
+fn parse_timestamp(value: &str) -> Option<NaiveDateTime> {
+    TIMESTAMP_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+}
+
+// Menyesuaikan nilai file dengan tipe kolom tujuan, hasilnya selalu teks yang siap di-bind
+pub fn coerce_import_value(raw: &str, data_type: &str, is_mysql: bool) -> Result<String, String> {
+    let value = raw.trim();
+    let data_type = data_type.to_lowercase();
+
+    if data_type == "boolean" || data_type.starts_with("tinyint(1)") {
+        let flag = parse_bool(value)
+            .or(match value {
+                "1" => Some(true),
+                "0" => Some(false),
+                _ => None,
+            })
+            .ok_or_else(|| format!("'{}' is not a valid boolean", value))?;
+
+        return Ok(match (flag, is_mysql) {
+            (true, true) => "1".to_string(),
+            (false, true) => "0".to_string(),
+            (flag, false) => flag.to_string(),
+        });
+    }
+
+    if data_type.starts_with("timestamp") || data_type.starts_with("datetime") {
+        if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+            return Ok(if is_mysql {
+                timestamp.naive_utc().to_string()
+            } else {
+                timestamp.to_rfc3339()
+            });
+        }
+
+        return parse_timestamp(value)
+            .or_else(|| parse_date(value).and_then(|d| d.and_hms_opt(0, 0, 0)))
+            .or_else(|| parse_excel_serial(value))
+            .map(|v| v.to_string())
+            .ok_or_else(|| format!("'{}' is not a valid timestamp", value));
+    }
+
+    if data_type == "date" {
+        return parse_date(value)
+            .or_else(|| parse_timestamp(value).map(|v| v.date()))
+            .or_else(|| parse_excel_serial(value).map(|v| v.date()))
+            .map(|v| v.to_string())
+            .ok_or_else(|| format!("'{}' is not a valid date", value));
+    }
+
+    if data_type.contains("int") && !data_type.contains("interval") && !data_type.contains("point")
+    {
+        return value
+            .parse::<i128>()
+            .map(|v| v.to_string())
+            .map_err(|_| format!("'{}' is not a valid integer", value));
+    }
+
+    if ["numeric", "decimal", "real", "double", "float"]
+        .iter()
+        .any(|t| data_type.starts_with(t))
+    {
+        return value
+            .parse::<f64>()
+            .map(|_| value.to_string())
+            .map_err(|_| format!("'{}' is not a valid number", value));
+    }
+
+    Ok(raw.to_string())
+}
@@ -0,0 +1,574 @@
+use crate::utils::database::{is_only_comment, split_manual_query};
+
+// Hanya kata yang reserved di kedua dialect agar nama objek tanpa quote tidak ikut berubah
+const KEYWORDS: &str = "add all alter and any as asc between by case cast check collate column constraint create cross default delete desc distinct drop else end except exists false fetch for foreign from full group having if ilike in index inner insert intersect interval into is join key lateral left like limit natural not null offset on or order outer over partition primary procedure recursive references rename replace returning right select set table then to trigger true union unique update using values when where window with";
+
+const TABLE_POSITION_WORDS: [&str; 5] = ["FROM", "JOIN", "INTO", "UPDATE", "TABLE"];
+const JOIN_MODIFIERS: [&str; 7] = [
+    "LEFT", "RIGHT", "INNER", "FULL", "CROSS", "NATURAL", "OUTER",
+];
+const OPERATOR_CHARS: &str = "+-*/<>=~!@#%^&|?:";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum KeywordCase {
+    Upper,
+    Lower,
+    Preserve,
+}
+
+pub struct SqlFormatOptions {
+    pub is_mysql: bool,
+    pub keyword_case: KeywordCase,
+    pub indent: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    Word,
+    Literal,
+    Operator,
+    Open,
+    Close,
+    Comma,
+    Dot,
+    Semicolon,
+    LineComment,
+    BlockComment,
+}
+
+struct Token {
+    kind: TokenKind,
+    text: String,
+    start: usize,
+    end: usize,
+    space_before: bool,
+    newline_before: bool,
+}
+
+fn char_at(chars: &[(usize, char)], i: usize) -> Option<char> {
+    chars.get(i).map(|(_, c)| *c)
+}
+
+fn scan_quoted(chars: &[(usize, char)], start: usize, quote: char, escape: bool) -> Option<usize> {
+    let mut i = start + 1;
+    loop {
+        match char_at(chars, i)? {
+            '\\' if escape => i += 2,
+            c if c == quote && char_at(chars, i + 1) == Some(quote) => i += 2,
+            c if c == quote => return Some(i + 1),
+            _ => i += 1,
+        }
+    }
+}
+
+// Mengembalikan None bila ada string atau komentar yang tidak ditutup
+fn tokenize(input: &str, is_mysql: bool) -> Option<Vec<Token>> {
+    let chars: Vec<(usize, char)> = input.char_indices().collect();
+    let byte_at = |i: usize| chars.get(i).map(|(b, _)| *b).unwrap_or(input.len());
+    let is_operator = |i: usize| {
+        char_at(&chars, i).is_some_and(|c| OPERATOR_CHARS.contains(c))
+            && !(is_mysql && char_at(&chars, i) == Some('#'))
+            && !matches!(
+                (char_at(&chars, i), char_at(&chars, i + 1)),
+                (Some('-'), Some('-')) | (Some('/'), Some('*'))
+            )
+    };
+
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut i = 0;
+    let (mut space, mut newline) = (false, false);
+
+    while let Some(c) = char_at(&chars, i) {
+        let mut start = i;
+        let next = char_at(&chars, i + 1);
+
+        let kind = if c.is_whitespace() {
+            space = true;
+            newline |= c == '\n';
+            i += 1;
+            continue;
+        } else if (c == '-' && next == Some('-')) || (is_mysql && c == '#') {
+            while char_at(&chars, i).is_some_and(|c| c != '\n') {
+                i += 1;
+            }
+            TokenKind::LineComment
+        } else if c == '/' && next == Some('*') {
+            // Block comment PostgreSQL boleh bersarang, MySQL tidak
+            let mut depth = 0;
+            loop {
+                match (char_at(&chars, i)?, char_at(&chars, i + 1)) {
+                    ('/', Some('*')) if depth == 0 || !is_mysql => {
+                        depth += 1;
+                        i += 2;
+                    }
+                    ('*', Some('/')) => {
+                        depth -= 1;
+                        i += 2;
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                    _ => i += 1,
+                }
+            }
+            TokenKind::BlockComment
+        } else if c == '\'' {
+            // Prefix seperti E'..', X'..' atau _utf8mb4'..' menempel pada string
+            let prefix = tokens
+                .last()
+                .filter(|last| last.kind == TokenKind::Word && !space && last.end == byte_at(i));
+            let escape = is_mysql || prefix.is_some_and(|last| last.text.eq_ignore_ascii_case("e"));
+            if prefix.is_some() {
+                let last = tokens.pop()?;
+                start = chars.iter().position(|(b, _)| *b == last.start)?;
+                space = last.space_before;
+                newline = last.newline_before;
+            }
+            i = scan_quoted(&chars, i, '\'', escape)?;
+            TokenKind::Literal
+        } else if c == '"' || (is_mysql && c == '`') {
+            i = scan_quoted(&chars, i, c, is_mysql && c == '"')?;
+            TokenKind::Literal
+        } else if c == '$' && !is_mysql && next.is_some_and(|n| n.is_ascii_digit()) {
+            i += 1;
+            while char_at(&chars, i).is_some_and(|c| c.is_ascii_digit()) {
+                i += 1;
+            }
+            TokenKind::Word
+        } else if c == '$' && !is_mysql {
+            let mut j = i + 1;
+            while char_at(&chars, j).is_some_and(|c| c.is_alphanumeric() || c == '_') {
+                j += 1;
+            }
+            if char_at(&chars, j) == Some('$') {
+                let tag = &input[byte_at(i)..byte_at(j + 1)];
+                let close = byte_at(j + 1) + input[byte_at(j + 1)..].find(tag)? + tag.len();
+                while byte_at(i) < close {
+                    i += 1;
+                }
+                TokenKind::Literal
+            } else {
+                i += 1;
+                TokenKind::Operator
+            }
+        } else if c.is_ascii_digit() || (c == '.' && next.is_some_and(|n| n.is_ascii_digit())) {
+            i += 1;
+            while let Some(c) = char_at(&chars, i) {
+                let is_exponent_sign = matches!(c, '+' | '-')
+                    && matches!(char_at(&chars, i - 1), Some('e' | 'E'))
+                    && char_at(&chars, i + 1).is_some_and(|n| n.is_ascii_digit());
+                if c.is_alphanumeric() || c == '_' || c == '.' || is_exponent_sign {
+                    i += 1;
+                } else {
+                    break;
+                }
+            }
+            TokenKind::Literal
+        } else if c.is_alphabetic() || c == '_' || (is_mysql && c == '@') {
+            i += 1;
+            while char_at(&chars, i).is_some_and(|c| {
+                c.is_alphanumeric() || c == '_' || c == '$' || (is_mysql && c == '@')
+            }) {
+                i += 1;
+            }
+            TokenKind::Word
+        } else if is_operator(i) {
+            i += 1;
+            while is_operator(i) {
+                i += 1;
+            }
+            TokenKind::Operator
+        } else {
+            i += 1;
+            match c {
+                '(' => TokenKind::Open,
+                ')' => TokenKind::Close,
+                ',' => TokenKind::Comma,
+                '.' => TokenKind::Dot,
+                ';' => TokenKind::Semicolon,
+                _ => TokenKind::Operator,
+            }
+        };
+
+        tokens.push(Token {
+            kind,
+            text: input[byte_at(start)..byte_at(i)].to_string(),
+            start: byte_at(start),
+            end: byte_at(i),
+            space_before: space,
+            newline_before: newline,
+        });
+        space = false;
+        newline = false;
+    }
+
+    Some(tokens)
+}
+
+fn is_comment(token: &Token) -> bool {
+    matches!(token.kind, TokenKind::LineComment | TokenKind::BlockComment)
+}
+
+fn next_word(tokens: &[Token], i: usize) -> Option<String> {
+    tokens[i + 1..]
+        .iter()
+        .find(|token| !is_comment(token))
+        .filter(|token| token.kind == TokenKind::Word)
+        .map(|token| token.text.to_uppercase())
+}
+
+fn is_keyword(word: &str) -> bool {
+    KEYWORDS.split(' ').any(|k| k.eq_ignore_ascii_case(word))
+}
+
+// Nama yang dikualifikasi atau berada di posisi nama tabel tidak diubah hurufnya
+fn cased(tokens: &[Token], i: usize, keyword_case: KeywordCase) -> String {
+    let token = &tokens[i];
+    if token.kind != TokenKind::Word
+        || keyword_case == KeywordCase::Preserve
+        || !is_keyword(&token.text)
+    {
+        return token.text.clone();
+    }
+
+    let prev = tokens[..i].iter().rev().find(|token| !is_comment(token));
+    let next = tokens[i + 1..].iter().find(|token| !is_comment(token));
+    let is_qualified = prev.is_some_and(|t| t.kind == TokenKind::Dot)
+        || next.is_some_and(|t| t.kind == TokenKind::Dot);
+    let is_table_position = prev.is_some_and(|t| {
+        t.kind == TokenKind::Word
+            && TABLE_POSITION_WORDS.contains(&t.text.to_uppercase().as_str())
+            && !matches!(
+                token.text.to_uppercase().as_str(),
+                "IF" | "SELECT" | "LATERAL"
+            )
+    });
+    if is_qualified || is_table_position {
+        return token.text.clone();
+    }
+
+    match keyword_case {
+        KeywordCase::Upper => token.text.to_uppercase(),
+        KeywordCase::Lower => token.text.to_lowercase(),
+        KeywordCase::Preserve => token.text.clone(),
+    }
+}
+
+// Statement selain query/DML hanya diubah huruf keyword-nya, susunan aslinya dipertahankan
+fn format_keyword_case(part: &str, tokens: &[Token], keyword_case: KeywordCase) -> String {
+    let mut out = String::new();
+    let mut last = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        out.push_str(&part[last..token.start]);
+        out.push_str(&cased(tokens, i, keyword_case));
+        last = token.end;
+    }
+    out.push_str(&part[last..]);
+    out
+}
+
+struct Writer<'a> {
+    out: String,
+    indent: &'a str,
+    level: usize,
+    is_line_start: bool,
+}
+
+impl Writer<'_> {
+    fn newline(&mut self, level: usize) {
+        self.level = level;
+        if self.is_line_start {
+            return;
+        }
+        self.out.truncate(self.out.trim_end_matches(' ').len());
+        self.out.push('\n');
+        self.is_line_start = true;
+    }
+
+    fn push(&mut self, text: &str, space: bool) {
+        if self.is_line_start {
+            self.out.push_str(&self.indent.repeat(self.level));
+            self.is_line_start = false;
+        } else if space {
+            self.out.push(' ');
+        }
+        self.out.push_str(text);
+    }
+}
+
+#[derive(Default)]
+struct Frame {
+    base: usize,
+    close_level: usize,
+    is_query: bool,
+    clause: Option<String>,
+    is_join: bool,
+    is_between: bool,
+    case_depth: usize,
+}
+
+impl Frame {
+    fn query(base: usize, close_level: usize) -> Self {
+        Frame {
+            base,
+            close_level,
+            is_query: true,
+            ..Default::default()
+        }
+    }
+}
+
+// Clause utama di baris sendiri dan isinya diindentasi, subquery membuka level indentasi baru
+fn format_layout(tokens: &[Token], options: &SqlFormatOptions) -> String {
+    let mut writer = Writer {
+        out: String::new(),
+        indent: &options.indent,
+        level: 0,
+        is_line_start: true,
+    };
+    let mut frames = vec![Frame::query(0, 0)];
+    let mut pending_break: Option<usize> = None;
+    let mut last_word = String::new();
+    let mut last_kind: Option<TokenKind> = None;
+
+    for (i, token) in tokens.iter().enumerate() {
+        let text = cased(tokens, i, options.keyword_case);
+        let upper = token.text.to_uppercase();
+        let next = next_word(tokens, i);
+        let space = match token.kind {
+            TokenKind::Close | TokenKind::Comma | TokenKind::Semicolon => false,
+            _ => token.space_before || last_kind == Some(TokenKind::Comma),
+        } && last_kind != Some(TokenKind::Open);
+
+        if let Some(level) = pending_break {
+            let is_inline = match token.kind {
+                TokenKind::Word => matches!(upper.as_str(), "DISTINCT" | "ALL" | "BY"),
+                TokenKind::LineComment | TokenKind::BlockComment => !token.newline_before,
+                _ => false,
+            };
+            if !is_inline {
+                writer.newline(level);
+                pending_break = None;
+            }
+        }
+
+        let frame_count = frames.len();
+        let frame = &mut frames[frame_count - 1];
+        let content = match frame.clause.as_deref() {
+            Some("WITH" | "INSERT" | "UPDATE" | "DELETE" | "LIMIT" | "OFFSET" | "FETCH") | None => {
+                frame.base
+            }
+            Some(_) => frame.base + 1,
+        };
+
+        match token.kind {
+            TokenKind::LineComment | TokenKind::BlockComment => {
+                if token.newline_before {
+                    writer.newline(content);
+                }
+                writer.push(token.text.trim_end(), true);
+                if token.kind == TokenKind::LineComment {
+                    writer.newline(writer.level);
+                }
+                continue;
+            }
+            TokenKind::Word if frame.is_query && frame.case_depth == 0 => {
+                let is_join_start = (JOIN_MODIFIERS.contains(&upper.as_str())
+                    && upper != "OUTER"
+                    && next.as_deref().is_some_and(|n| {
+                        n == "JOIN" || n == "STRAIGHT_JOIN" || JOIN_MODIFIERS.contains(&n)
+                    })
+                    || matches!(upper.as_str(), "JOIN" | "STRAIGHT_JOIN"))
+                    && !JOIN_MODIFIERS.contains(&last_word.as_str());
+                let is_block = match upper.as_str() {
+                    "SELECT" | "WHERE" | "HAVING" | "SET" | "VALUES" | "RETURNING" => true,
+                    "FROM" => !matches!(last_word.as_str(), "DELETE" | "DISTINCT"),
+                    "GROUP" | "ORDER" => next.as_deref() == Some("BY"),
+                    _ => false,
+                };
+                let is_inline = match upper.as_str() {
+                    "INSERT" | "LIMIT" | "OFFSET" | "FETCH" => true,
+                    "UPDATE" | "DELETE" => {
+                        !matches!(last_word.as_str(), "DO" | "FOR" | "ON" | "KEY")
+                    }
+                    "WITH" => frame.clause.is_none(),
+                    _ => false,
+                };
+
+                if is_block {
+                    writer.newline(frame.base);
+                    writer.push(&text, space);
+                    pending_break = Some(frame.base + 1);
+                    frame.clause = Some(upper.clone());
+                    frame.is_join = false;
+                } else if is_inline {
+                    writer.newline(frame.base);
+                    writer.push(&text, space);
+                    frame.clause = Some(upper.clone());
+                    frame.is_join = false;
+                } else if matches!(upper.as_str(), "UNION" | "EXCEPT" | "INTERSECT") {
+                    writer.newline(frame.base);
+                    writer.push(&text, space);
+                    pending_break = Some(frame.base);
+                    frame.clause = None;
+                } else if is_join_start {
+                    writer.newline(frame.base + 1);
+                    writer.push(&text, space);
+                    frame.is_join = true;
+                } else if matches!(upper.as_str(), "AND" | "OR") {
+                    if upper == "AND" && frame.is_between {
+                        frame.is_between = false;
+                    } else if matches!(frame.clause.as_deref(), Some("WHERE" | "HAVING")) {
+                        writer.newline(frame.base + 1);
+                    } else if frame.is_join {
+                        writer.newline(frame.base + 2);
+                    }
+                    writer.push(&text, space);
+                } else {
+                    match upper.as_str() {
+                        "BETWEEN" => frame.is_between = true,
+                        "CASE" => frame.case_depth += 1,
+                        _ => {}
+                    }
+                    writer.push(&text, space);
+                }
+            }
+            TokenKind::Word => {
+                match upper.as_str() {
+                    "CASE" => frame.case_depth += 1,
+                    "END" if frame.case_depth > 0 => frame.case_depth -= 1,
+                    _ => {}
+                }
+                writer.push(&text, space);
+            }
+            TokenKind::Open => {
+                writer.push(&text, space);
+                if matches!(next.as_deref(), Some("SELECT" | "WITH" | "VALUES")) {
+                    let close_level = writer.level;
+                    frames.push(Frame::query(close_level + 1, close_level));
+                    writer.newline(close_level + 1);
+                } else {
+                    frames.push(Frame {
+                        base: writer.level,
+                        close_level: writer.level,
+                        ..Default::default()
+                    });
+                }
+            }
+            TokenKind::Close => {
+                if frames.len() > 1
+                    && let Some(frame) = frames.pop()
+                    && frame.is_query
+                {
+                    writer.newline(frame.close_level);
+                }
+                writer.push(&text, space);
+            }
+            TokenKind::Comma => {
+                writer.push(&text, space);
+                if frame.is_query && frame.case_depth == 0 {
+                    match frame.clause.as_deref() {
+                        Some(
+                            "SELECT" | "SET" | "VALUES" | "RETURNING" | "GROUP" | "ORDER" | "FROM",
+                        ) => writer.newline(frame.base + 1),
+                        Some("WITH") => writer.newline(frame.base),
+                        _ => {}
+                    }
+                }
+            }
+            TokenKind::Semicolon => {
+                writer.push(&text, space);
+                frames = vec![Frame::query(0, 0)];
+                pending_break = None;
+                writer.newline(0);
+            }
+            _ => writer.push(&text, space),
+        }
+
+        if token.kind == TokenKind::Word {
+            last_word = upper;
+        }
+        last_kind = Some(token.kind);
+    }
+
+    writer.out.trim_end().to_string()
+}
+
+fn same_tokens(original: &[Token], formatted: &[Token]) -> bool {
+    original.len() == formatted.len()
+        && original.iter().zip(formatted).all(|(a, b)| {
+            a.kind == b.kind
+                && match a.kind {
+                    TokenKind::Word => a.text.eq_ignore_ascii_case(&b.text),
+                    TokenKind::LineComment | TokenKind::BlockComment => {
+                        a.text.trim_end() == b.text.trim_end()
+                    }
+                    _ => a.text == b.text,
+                }
+        })
+}
+
+// Hasil format harus menghasilkan token yang sama, bila tidak statement dikembalikan apa adanya
+fn format_statement(part: &str, options: &SqlFormatOptions) -> String {
+    let Some(tokens) = tokenize(part, options.is_mysql) else {
+        return part.to_string();
+    };
+
+    let first = tokens.iter().find(|token| !is_comment(token));
+    let is_query = first.is_some_and(|token| {
+        token.kind == TokenKind::Open
+            || (token.kind == TokenKind::Word
+                && matches!(
+                    token.text.to_uppercase().as_str(),
+                    "SELECT" | "WITH" | "INSERT" | "UPDATE" | "DELETE" | "VALUES"
+                ))
+    });
+    let has_block = tokens
+        .iter()
+        .any(|token| token.kind == TokenKind::Word && token.text.eq_ignore_ascii_case("begin"));
+
+    let formatted = if is_query && !has_block {
+        format_layout(&tokens, options)
+    } else {
+        format_keyword_case(part, &tokens, options.keyword_case)
+    };
+
+    match tokenize(&formatted, options.is_mysql) {
+        Some(result) if same_tokens(&tokens, &result) => formatted.trim().to_string(),
+        _ => part.to_string(),
+    }
+}
+
+// Format per statement mengikuti split_manual_query agar pemisahan saat dijalankan tetap sama
+pub fn format_sql(input: &str, options: &SqlFormatOptions) -> (String, bool) {
+    let parts = split_manual_query(input);
+    let formatted: Vec<String> = parts
+        .iter()
+        .map(|part| format_statement(part, options))
+        .collect();
+
+    let mut script = String::new();
+    for (i, part) in formatted.iter().enumerate() {
+        if i > 0 {
+            script.push_str("\n\n");
+        }
+        script.push_str(part);
+
+        let ends_with_line_comment = tokenize(part, options.is_mysql)
+            .and_then(|tokens| tokens.last().map(|t| t.kind == TokenKind::LineComment))
+            .unwrap_or(false);
+        if i + 1 == formatted.len() && is_only_comment(part) {
+            continue;
+        } else if ends_with_line_comment {
+            script.push_str("\n;");
+        } else {
+            script.push(';');
+        }
+    }
+
+    if split_manual_query(&script) == formatted {
+        (script, true)
+    } else {
+        (input.to_string(), false)
+    }
+}